@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Per-user history feeds page through a user's comments and upvotes newest first.
+CREATE INDEX comments_user_id_created_at_idx ON comments (user_id, created_at DESC, id DESC);
+CREATE INDEX votes_user_id_idx ON votes (user_id);
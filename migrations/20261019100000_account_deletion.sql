@@ -0,0 +1,21 @@
+-- Add migration script here
+-- Accounts are anonymized rather than deleted so discussion threads survive.
+ALTER TABLE users
+ADD COLUMN deletion_requested_at TIMESTAMPTZ,
+ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Tombstoned accounts all share the "[deleted]" username, so uniqueness only applies to live accounts.
+DROP INDEX users_email_key;
+DROP INDEX users_username_key;
+CREATE UNIQUE INDEX users_email_key ON users (email) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX users_username_key ON users (username) WHERE deleted_at IS NULL;
+CREATE INDEX users_deletion_requested_at_idx ON users (deletion_requested_at) WHERE deletion_requested_at IS NOT NULL AND deleted_at IS NULL;
+
+-- A stray hard delete must never take a user's posts and comments with it.
+ALTER TABLE posts
+DROP CONSTRAINT posts_user_id_fkey,
+ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT;
+
+ALTER TABLE comments
+DROP CONSTRAINT comments_user_id_fkey,
+ADD CONSTRAINT comments_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT;
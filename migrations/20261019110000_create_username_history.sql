@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE username_history (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    old_username TEXT NOT NULL,
+    new_username TEXT NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- nobody else may claim old_username until this passes
+    reserved_until TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX username_history_old_username_idx ON username_history (old_username, changed_at DESC);
+CREATE INDEX username_history_user_id_idx ON username_history (user_id, changed_at DESC);
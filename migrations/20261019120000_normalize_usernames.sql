@@ -0,0 +1,42 @@
+-- Add migration script here
+-- Lookups go through username_normalized (NFKC + case folding); uniqueness through the
+-- confusable skeleton computed by the application. Existing rows are backfilled with the
+-- normalized form, the closest approximation SQL can make; rows written by the app get the real skeleton.
+ALTER TABLE users
+ADD COLUMN username_normalized TEXT,
+ADD COLUMN username_skeleton TEXT;
+
+UPDATE users
+SET username_normalized = lower(normalize(username, NFKC)),
+    username_skeleton = lower(normalize(username, NFKC));
+
+-- Case variants registered before this change ("Alice" and "alice") keep their accounts;
+-- every one but the oldest gets a skeleton the app can never produce so the index below holds.
+UPDATE users u
+SET username_skeleton = u.username_skeleton || '#' || u.id::text
+WHERE u.deleted_at IS NULL
+  AND EXISTS (
+      SELECT 1 FROM users o
+      WHERE o.username_skeleton = u.username_skeleton
+        AND o.deleted_at IS NULL
+        AND (o.created_at, o.id) < (u.created_at, u.id)
+  );
+
+ALTER TABLE users
+ALTER COLUMN username_normalized SET NOT NULL,
+ALTER COLUMN username_skeleton SET NOT NULL;
+
+CREATE UNIQUE INDEX users_username_skeleton_key ON users (username_skeleton) WHERE deleted_at IS NULL;
+CREATE INDEX users_username_normalized_idx ON users (username_normalized) WHERE deleted_at IS NULL;
+
+-- Reservations on released names are checked against the skeleton too.
+ALTER TABLE username_history
+ADD COLUMN old_username_skeleton TEXT;
+
+UPDATE username_history SET old_username_skeleton = lower(normalize(old_username, NFKC));
+
+ALTER TABLE username_history
+ALTER COLUMN old_username_skeleton SET NOT NULL;
+
+DROP INDEX username_history_old_username_idx;
+CREATE INDEX username_history_old_username_skeleton_idx ON username_history (old_username_skeleton, changed_at DESC);
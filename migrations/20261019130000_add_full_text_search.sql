@@ -0,0 +1,23 @@
+-- Add migration script here
+-- Weighted so title hits outrank description hits, which outrank body hits.
+ALTER TABLE posts
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(short_description, '')), 'B') ||
+    setweight(to_tsvector('english', coalesce(body, '')), 'C')
+) STORED;
+
+CREATE INDEX posts_search_vector_idx ON posts USING GIN (search_vector);
+
+ALTER TABLE comments
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (to_tsvector('english', body)) STORED;
+
+CREATE INDEX comments_search_vector_idx ON comments USING GIN (search_vector);
+
+-- Host of the link without a leading "www.", for filtering by site.
+ALTER TABLE posts
+ADD COLUMN domain TEXT GENERATED ALWAYS AS (
+    lower(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://(?:www\.)?([^/:?#]+)'))
+) STORED;
+
+CREATE INDEX posts_domain_idx ON posts (domain);
@@ -0,0 +1,32 @@
+-- Add migration script here
+-- Site-wide role; moderators manage shared data such as tags.
+ALTER TABLE users
+ADD COLUMN role TEXT NOT NULL DEFAULT 'user' CHECK (role IN ('user', 'moderator', 'admin'));
+
+CREATE TABLE tags (
+    id UUID PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- text_pattern_ops so autocomplete's `LIKE 'prefix%'` can use the index
+CREATE INDEX tags_slug_prefix_idx ON tags (slug text_pattern_ops);
+
+-- Alternative spellings that resolve to a canonical tag; also left behind by merges.
+CREATE TABLE tag_aliases (
+    alias_slug TEXT PRIMARY KEY,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX tag_aliases_prefix_idx ON tag_aliases (alias_slug text_pattern_ops);
+CREATE INDEX tag_aliases_tag_id_idx ON tag_aliases (tag_id);
+
+CREATE TABLE post_tags (
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+CREATE INDEX post_tags_tag_id_idx ON post_tags (tag_id, post_id);
@@ -0,0 +1,32 @@
+-- Add migration script here
+CREATE TABLE communities (
+    id UUID PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    rules TEXT[] NOT NULL DEFAULT '{}',
+    -- public: anyone reads and posts; restricted: anyone reads, members post; private: members only
+    visibility TEXT NOT NULL DEFAULT 'public' CHECK (visibility IN ('public', 'restricted', 'private')),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE community_members (
+    community_id UUID NOT NULL REFERENCES communities(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL DEFAULT 'member' CHECK (role IN ('member', 'moderator', 'owner')),
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (community_id, user_id)
+);
+
+CREATE INDEX community_members_user_id_idx ON community_members (user_id);
+
+-- Every existing post moves into a default community so community_id can be required.
+INSERT INTO communities (id, slug, name, description)
+VALUES ('00000000-0000-0000-0000-000000000001', 'general', 'General', 'Everything that does not fit anywhere else.');
+
+ALTER TABLE posts ADD COLUMN community_id UUID REFERENCES communities(id) ON DELETE RESTRICT;
+UPDATE posts SET community_id = '00000000-0000-0000-0000-000000000001';
+ALTER TABLE posts ALTER COLUMN community_id SET NOT NULL;
+
+CREATE INDEX posts_community_created_at_idx ON posts (community_id, created_at DESC, id DESC);
@@ -0,0 +1,26 @@
+-- Add migration script here
+CREATE TABLE follows (
+    follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+
+CREATE INDEX follows_followee_id_idx ON follows (followee_id);
+
+CREATE TABLE tag_follows (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, tag_id)
+);
+
+-- Hot ranking as a point in time: every 10x in score moves a post 12.5 hours (45000s) later,
+-- so newer posts need fewer votes to rank higher. Kept in step with score by the vote path.
+ALTER TABLE posts ADD COLUMN hot_at TIMESTAMPTZ;
+UPDATE posts SET hot_at = created_at + make_interval(secs => sign(score::float8) * log(greatest(abs(score), 1)::float8) * 45000);
+ALTER TABLE posts ALTER COLUMN hot_at SET NOT NULL;
+ALTER TABLE posts ALTER COLUMN hot_at SET DEFAULT NOW();
+
+CREATE INDEX posts_hot_at_idx ON posts (hot_at DESC, id DESC);
@@ -0,0 +1,20 @@
+-- Add migration script here
+CREATE TABLE saved_collections (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+-- A post is saved at most once per user, optionally filed in one of their collections.
+CREATE TABLE saved_posts (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    collection_id UUID REFERENCES saved_collections(id) ON DELETE SET NULL,
+    saved_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, post_id)
+);
+
+CREATE INDEX saved_posts_user_saved_at_idx ON saved_posts (user_id, saved_at DESC, post_id DESC);
+CREATE INDEX saved_posts_collection_idx ON saved_posts (collection_id, saved_at DESC, post_id DESC);
@@ -0,0 +1,17 @@
+-- Add migration script here
+CREATE TABLE muted_users (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muted_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, muted_user_id)
+);
+
+-- Normalized like posts.domain; also hides the domain's subdomains.
+CREATE TABLE muted_domains (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    domain TEXT NOT NULL,
+    muted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, domain)
+);
+
+CREATE INDEX hidden_posts_user_hidden_at_idx ON hidden_posts (user_id, hidden_at DESC, post_id DESC);
@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Normalized link used to detect duplicate submissions; see `url_canon::canonicalize`.
+ALTER TABLE posts ADD COLUMN canonical_url TEXT;
+
+-- Existing links get an approximation (https, lowercase host without www., no port or fragment);
+-- tracking parameters and query order are only normalized for new submissions.
+UPDATE posts
+SET canonical_url = 'https://' || domain
+    || regexp_replace(coalesce(substring(url from '^[a-zA-Z][a-zA-Z0-9+.-]*://[^/?#]+([^#]*)'), ''), '^(?!/)', '/')
+WHERE url IS NOT NULL AND domain IS NOT NULL;
+
+CREATE INDEX posts_canonical_url_idx ON posts (community_id, canonical_url, created_at DESC) WHERE canonical_url IS NOT NULL;
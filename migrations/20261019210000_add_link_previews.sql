@@ -0,0 +1,9 @@
+-- Add migration script here
+-- Filled in the background from the linked page's OpenGraph/Twitter/HTML meta tags.
+-- `preview_fetched_at` is set after every attempt, successful or not.
+ALTER TABLE posts
+ADD COLUMN preview_title TEXT,
+ADD COLUMN preview_site_name TEXT,
+ADD COLUMN preview_description TEXT,
+ADD COLUMN preview_image_url TEXT,
+ADD COLUMN preview_fetched_at TIMESTAMPTZ;
@@ -0,0 +1,31 @@
+-- Add migration script here
+-- Removal is soft so reported content can still be reviewed, and a removed post keeps its comments.
+ALTER TABLE posts
+    ADD COLUMN removed_at TIMESTAMPTZ,
+    ADD COLUMN removed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN locked_at TIMESTAMPTZ;
+
+ALTER TABLE comments
+    ADD COLUMN removed_at TIMESTAMPTZ,
+    ADD COLUMN removed_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- target_id has no foreign key: reports outlive what they point at.
+CREATE TABLE reports (
+    id UUID PRIMARY KEY,
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_type TEXT NOT NULL CHECK (target_type IN ('post', 'comment')),
+    target_id UUID NOT NULL,
+    community_id UUID NOT NULL REFERENCES communities(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    details TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolution TEXT,
+    resolution_note TEXT
+);
+
+-- one open report per reporter and item; reporting again updates it
+CREATE UNIQUE INDEX reports_open_reporter_idx ON reports (reporter_id, target_type, target_id) WHERE resolved_at IS NULL;
+CREATE INDEX reports_open_target_idx ON reports (target_type, target_id) WHERE resolved_at IS NULL;
+CREATE INDEX reports_open_community_idx ON reports (community_id, created_at DESC) WHERE resolved_at IS NULL;
@@ -0,0 +1,10 @@
+-- Add migration script here
+-- Posts and comments are no longer deleted outright, which used to cascade away their votes and replies.
+-- deleted_at is set when the author deletes; removed_at/removed_by/removal_reason when a moderator removes.
+ALTER TABLE posts
+    ADD COLUMN deleted_at TIMESTAMPTZ,
+    ADD COLUMN removal_reason TEXT;
+
+ALTER TABLE comments
+    ADD COLUMN deleted_at TIMESTAMPTZ,
+    ADD COLUMN removal_reason TEXT;
@@ -0,0 +1,32 @@
+-- Add migration script here
+-- Bans lock an account out, suspensions make it read-only, shadowbans hide its content from everyone else.
+-- A sanction without expires_at lasts until revoked.
+CREATE TABLE user_sanctions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('ban', 'suspension', 'shadowban')),
+    reason TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    revoked_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX user_sanctions_user_idx ON user_sanctions (user_id, created_at DESC);
+CREATE INDEX user_sanctions_open_idx ON user_sanctions (kind, user_id) WHERE revoked_at IS NULL;
+
+CREATE VIEW active_sanctions AS
+    SELECT * FROM user_sanctions
+    WHERE revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW());
+
+-- Where accounts sign up and log in from, to spot new accounts of banned users.
+CREATE TABLE user_ips (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ip TEXT NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, ip)
+);
+
+CREATE INDEX user_ips_ip_idx ON user_ips (ip);
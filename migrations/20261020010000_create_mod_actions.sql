@@ -0,0 +1,29 @@
+-- Add migration script here
+-- Append-only record of what moderators did; target_id has no foreign key so entries outlive their targets.
+CREATE TABLE mod_actions (
+    id UUID PRIMARY KEY,
+    actor_id UUID NOT NULL REFERENCES users(id),
+    action TEXT NOT NULL,
+    target_type TEXT NOT NULL CHECK (target_type IN ('post', 'comment', 'user')),
+    target_id UUID NOT NULL,
+    community_id UUID REFERENCES communities(id),
+    reason TEXT,
+    before JSONB,
+    after JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX mod_actions_created_idx ON mod_actions (created_at DESC, id DESC);
+CREATE INDEX mod_actions_community_idx ON mod_actions (community_id, created_at DESC);
+CREATE INDEX mod_actions_actor_idx ON mod_actions (actor_id, created_at DESC);
+CREATE INDEX mod_actions_target_idx ON mod_actions (target_type, target_id);
+
+CREATE FUNCTION mod_actions_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'mod_actions is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER mod_actions_no_update_or_delete
+    BEFORE UPDATE OR DELETE ON mod_actions
+    FOR EACH ROW EXECUTE FUNCTION mod_actions_append_only();
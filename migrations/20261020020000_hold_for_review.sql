@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Posts and comments the content filter holds stay out of sight until a moderator approves them.
+ALTER TABLE posts
+    ADD COLUMN held_at TIMESTAMPTZ,
+    ADD COLUMN hold_reason TEXT;
+
+ALTER TABLE comments
+    ADD COLUMN held_at TIMESTAMPTZ,
+    ADD COLUMN hold_reason TEXT;
+
+CREATE INDEX posts_held_idx ON posts (held_at DESC, id DESC) WHERE held_at IS NOT NULL;
+CREATE INDEX comments_held_idx ON comments (held_at DESC, id DESC) WHERE held_at IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Add migration script here
+-- How moderators have treated content linking to each domain, fed back into the spam score.
+-- Domains are normalized like posts.domain.
+CREATE TABLE domain_reputation (
+    domain TEXT PRIMARY KEY,
+    approved INT NOT NULL DEFAULT 0,
+    removed INT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The spam check reads an author's latest posts; comments already have the same index.
+CREATE INDEX posts_user_id_created_at_idx ON posts (user_id, created_at DESC, id DESC);
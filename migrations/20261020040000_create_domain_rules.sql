@@ -0,0 +1,16 @@
+-- Add migration script here
+-- Admin-managed rules for link submissions. A rule covers the domain and its subdomains;
+-- domains are normalized like posts.domain.
+CREATE TABLE domain_rules (
+    domain TEXT PRIMARY KEY,
+    action TEXT NOT NULL CHECK (action IN ('block', 'allow', 'review', 'rate_limit')),
+    max_posts INT CHECK (max_posts > 0),
+    window_hours INT CHECK (window_hours > 0),
+    reason TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((action = 'rate_limit') = (max_posts IS NOT NULL AND window_hours IS NOT NULL))
+);
+
+-- Rate limits count a user's recent links to a domain.
+CREATE INDEX posts_user_domain_created_at_idx ON posts (user_id, domain, created_at DESC) WHERE domain IS NOT NULL;
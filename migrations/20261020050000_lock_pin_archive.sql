@@ -0,0 +1,11 @@
+-- Add migration script here
+-- Pinned posts sit above the site-wide feeds, lowest pin_position first.
+-- Archived posts stay readable but take no new comments or votes, like locked ones.
+ALTER TABLE posts
+    ADD COLUMN pinned_at TIMESTAMPTZ,
+    ADD COLUMN pin_position INTEGER,
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD CONSTRAINT posts_pin_position_check CHECK ((pinned_at IS NULL) = (pin_position IS NULL));
+
+CREATE INDEX posts_pinned_idx ON posts (pin_position, pinned_at) WHERE pinned_at IS NOT NULL;
+CREATE INDEX posts_unarchived_created_at_idx ON posts (created_at) WHERE archived_at IS NULL;
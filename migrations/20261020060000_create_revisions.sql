@@ -0,0 +1,28 @@
+-- Add migration script here
+-- Each row keeps a version of a post or comment as it was before an edit replaced it;
+-- editor_id is whoever made that edit, the author or a moderator.
+ALTER TABLE posts ADD COLUMN edited_at TIMESTAMPTZ;
+ALTER TABLE comments ADD COLUMN edited_at TIMESTAMPTZ;
+
+CREATE TABLE post_revisions (
+    id UUID PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    editor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    title TEXT NOT NULL,
+    short_description TEXT NOT NULL,
+    url TEXT,
+    body TEXT,
+    replaced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX post_revisions_post_idx ON post_revisions (post_id, replaced_at);
+
+CREATE TABLE comment_revisions (
+    id UUID PRIMARY KEY,
+    comment_id UUID NOT NULL REFERENCES comments(id) ON DELETE CASCADE,
+    editor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    replaced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX comment_revisions_comment_idx ON comment_revisions (comment_id, replaced_at);
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- Bumped on every edit; clients send it back in If-Match so concurrent edits can't overwrite each other.
+ALTER TABLE posts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
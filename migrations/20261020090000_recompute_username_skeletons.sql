@@ -0,0 +1,33 @@
+-- Add migration script here
+-- 20261019120000 backfilled skeletons with lower(normalize(..., NFKC)), which is not the UTS #39
+-- skeleton, so lookalike names registered before it were never caught. The app recomputes every
+-- skeleton flagged here when it next starts, before serving requests; until then each account
+-- holds a placeholder no skeleton can collide with.
+ALTER TABLE users
+ADD COLUMN username_skeleton_pending BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE users SET username_skeleton = '#' || id::text, username_skeleton_pending = TRUE;
+
+ALTER TABLE username_history
+ADD COLUMN old_username_skeleton_pending BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE username_history SET old_username_skeleton_pending = TRUE;
+
+-- Logins match username_normalized, so it has to be unique as well. Case variants registered
+-- before usernames were normalized ("Alice" and "alice") keep their accounts, but all except the
+-- oldest are renamed to their normalized name plus a suffix from their id; they can still log in
+-- with their email.
+UPDATE users u
+SET username = left(u.username_normalized, 31) || '_' || left(u.id::text, 8),
+    username_normalized = left(u.username_normalized, 31) || '_' || left(u.id::text, 8)
+WHERE u.deleted_at IS NULL
+  AND EXISTS (
+      SELECT 1 FROM users o
+      WHERE o.username_normalized = u.username_normalized
+        AND o.deleted_at IS NULL
+        AND (o.created_at, o.id) < (u.created_at, u.id)
+  );
+
+DROP INDEX users_username_normalized_idx;
+CREATE UNIQUE INDEX users_username_normalized_key ON users (username_normalized) WHERE deleted_at IS NULL;
+CREATE INDEX users_username_skeleton_pending_idx ON users (created_at, id) WHERE username_skeleton_pending;
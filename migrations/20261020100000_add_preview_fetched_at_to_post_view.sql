@@ -0,0 +1,15 @@
+-- Add migration script here
+-- The preview worker needs to know whether a fetch was already attempted, failed ones included.
+CREATE OR REPLACE VIEW post_view AS
+    SELECT p.id, p.user_id, p.community_id, p.title, p.url, p.canonical_url, p.domain, p.body, p.short_description,
+           p.score, p.created_at, p.hot_at,
+           p.preview_title, p.preview_site_name, p.preview_description, p.preview_image_url,
+           p.locked_at, p.held_at, p.pinned_at, p.pin_position, p.archived_at, p.edited_at, p.version,
+           p.deleted_at, p.removed_at,
+           u.avatar, u.username AS author_username,
+           c.slug AS community, c.visibility AS community_visibility,
+           ARRAY(SELECT t.slug FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = p.id ORDER BY t.slug) AS tags,
+           p.preview_fetched_at
+    FROM posts p
+    JOIN users u ON u.id = p.user_id
+    JOIN communities c ON c.id = p.community_id;
@@ -0,0 +1,168 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::application::avatar_service;
+use crate::application::error::AppError;
+use crate::domain::blobs::BlobStore;
+use crate::domain::comments::{Comment, CommentRepository};
+use crate::domain::posts::{FeedCursor, FeedSort, Post, PostRepository};
+use crate::domain::users::{UserPublic, UserRepository};
+use crate::domain::votes::{Vote, VoteRepository};
+use crate::infrastructure::auth;
+
+/// How long a deletion request can still be cancelled before the account is anonymized.
+pub const DELETION_GRACE_PERIOD_DAYS: i64 = 14;
+
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Serialize)]
+pub struct DeletionScheduled {
+    pub requested_at: DateTime<Utc>,
+    pub scheduled_for: DateTime<Utc>,
+}
+
+/// Account lifecycle operations that span every table holding a user's data.
+pub struct AccountService {
+    users: Arc<dyn UserRepository>,
+    posts: Arc<dyn PostRepository>,
+    comments: Arc<dyn CommentRepository>,
+    votes: Arc<dyn VoteRepository>,
+    blobs: Arc<dyn BlobStore>,
+}
+
+impl AccountService {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        posts: Arc<dyn PostRepository>,
+        comments: Arc<dyn CommentRepository>,
+        votes: Arc<dyn VoteRepository>,
+        blobs: Arc<dyn BlobStore>,
+    ) -> Self {
+        Self { users, posts, comments, votes, blobs }
+    }
+
+    /// Starts the grace period. The password is required again so a stolen token alone can't do this.
+    pub async fn request_deletion(&self, user_id: Uuid, password: &str) -> Result<DeletionScheduled, AppError> {
+        let user = self.users.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if !auth::verify_password(password, &user.password_hash)? {
+            return Err(AppError::Unauthorized);
+        }
+
+        let requested_at = user.deletion_requested_at.unwrap_or_else(Utc::now);
+        if user.deletion_requested_at.is_none() {
+            self.users.set_deletion_requested(user_id, Some(requested_at)).await?;
+        }
+
+        Ok(DeletionScheduled {
+            requested_at,
+            scheduled_for: requested_at + Duration::days(DELETION_GRACE_PERIOD_DAYS),
+        })
+    }
+
+    pub async fn cancel_deletion(&self, user_id: Uuid) -> Result<(), AppError> {
+        let user = self.users.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if user.deletion_requested_at.is_none() {
+            return Err(AppError::not_found("no pending account deletion"));
+        }
+
+        self.users.set_deletion_requested(user_id, None).await?;
+        Ok(())
+    }
+
+    /// Anonymizes every account whose grace period has run out and deletes their avatars, which are
+    /// served publicly by URL.
+    pub async fn purge_expired_deletions(&self) -> Result<u64, AppError> {
+        let cutoff = Utc::now() - Duration::days(DELETION_GRACE_PERIOD_DAYS);
+        let avatars = self.users.anonymize_requested_before(cutoff).await?;
+        for avatar in &avatars {
+            // the account no longer points at it, so no later sweep will find it again
+            if let Err(e) = avatar_service::delete_avatar(self.blobs.as_ref(), avatar).await {
+                tracing::error!(error = ?e, avatar, "failed to delete the avatar of an anonymized account");
+            }
+        }
+        Ok(avatars.len() as u64)
+    }
+
+    /// Runs `purge_expired_deletions` periodically for the lifetime of the process.
+    pub fn spawn_deletion_sweeper(self: Arc<Self>, every: StdDuration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                match self.purge_expired_deletions().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(accounts = n, "anonymized accounts past their deletion grace period"),
+                    Err(e) => tracing::error!(error = ?e, "account deletion sweep failed"),
+                }
+            }
+        })
+    }
+
+    /// Builds a ZIP archive with one JSON document per kind of data we hold about the user.
+    pub async fn export(&self, user_id: Uuid) -> Result<Vec<u8>, AppError> {
+        let user = self.users.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+
+        let posts = self.all_posts(user_id).await?;
+        let comments = self.all_comments(user_id).await?;
+        let votes: Vec<Vote> = self.votes.list_by_user(user_id).await?;
+        let profile: UserPublic = user.into();
+
+        let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        write_json(&mut zip, options, "profile.json", &profile)?;
+        write_json(&mut zip, options, "posts.json", &posts)?;
+        write_json(&mut zip, options, "comments.json", &comments)?;
+        write_json(&mut zip, options, "votes.json", &votes)?;
+
+        let cursor = zip.finish().map_err(anyhow::Error::from)?;
+        Ok(cursor.into_inner())
+    }
+
+    async fn all_posts(&self, user_id: Uuid) -> Result<Vec<Post>, AppError> {
+        let mut all = Vec::new();
+        let mut after = None;
+        loop {
+            let page = self.posts.list_by_author(user_id, Some(user_id), FeedSort::New, after, EXPORT_PAGE_SIZE).await?;
+            let done = (page.len() as i64) < EXPORT_PAGE_SIZE;
+            after = page.last().map(|p| FeedCursor::at(FeedSort::New, 0, p.created_at, p.id));
+            all.extend(page);
+            if done { return Ok(all); }
+        }
+    }
+
+    async fn all_comments(&self, user_id: Uuid) -> Result<Vec<Comment>, AppError> {
+        let mut all = Vec::new();
+        let mut after = None;
+        loop {
+            let page = self.comments.list_by_author(user_id, Some(user_id), FeedSort::New, after, EXPORT_PAGE_SIZE).await?;
+            let done = (page.len() as i64) < EXPORT_PAGE_SIZE;
+            after = page.last().map(|c| FeedCursor::at(FeedSort::New, 0, c.created_at, c.id));
+            all.extend(page);
+            if done { return Ok(all); }
+        }
+    }
+}
+
+fn write_json<W: Write + std::io::Seek, T: Serialize>(
+    zip: &mut ZipWriter<W>,
+    options: SimpleFileOptions,
+    name: &str,
+    value: &T,
+) -> Result<(), AppError> {
+    zip.start_file(name, options).map_err(anyhow::Error::from)?;
+    let json = serde_json::to_vec_pretty(value).map_err(anyhow::Error::from)?;
+    zip.write_all(&json).map_err(anyhow::Error::from)?;
+    Ok(())
+}
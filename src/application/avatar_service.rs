@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::domain::blobs::{Blob, BlobStore};
+use crate::domain::users::UserRepository;
+
+pub const MAX_AVATAR_BYTES: usize = 5 * 1024 * 1024;
+const MIN_DIMENSION: u32 = 64;
+const MAX_DIMENSION: u32 = 4096;
+/// Square thumbnail edge lengths; the first one is what `users.avatar` points at.
+pub const AVATAR_SIZES: &[u32] = &[256, 128, 64];
+
+/// Public path prefix avatars are served under; see `avatar_handler::get_avatar`.
+const AVATAR_URL_PREFIX: &str = "/api/avatars/";
+
+#[derive(Debug, Serialize)]
+pub struct AvatarUrls {
+    pub avatar: String,
+    pub sizes: BTreeMap<u32, String>,
+}
+
+pub struct AvatarService {
+    users: Arc<dyn UserRepository>,
+    blobs: Arc<dyn BlobStore>,
+}
+
+impl AvatarService {
+    pub fn new(users: Arc<dyn UserRepository>, blobs: Arc<dyn BlobStore>) -> Self { Self { users, blobs } }
+
+    /// Validates an uploaded image, stores square thumbnails and points the user's avatar at them.
+    pub async fn upload(&self, user_id: Uuid, bytes: Vec<u8>) -> Result<AvatarUrls, AppError> {
+        if bytes.len() > MAX_AVATAR_BYTES {
+            return Err(AppError::validation(format!("avatar must be at most {} bytes", MAX_AVATAR_BYTES)));
+        }
+
+        let user = self.users.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+
+        // decoding and resizing are CPU bound
+        let thumbnails = tokio::task::spawn_blocking(move || render_thumbnails(&bytes))
+            .await
+            .map_err(anyhow::Error::from)??;
+
+        // a fresh version per upload lets every size be cached forever
+        let version = Uuid::new_v4();
+        let mut sizes = BTreeMap::new();
+        for (size, png) in thumbnails {
+            let key = format!("avatars/{user_id}/{version}/{size}.png");
+            self.blobs.put(&key, Blob { bytes: png, content_type: "image/png".into() }).await?;
+            sizes.insert(size, format!("{AVATAR_URL_PREFIX}{user_id}/{version}/{size}.png"));
+        }
+
+        let avatar = sizes[&AVATAR_SIZES[0]].clone();
+        self.users.update_avatar(user_id, &avatar).await?;
+        self.remove_previous(&user.avatar).await;
+
+        Ok(AvatarUrls { avatar, sizes })
+    }
+
+    pub async fn get(&self, user_id: Uuid, version: Uuid, file: &str) -> Result<Blob, AppError> {
+        let key = format!("avatars/{user_id}/{version}/{file}");
+        self.blobs.get(&key).await?
+            .ok_or_else(|| AppError::not_found("avatar"))
+    }
+
+    /// Best effort: a leftover old avatar only costs storage.
+    async fn remove_previous(&self, previous: &str) {
+        if let Err(e) = delete_avatar(self.blobs.as_ref(), previous).await {
+            tracing::warn!(error = ?e, previous, "failed to delete previous avatar");
+        }
+    }
+}
+
+/// Deletes every size of the avatar at the URL `avatar`; the default avatar has nothing to delete.
+pub async fn delete_avatar(blobs: &dyn BlobStore, avatar: &str) -> anyhow::Result<()> {
+    let Some(rest) = avatar.strip_prefix(AVATAR_URL_PREFIX) else { return Ok(()) };
+    let Some((dir, _)) = rest.rsplit_once('/') else { return Ok(()) };
+    for size in AVATAR_SIZES {
+        blobs.delete(&format!("avatars/{dir}/{size}.png")).await?;
+    }
+    Ok(())
+}
+
+fn render_thumbnails(bytes: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, AppError> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(anyhow::Error::from)?;
+
+    match reader.format() {
+        Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Gif) => {}
+        _ => return Err(AppError::validation("avatar must be a PNG, JPEG, WebP or GIF image")),
+    }
+
+    // refuse decompression bombs before allocating the pixel buffer
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    let mut reader = reader;
+    reader.limits(limits);
+
+    let image = reader.decode()
+        .map_err(|_| AppError::validation(format!("avatar could not be decoded or is larger than {MAX_DIMENSION}x{MAX_DIMENSION}")))?;
+
+    if image.width() < MIN_DIMENSION || image.height() < MIN_DIMENSION {
+        return Err(AppError::validation(format!("avatar must be at least {MIN_DIMENSION}x{MIN_DIMENSION}")));
+    }
+
+    let square = center_square(&image);
+    AVATAR_SIZES.iter()
+        .map(|&size| {
+            let thumb = square.resize_exact(size, size, FilterType::Lanczos3);
+            let mut png = Vec::new();
+            thumb.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .map_err(anyhow::Error::from)?;
+            Ok((size, png))
+        })
+        .collect()
+}
+
+fn center_square(image: &DynamicImage) -> DynamicImage {
+    let side = image.width().min(image.height());
+    let x = (image.width() - side) / 2;
+    let y = (image.height() - side) / 2;
+    image.crop_imm(x, y, side, side)
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::application::error::AppError;
+use crate::application::posts_service;
+use crate::application::utils::content_policy;
+use crate::application::utils::spam::SpamScorer;
+use crate::domain::comments::{Comment, CommentRepository};
+use crate::domain::communities::CommunityRepository;
+use crate::domain::content_filter::ContentFilter;
+use crate::domain::posts::{FeedCursor, FeedSort, PostRepository};
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct CreateCommentInput {
+    pub parent_id: Option<Uuid>,
+    #[validate(length(min = 1, max = 10_000))]
+    pub body: String,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct UpdateCommentInput {
+    #[validate(length(min = 1, max = 10_000))]
+    pub body: String,
+}
+
+/// A comment with its replies nested underneath, as returned for a post's thread.
+#[derive(Debug, Serialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub children: Vec<CommentNode>,
+}
+
+pub struct CommentService {
+    repo: Arc<dyn CommentRepository>,
+    posts: Arc<dyn PostRepository>,
+    communities: Arc<dyn CommunityRepository>,
+    filter: Arc<dyn ContentFilter>,
+    spam: Arc<SpamScorer>,
+    /// Edits this soon after commenting don't set `edited_at`.
+    edit_grace: Duration,
+}
+
+impl CommentService {
+    pub fn new(repo: Arc<dyn CommentRepository>, posts: Arc<dyn PostRepository>, communities: Arc<dyn CommunityRepository>, filter: Arc<dyn ContentFilter>, spam: Arc<SpamScorer>, edit_grace: Duration) -> Self {
+        Self { repo, posts, communities, filter, spam, edit_grace }
+    }
+
+    /// Commenting follows the same rules as posting in the post's community. Locked and archived posts take no new comments.
+    /// A comment the content filter or the spam check holds is only shown to its author until a moderator approves it.
+    pub async fn create(&self, user_id: Uuid, post_id: Uuid, mut input: CreateCommentInput) -> Result<Comment, AppError> {
+        input.validate().map_err(|e| AppError::validation(e.to_string()))?;
+
+        let post = self.posts.find_by_id(post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        let (community, role) = posts_service::check_readable(self.communities.as_ref(), Some(user_id), &post).await?;
+        if !community.can_post(role) {
+            return Err(AppError::forbidden("only members can comment in this community"));
+        }
+        posts_service::check_open(&post)?;
+
+        // ensure parent belongs to same post if provided
+        if let Some(parent_id) = input.parent_id {
+            match self.repo.find_by_id(parent_id).await? {
+                Some(parent) if parent.post_id == post_id && parent.is_visible() => {}
+                _ => return Err(AppError::validation("parent comment not found in this post")),
+            }
+        }
+
+        let hold_reason = content_policy::screen(self.filter.as_ref(), &post.community, &mut [&mut input.body])?;
+        let spam_reason = self.spam.check(user_id, &input.body, None).await?;
+        let hold_reason = [hold_reason, spam_reason].into_iter().flatten().reduce(|a, b| format!("{a}; {b}"));
+        let comment = self.repo.create(post_id, user_id, input.parent_id, &input.body, hold_reason.as_deref()).await?;
+        Ok(comment)
+    }
+
+    /// Authors can edit their own comments while the post is open; the replaced body is kept as a
+    /// revision. An edit the content filter holds hides the comment until a moderator approves it.
+    pub async fn update(&self, user_id: Uuid, comment_id: Uuid, mut input: UpdateCommentInput) -> Result<Comment, AppError> {
+        input.validate().map_err(|e| AppError::validation(e.to_string()))?;
+
+        let comment = self.repo.find_by_id(comment_id).await?
+            .filter(|c| c.is_visible())
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        if comment.user_id != user_id {
+            return Err(AppError::forbidden("you can only edit your own comments"));
+        }
+        let post = self.posts.find_by_id(comment.post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        posts_service::check_open(&post)?;
+
+        let hold_reason = content_policy::screen(self.filter.as_ref(), &post.community, &mut [&mut input.body])?;
+        let mark_edited = Utc::now() - comment.created_at > self.edit_grace;
+        let comment = self.repo.update(comment_id, user_id, &input.body, hold_reason.as_deref(), mark_edited).await?;
+        Ok(comment)
+    }
+
+    /// Returns the post's comments as a tree, oldest first at every level. Deleted and removed
+    /// comments show up as tombstones while they have replies, so the replies keep their place;
+    /// so do held ones, to everyone but their author.
+    pub async fn thread(&self, viewer: Option<Uuid>, post_id: Uuid) -> Result<Vec<CommentNode>, AppError> {
+        let post = self.posts.find_for_viewer(post_id, viewer).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        posts_service::check_readable(self.communities.as_ref(), viewer, &post).await?;
+        let comments = self.repo.list_for_post(post_id, viewer).await?;
+        Ok(build_tree(comments))
+    }
+
+    /// Authors can delete their own comments; moderators remove them through the moderation service.
+    pub async fn delete(&self, user_id: Uuid, comment_id: Uuid) -> Result<(), AppError> {
+        let comment = self.repo.find_by_id(comment_id).await?
+            .filter(|c| c.deleted_at.is_none())
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        if comment.user_id != user_id {
+            return Err(AppError::forbidden("you can only delete your own comments"));
+        }
+        self.repo.delete(comment_id).await?;
+        Ok(())
+    }
+
+    pub async fn list_by_author(
+        &self,
+        user_id: Uuid,
+        viewer: Option<Uuid>,
+        sort: FeedSort,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Comment>, AppError> {
+        Ok(self.repo.list_by_author(user_id, viewer, sort, after, limit).await?)
+    }
+}
+
+fn build_tree(comments: Vec<Comment>) -> Vec<CommentNode> {
+    let mut children: HashMap<Option<Uuid>, Vec<Comment>> = HashMap::new();
+    for c in comments {
+        children.entry(c.parent_id).or_default().push(c);
+    }
+
+    fn attach(parent: Option<Uuid>, children: &mut HashMap<Option<Uuid>, Vec<Comment>>) -> Vec<CommentNode> {
+        children.remove(&parent).unwrap_or_default()
+            .into_iter()
+            .filter_map(|comment| {
+                let replies = attach(Some(comment.id), children);
+                if (!comment.is_visible() || comment.hidden) && replies.is_empty() {
+                    return None;
+                }
+                Some(CommentNode { comment: tombstone(comment, replies.len()), children: replies })
+            })
+            .collect()
+    }
+
+    attach(None, &mut children)
+}
+
+/// Blanks what a deleted, hidden or removed comment said; a deleted or hidden one also loses its author.
+/// Hidden ones get the very tombstone deleted ones do, so readers can't tell them apart: both are dated
+/// as deleted when written, and count only the `shown_replies` left under them.
+fn tombstone(mut comment: Comment, shown_replies: usize) -> Comment {
+    if comment.deleted_at.is_some() || comment.hidden {
+        return Comment {
+            body: "[deleted]".to_string(),
+            author_username: "[deleted]".to_string(),
+            user_id: Uuid::nil(),
+            deleted_at: Some(comment.created_at),
+            removed_at: None,
+            removal_reason: None,
+            held_at: None,
+            edited_at: None,
+            reply_count: shown_replies as i64,
+            hidden: false,
+            ..comment
+        };
+    }
+    if comment.removed_at.is_some() {
+        comment.body = "[removed]".to_string();
+    }
+    comment
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::build_tree;
+    use crate::domain::comments::Comment;
+
+    fn comment(parent: Option<&Comment>) -> Comment {
+        Comment {
+            id: Uuid::new_v4(),
+            post_id: Uuid::nil(),
+            user_id: Uuid::new_v4(),
+            parent_id: parent.map(|p| p.id),
+            body: "a comment".to_string(),
+            created_at: Utc::now() - Duration::hours(1),
+            author_username: "author".to_string(),
+            deleted_at: None,
+            removed_at: None,
+            removal_reason: None,
+            held_at: None,
+            edited_at: None,
+            reply_count: 0,
+            hidden: false,
+        }
+    }
+
+    /// What a reader sees of a comment, but for what every comment has its own of.
+    fn shown(c: &Comment) -> serde_json::Value {
+        let mut json = serde_json::to_value(c).unwrap();
+        for own in ["id", "created_at", "deleted_at"] {
+            json[own] = serde_json::Value::Null;
+        }
+        json
+    }
+
+    #[test]
+    fn hidden_comments_look_deleted() {
+        let mut deleted = comment(None);
+        deleted.deleted_at = Some(Utc::now());
+        deleted.edited_at = Some(Utc::now());
+        deleted.reply_count = 2;
+        let mut held = comment(None);
+        held.held_at = Some(Utc::now());
+        held.hidden = true;
+        held.reply_count = 2;
+        let mut shadowbanned = comment(None);
+        shadowbanned.hidden = true;
+        shadowbanned.reply_count = 2;
+
+        // each keeps one reply in sight and one hidden reply out of it
+        let mut comments = Vec::new();
+        for parent in [&deleted, &held, &shadowbanned] {
+            let mut gone = comment(Some(parent));
+            gone.hidden = true;
+            comments.extend([comment(Some(parent)), gone]);
+        }
+        comments.extend([deleted, held, shadowbanned]);
+
+        let tree = build_tree(comments);
+        assert_eq!(tree.len(), 3);
+        for node in &tree {
+            assert_eq!(node.children.len(), 1);
+            assert_eq!(node.comment.body, "[deleted]");
+            assert_eq!(node.comment.reply_count, 1);
+            assert_eq!(node.comment.deleted_at, Some(node.comment.created_at));
+            assert_eq!(shown(&node.comment), shown(&tree[0].comment));
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::application::error::AppError;
+use crate::application::utils::validation;
+use crate::domain::communities::{Community, CommunityRepository, MemberRole, Visibility};
+use crate::domain::mod_log::{ModActionKind, ModTarget, ModerationStore, NewModAction};
+use crate::domain::users::UserRepository;
+
+const MAX_RULES: usize = 15;
+const MAX_RULE_LEN: usize = 500;
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct CreateCommunityInput {
+    pub slug: String,
+    #[validate(length(min = 3, max = 100))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default = "default_visibility")]
+    pub visibility: Visibility,
+}
+
+fn default_visibility() -> Visibility { Visibility::Public }
+
+/// Fields left out are kept as they are.
+#[derive(Debug, Validate, Deserialize)]
+pub struct UpdateCommunityInput {
+    #[validate(length(min = 3, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    pub rules: Option<Vec<String>>,
+    pub visibility: Option<Visibility>,
+}
+
+pub struct CommunityService {
+    repo: Arc<dyn CommunityRepository>,
+    users: Arc<dyn UserRepository>,
+    /// Member roles change in the same transaction as their log entry.
+    store: Arc<dyn ModerationStore>,
+}
+
+impl CommunityService {
+    pub fn new(repo: Arc<dyn CommunityRepository>, users: Arc<dyn UserRepository>, store: Arc<dyn ModerationStore>) -> Self {
+        Self { repo, users, store }
+    }
+
+    pub async fn create(&self, user_id: Uuid, input: CreateCommunityInput) -> Result<Community, AppError> {
+        input.validate().map_err(|e| AppError::validation(e.to_string()))?;
+        let slug = input.slug.trim().to_lowercase();
+        validation::validate_community_slug(&slug)
+            .map_err(|_| AppError::validation("Slug must be 3-32 characters of a-z, 0-9 and _"))?;
+        validate_rules(&input.rules)?;
+
+        self.repo.create(&slug, input.name.trim(), &input.description, &input.rules, input.visibility, user_id).await?
+            .ok_or_else(|| AppError::conflict("community already exists"))
+    }
+
+    pub async fn find(&self, slug: &str) -> Result<Community, AppError> {
+        self.repo.find_by_slug(&slug.to_lowercase()).await?
+            .ok_or_else(|| AppError::not_found("community not found"))
+    }
+
+    pub async fn update(&self, user_id: Uuid, slug: &str, input: UpdateCommunityInput) -> Result<Community, AppError> {
+        input.validate().map_err(|e| AppError::validation(e.to_string()))?;
+        let community = self.find(slug).await?;
+        self.require_moderator(user_id, &community).await?;
+
+        let rules = input.rules.unwrap_or(community.rules);
+        validate_rules(&rules)?;
+
+        Ok(self.repo.update(
+            community.id,
+            input.name.as_deref().map(str::trim).unwrap_or(&community.name),
+            input.description.as_deref().unwrap_or(&community.description),
+            &rules,
+            input.visibility.unwrap_or(community.visibility),
+        ).await?)
+    }
+
+    pub async fn member_role(&self, user_id: Uuid, community: &Community) -> Result<Option<MemberRole>, AppError> {
+        Ok(self.repo.member_role(community.id, user_id).await?)
+    }
+
+    /// Anyone can join a public community; restricted and private ones are joined by a moderator adding you.
+    pub async fn join(&self, user_id: Uuid, slug: &str) -> Result<Community, AppError> {
+        let community = self.find(slug).await?;
+        if self.repo.member_role(community.id, user_id).await?.is_some() {
+            return Ok(community);
+        }
+        if community.visibility != Visibility::Public {
+            return Err(AppError::forbidden("ask a moderator to add you to this community"));
+        }
+
+        self.repo.set_member(community.id, user_id, MemberRole::Member).await?;
+        self.find(slug).await
+    }
+
+    pub async fn leave(&self, user_id: Uuid, slug: &str) -> Result<(), AppError> {
+        let community = self.find(slug).await?;
+        match self.repo.member_role(community.id, user_id).await? {
+            None => Err(AppError::not_found("not a member of this community")),
+            Some(MemberRole::Owner) => Err(AppError::conflict("the owner cannot leave the community")),
+            Some(_) => {
+                self.repo.remove_member(community.id, user_id).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Moderators add members; only the owner (or a site moderator) appoints or demotes moderators.
+    pub async fn set_member_role(&self, actor_id: Uuid, slug: &str, user_id: Uuid, role: MemberRole) -> Result<(), AppError> {
+        let community = self.find(slug).await?;
+        if role == MemberRole::Owner {
+            return Err(AppError::validation("ownership cannot be assigned"));
+        }
+
+        let current = self.repo.member_role(community.id, user_id).await?;
+        if current == Some(MemberRole::Owner) {
+            return Err(AppError::conflict("the owner's role cannot be changed"));
+        }
+        if role == MemberRole::Moderator || current == Some(MemberRole::Moderator) {
+            self.require_owner(actor_id, &community).await?;
+        } else {
+            self.require_moderator(actor_id, &community).await?;
+        }
+
+        let mut tx = self.store.begin().await?;
+        tx.set_member(community.id, user_id, role).await?;
+        tx.record(role_change(actor_id, &community, user_id, current, Some(role))).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn remove_member(&self, actor_id: Uuid, slug: &str, user_id: Uuid) -> Result<(), AppError> {
+        let community = self.find(slug).await?;
+        let current = self.repo.member_role(community.id, user_id).await?;
+        match current {
+            None => return Err(AppError::not_found("not a member of this community")),
+            Some(MemberRole::Owner) => return Err(AppError::conflict("the owner cannot be removed")),
+            Some(MemberRole::Moderator) => self.require_owner(actor_id, &community).await?,
+            Some(MemberRole::Member) => self.require_moderator(actor_id, &community).await?,
+        }
+
+        let mut tx = self.store.begin().await?;
+        tx.remove_member(community.id, user_id).await?;
+        tx.record(role_change(actor_id, &community, user_id, current, None)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn joined(&self, user_id: Uuid) -> Result<Vec<Community>, AppError> {
+        Ok(self.repo.list_joined(user_id).await?)
+    }
+
+    /// Community moderators and owners, plus site-wide moderators.
+    pub async fn can_moderate(&self, user_id: Uuid, community: &Community) -> Result<bool, AppError> {
+        if matches!(self.repo.member_role(community.id, user_id).await?, Some(role) if role >= MemberRole::Moderator) {
+            return Ok(true);
+        }
+        self.is_site_moderator(user_id).await
+    }
+
+    pub async fn require_moderator(&self, user_id: Uuid, community: &Community) -> Result<(), AppError> {
+        if !self.can_moderate(user_id, community).await? {
+            return Err(AppError::forbidden("community moderator role required"));
+        }
+        Ok(())
+    }
+
+    async fn require_owner(&self, user_id: Uuid, community: &Community) -> Result<(), AppError> {
+        if self.repo.member_role(community.id, user_id).await? == Some(MemberRole::Owner) || self.is_site_moderator(user_id).await? {
+            return Ok(());
+        }
+        Err(AppError::forbidden("only the community owner can do this"))
+    }
+
+    async fn is_site_moderator(&self, user_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.users.find_by_id(user_id).await?.is_some_and(|u| u.role.is_moderator()))
+    }
+}
+
+/// The log entry for a member's role going from `before` to `after`; `None` stands for not being a member.
+fn role_change(actor_id: Uuid, community: &Community, user_id: Uuid, before: Option<MemberRole>, after: Option<MemberRole>) -> NewModAction {
+    NewModAction {
+        actor_id,
+        action: ModActionKind::RoleChange,
+        target_type: ModTarget::User,
+        target_id: user_id,
+        community_id: Some(community.id),
+        reason: String::new(),
+        before: Some(serde_json::json!({ "role": before })),
+        after: Some(serde_json::json!({ "role": after })),
+    }
+}
+
+fn validate_rules(rules: &[String]) -> Result<(), AppError> {
+    if rules.len() > MAX_RULES {
+        return Err(AppError::validation(format!("A community can have at most {MAX_RULES} rules")));
+    }
+    if rules.iter().any(|r| r.trim().is_empty() || r.chars().count() > MAX_RULE_LEN) {
+        return Err(AppError::validation(format!("Rules must be between 1 and {MAX_RULE_LEN} characters")));
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::utils::validation;
+use crate::domain::domains::{DomainAction, DomainRepository, DomainRule, DomainStats};
+use crate::domain::posts::{FeedCursor, FeedSort, Post, PostRepository};
+
+const MAX_REASON_LEN: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct DomainRuleInput {
+    pub action: DomainAction,
+    /// Required for `rate_limit`, and only allowed there; so is `window_hours`.
+    pub max_posts: Option<i32>,
+    pub window_hours: Option<i32>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+pub struct DomainService {
+    repo: Arc<dyn DomainRepository>,
+    posts: Arc<dyn PostRepository>,
+}
+
+impl DomainService {
+    pub fn new(repo: Arc<dyn DomainRepository>, posts: Arc<dyn PostRepository>) -> Self { Self { repo, posts } }
+
+    pub async fn rules(&self) -> Result<Vec<DomainRule>, AppError> {
+        Ok(self.repo.list_rules().await?)
+    }
+
+    /// Sets the rule for `domain` and its subdomains, replacing any it had. Links already posted are left alone.
+    pub async fn set_rule(&self, admin_id: Uuid, domain: &str, input: DomainRuleInput) -> Result<DomainRule, AppError> {
+        let domain = parse_domain(domain)?;
+        let reason = input.reason.trim();
+        if reason.chars().count() > MAX_REASON_LEN {
+            return Err(AppError::validation(format!("Reasons can be at most {MAX_REASON_LEN} characters")));
+        }
+        match (input.action, input.max_posts, input.window_hours) {
+            (DomainAction::RateLimit, Some(max_posts), Some(window_hours)) if max_posts > 0 && window_hours > 0 => {}
+            (DomainAction::RateLimit, _, _) => {
+                return Err(AppError::validation("A rate limit needs a positive max_posts and window_hours"));
+            }
+            (_, None, None) => {}
+            _ => return Err(AppError::validation("max_posts and window_hours only apply to rate limits")),
+        }
+
+        Ok(self.repo.upsert_rule(&domain, input.action, input.max_posts, input.window_hours, reason, admin_id).await?)
+    }
+
+    pub async fn remove_rule(&self, domain: &str) -> Result<(), AppError> {
+        if !self.repo.delete_rule(&validation::normalize_domain(domain)).await? {
+            return Err(AppError::not_found("no rule for this domain"));
+        }
+        Ok(())
+    }
+
+    /// Posts linking to the domain or its subdomains.
+    pub async fn posts(
+        &self,
+        domain: &str,
+        viewer: Option<Uuid>,
+        sort: FeedSort,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Post>, AppError> {
+        let domain = parse_domain(domain)?;
+        Ok(self.posts.list_by_domain(&domain, viewer, sort, after, limit).await?)
+    }
+
+    pub async fn stats(&self, domain: &str) -> Result<DomainStats, AppError> {
+        let domain = parse_domain(domain)?;
+        Ok(self.repo.stats(&domain).await?)
+    }
+}
+
+/// Applies the rule covering `url`'s domain to a user linking to it. Refuses blocked domains and
+/// links over a rate limit; otherwise returns why the post must be held for review, if it must.
+pub async fn check_link(repo: &dyn DomainRepository, user_id: Uuid, url: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(domain) = url.and_then(|u| Url::parse(u).ok()).and_then(|u| u.host_str().map(validation::normalize_domain)) else {
+        return Ok(None);
+    };
+    let Some(rule) = repo.rule_for(&domain).await? else {
+        return Ok(None);
+    };
+
+    match rule.action {
+        DomainAction::Allow => Ok(None),
+        DomainAction::Block => {
+            let reason = rule.reason.map(|r| format!(": {r}")).unwrap_or_default();
+            Err(AppError::validation(format!("Links to {} aren't allowed{reason}", rule.domain)))
+        }
+        DomainAction::Review => Ok(Some(format!("domain rule: links to {} are reviewed", rule.domain))),
+        DomainAction::RateLimit => {
+            let (Some(max_posts), Some(window_hours)) = (rule.max_posts, rule.window_hours) else {
+                return Ok(None);
+            };
+            let since = Utc::now() - Duration::hours(window_hours.into());
+            if repo.count_recent_posts(user_id, &rule.domain, since).await? >= i64::from(max_posts) {
+                return Err(AppError::rate_limited(format!(
+                    "You can link to {} at most {max_posts} times every {window_hours} hours", rule.domain
+                )));
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Turns the `SPAM_BANNED_DOMAINS` list into `review` rules, so links to them are held like any
+/// other reviewed domain. A domain that already has a rule keeps it, so an admin's `allow` wins;
+/// a rule an admin deletes comes back on the next start while the domain is still listed.
+/// Returns how many rules were added.
+pub async fn seed_banned_domains(repo: &dyn DomainRepository, domains: &[String]) -> Result<u64, AppError> {
+    let mut added = 0;
+    for domain in domains {
+        let domain = parse_domain(domain)?;
+        if repo.insert_rule(&domain, DomainAction::Review, "listed in SPAM_BANNED_DOMAINS").await? {
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+fn parse_domain(raw: &str) -> Result<String, AppError> {
+    let domain = validation::normalize_domain(raw);
+    validation::validate_domain(&domain)
+        .map_err(|_| AppError::validation("Domain must be a hostname like example.com"))?;
+    Ok(domain)
+}
@@ -10,6 +10,13 @@ pub enum AppError {
     NotFound(String),
     #[error("unauthorized")]
     Unauthorized,
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    #[error("too many requests: {0}")]
+    RateLimited(String),
+    /// The client's `If-Match` no longer matches what is stored.
+    #[error("precondition failed: {0}")]
+    PreconditionFailed(String),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -18,4 +25,7 @@ impl AppError {
     pub fn validation(msg: impl Into<String>) -> Self { Self::Validation(msg.into()) }
     pub fn conflict(msg: impl Into<String>) -> Self { Self::Conflict(msg.into()) }
     pub fn not_found(msg: impl Into<String>) -> Self { Self::NotFound(msg.into()) }
+    pub fn forbidden(msg: impl Into<String>) -> Self { Self::Forbidden(msg.into()) }
+    pub fn rate_limited(msg: impl Into<String>) -> Self { Self::RateLimited(msg.into()) }
+    pub fn precondition_failed(msg: impl Into<String>) -> Self { Self::PreconditionFailed(msg.into()) }
 }
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::domain::follows::FollowRepository;
+
+#[derive(Debug, Serialize)]
+pub struct Following {
+    pub users: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+pub struct FollowService {
+    repo: Arc<dyn FollowRepository>,
+}
+
+impl FollowService {
+    pub fn new(repo: Arc<dyn FollowRepository>) -> Self { Self { repo } }
+
+    /// Following someone twice is not an error.
+    pub async fn follow_user(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), AppError> {
+        if follower_id == followee_id {
+            return Err(AppError::validation("You cannot follow yourself"));
+        }
+        self.repo.follow_user(follower_id, followee_id).await?;
+        Ok(())
+    }
+
+    pub async fn unfollow_user(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), AppError> {
+        if !self.repo.unfollow_user(follower_id, followee_id).await? {
+            return Err(AppError::not_found("not following this user"));
+        }
+        Ok(())
+    }
+
+    pub async fn follow_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<(), AppError> {
+        self.repo.follow_tag(user_id, tag_id).await?;
+        Ok(())
+    }
+
+    pub async fn unfollow_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<(), AppError> {
+        if !self.repo.unfollow_tag(user_id, tag_id).await? {
+            return Err(AppError::not_found("not following this tag"));
+        }
+        Ok(())
+    }
+
+    pub async fn following(&self, user_id: Uuid) -> Result<Following, AppError> {
+        Ok(Following {
+            users: self.repo.followed_usernames(user_id).await?,
+            tags: self.repo.followed_tags(user_id).await?,
+        })
+    }
+}
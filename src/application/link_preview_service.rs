@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use tokio::sync::{broadcast::{self, error::RecvError}, Semaphore};
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::utils::link_meta;
+use crate::domain::posts::{Post, PostEvent};
+use crate::domain::previews::{LinkPreview, PageFetcher, PreviewRepository};
+
+/// Fetches are slow and external; don't let a burst of link posts open unbounded connections.
+const MAX_CONCURRENT_FETCHES: usize = 4;
+
+pub struct LinkPreviewService {
+    fetcher: Arc<dyn PageFetcher>,
+    previews: Arc<dyn PreviewRepository>,
+    permits: Arc<Semaphore>,
+}
+
+impl LinkPreviewService {
+    pub fn new(fetcher: Arc<dyn PageFetcher>, previews: Arc<dyn PreviewRepository>) -> Self {
+        Self { fetcher, previews, permits: Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES)) }
+    }
+
+    /// Fetches `url` and stores what it yields. A failed fetch is recorded as an empty attempt
+    /// and reported as `None`; only storage errors are returned.
+    pub async fn refresh(&self, post_id: Uuid, url: &str) -> Result<Option<LinkPreview>, AppError> {
+        match self.fetcher.fetch(url).await {
+            Ok(page) => {
+                let preview = link_meta::extract_preview(&page);
+                self.previews.set_preview(post_id, url, Some(&preview)).await?;
+                Ok(Some(preview))
+            }
+            Err(e) => {
+                tracing::info!(%post_id, url, error = %e, "link preview fetch failed");
+                self.previews.set_preview(post_id, url, None).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Fetches previews for new link posts, and for edited ones whose link changed, for the
+    /// lifetime of the process.
+    pub fn spawn_worker(self: Arc<Self>, mut events: broadcast::Receiver<PostEvent>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let post = match events.recv().await {
+                    Ok(PostEvent::Created(post)) | Ok(PostEvent::Updated(post)) => post,
+                    Ok(PostEvent::Deleted(_)) => continue,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "link preview worker fell behind; some posts will have no preview");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                let Some(url) = post.url.clone().filter(|_| needs_preview(&post)) else { continue };
+
+                let Ok(permit) = self.permits.clone().acquire_owned().await else { return };
+                let service = self.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = service.refresh(post.id, &url).await {
+                        tracing::error!(post_id = %post.id, error = %e, "failed to store link preview");
+                    }
+                    drop(permit);
+                });
+            }
+        })
+    }
+}
+
+/// Every attempt is recorded, failed ones too, and editing a post's link clears the record; so only
+/// new links are fetched, once each.
+fn needs_preview(post: &Post) -> bool {
+    post.url.is_some() && post.preview_fetched_at.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use uuid::Uuid;
+
+    use super::LinkPreviewService;
+    use crate::domain::previews::{LinkPreview, PreviewRepository};
+    use crate::infrastructure::page_fetcher::fake::FakePageFetcher;
+
+    /// Remembers every stored preview, in order.
+    #[derive(Default)]
+    struct StoredPreviews(Mutex<Vec<(Uuid, String, Option<LinkPreview>)>>);
+
+    #[async_trait::async_trait]
+    impl PreviewRepository for StoredPreviews {
+        async fn set_preview(&self, post_id: Uuid, url: &str, preview: Option<&LinkPreview>) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push((post_id, url.to_string(), preview.cloned()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_stores_the_preview_of_the_fetched_page() {
+        let url = "https://example.com/article";
+        let fetcher = Arc::new(FakePageFetcher::new().with_html(url, r#"<head><meta property="og:title" content="An article"></head>"#));
+        let stored = Arc::new(StoredPreviews::default());
+        let service = LinkPreviewService::new(fetcher.clone(), stored.clone());
+        let post_id = Uuid::new_v4();
+
+        let preview = service.refresh(post_id, url).await.unwrap().unwrap();
+
+        assert_eq!(preview.title.as_deref(), Some("An article"));
+        assert_eq!(preview.site_name.as_deref(), Some("example.com"));
+        assert_eq!(fetcher.requested(), vec![url.to_string()]);
+        assert_eq!(*stored.0.lock().unwrap(), vec![(post_id, url.to_string(), Some(preview))]);
+    }
+
+    #[tokio::test]
+    async fn refresh_records_a_failed_fetch_as_an_empty_attempt() {
+        let url = "https://unreachable.example/";
+        let stored = Arc::new(StoredPreviews::default());
+        let service = LinkPreviewService::new(Arc::new(FakePageFetcher::new()), stored.clone());
+        let post_id = Uuid::new_v4();
+
+        assert_eq!(service.refresh(post_id, url).await.unwrap(), None);
+        assert_eq!(*stored.0.lock().unwrap(), vec![(post_id, url.to_string(), None)]);
+    }
+}
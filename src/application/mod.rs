@@ -1,5 +1,21 @@
 pub mod posts_service;
 pub mod user_service;
 pub mod vote_service;
+pub mod comment_service;
+pub mod community_service;
+pub mod follow_service;
+pub mod account_service;
+pub mod avatar_service;
+pub mod search_service;
+pub mod tag_service;
+pub mod saved_service;
+pub mod mute_service;
+pub mod link_preview_service;
+pub mod moderation_service;
+pub mod domain_service;
+pub mod revision_service;
 pub mod utils;
-pub mod error;
\ No newline at end of file
+pub mod error;
+
+#[cfg(test)]
+pub(crate) mod test_support;
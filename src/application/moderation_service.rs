@@ -0,0 +1,720 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::posts_service;
+use crate::application::utils::spam;
+use crate::domain::comments::CommentRepository;
+use crate::domain::communities::{Community, CommunityRepository, MemberRole, Visibility};
+use crate::domain::mod_log::{ModAction, ModActionKind, ModLogFilter, ModLogRepository, ModTarget, ModerationStore, ModerationTx, NewModAction};
+use crate::domain::posts::{Post, PostEdit, PostEvent, PostRepository};
+use crate::domain::reports::{HeldItem, ReportReason, ReportRepository, ReportTarget, ReportedItem, Resolution};
+use crate::domain::sanctions::{EvasionSignal, Sanction, SanctionKind, SanctionRepository};
+use crate::domain::users::UserRepository;
+
+const MAX_DETAILS_LEN: usize = 1000;
+const MAX_NOTE_LEN: usize = 1000;
+/// Sharing one of these with a banned account says nothing, so they don't count as an evasion signal.
+const COMMON_EMAIL_DOMAINS: &[&str] = &[
+    "gmail.com", "googlemail.com", "outlook.com", "hotmail.com", "live.com", "msn.com",
+    "yahoo.com", "icloud.com", "me.com", "aol.com", "proton.me", "protonmail.com",
+    "gmx.com", "gmx.de", "mail.com", "yandex.ru", "qq.com",
+];
+
+#[derive(Debug, Deserialize)]
+pub struct ReportInput {
+    pub reason: ReportReason,
+    #[serde(default)]
+    pub details: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveInput {
+    pub action: Resolution,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SanctionInput {
+    pub kind: SanctionKind,
+    #[serde(default)]
+    pub reason: String,
+    /// Left out for a sanction that lasts until revoked.
+    pub duration_hours: Option<i64>,
+}
+
+/// A moderator's edit replaces the same fields as the author's own `PUT /posts/{id}`.
+#[derive(Debug, Deserialize)]
+pub struct EditPostInput {
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+pub struct ModerationService {
+    repo: Arc<dyn ReportRepository>,
+    posts: Arc<dyn PostRepository>,
+    comments: Arc<dyn CommentRepository>,
+    communities: Arc<dyn CommunityRepository>,
+    users: Arc<dyn UserRepository>,
+    sanctions: Arc<dyn SanctionRepository>,
+    log: Arc<dyn ModLogRepository>,
+    store: Arc<dyn ModerationStore>,
+    events: broadcast::Sender<PostEvent>,
+}
+
+/// The repositories `ModerationService` reads through; every moderator action is written through `store`.
+pub struct ModerationRepos {
+    pub reports: Arc<dyn ReportRepository>,
+    pub posts: Arc<dyn PostRepository>,
+    pub comments: Arc<dyn CommentRepository>,
+    pub communities: Arc<dyn CommunityRepository>,
+    pub users: Arc<dyn UserRepository>,
+    pub sanctions: Arc<dyn SanctionRepository>,
+    pub log: Arc<dyn ModLogRepository>,
+    pub store: Arc<dyn ModerationStore>,
+}
+
+impl ModerationService {
+    pub fn new(repos: ModerationRepos, events: broadcast::Sender<PostEvent>) -> Self {
+        let ModerationRepos { reports: repo, posts, comments, communities, users, sanctions, log, store } = repos;
+        Self { repo, posts, comments, communities, users, sanctions, log, store, events }
+    }
+
+    /// Reporting the same item again replaces the reporter's earlier reason and details.
+    pub async fn report_post(&self, reporter_id: Uuid, post_id: Uuid, input: ReportInput) -> Result<(), AppError> {
+        let details = validate_report(&input)?;
+        let post = self.posts.find_by_id(post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        if post.user_id == reporter_id {
+            return Err(AppError::validation("You can't report your own post"));
+        }
+        let community = self.readable_community(reporter_id, &post).await?;
+
+        self.repo.upsert(reporter_id, ReportTarget::Post, post_id, community.id, input.reason, details).await?;
+        Ok(())
+    }
+
+    pub async fn report_comment(&self, reporter_id: Uuid, comment_id: Uuid, input: ReportInput) -> Result<(), AppError> {
+        let details = validate_report(&input)?;
+        let comment = self.comments.find_by_id(comment_id).await?
+            .filter(|c| c.is_visible())
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        if comment.user_id == reporter_id {
+            return Err(AppError::validation("You can't report your own comment"));
+        }
+        let post = self.posts.find_by_id(comment.post_id).await?
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        let community = self.readable_community(reporter_id, &post).await?;
+
+        self.repo.upsert(reporter_id, ReportTarget::Comment, comment_id, community.id, input.reason, details).await?;
+        Ok(())
+    }
+
+    /// Site moderators see every community's reports; community moderators must name their community.
+    pub async fn queue(
+        &self,
+        user_id: Uuid,
+        community: Option<&str>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<Vec<ReportedItem>, AppError> {
+        let community_id = match community {
+            Some(slug) => {
+                let community = self.communities.find_by_slug(&slug.to_lowercase()).await?
+                    .ok_or_else(|| AppError::not_found("community not found"))?;
+                self.require_moderator(user_id, &community).await?;
+                Some(community.id)
+            }
+            None if self.is_site_moderator(user_id).await? => None,
+            None => return Err(AppError::forbidden("moderator role required")),
+        };
+
+        Ok(self.repo.queue(community_id, after, limit).await?)
+    }
+
+    /// What the content filter is holding for review; who may see it works as in `queue`.
+    pub async fn held(
+        &self,
+        user_id: Uuid,
+        community: Option<&str>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<Vec<HeldItem>, AppError> {
+        let community_id = match community {
+            Some(slug) => {
+                let community = self.communities.find_by_slug(&slug.to_lowercase()).await?
+                    .ok_or_else(|| AppError::not_found("community not found"))?;
+                self.require_moderator(user_id, &community).await?;
+                Some(community.id)
+            }
+            None if self.is_site_moderator(user_id).await? => None,
+            None => return Err(AppError::forbidden("moderator role required")),
+        };
+
+        Ok(self.repo.held_queue(community_id, after, limit).await?)
+    }
+
+    /// Releases a post or comment the content filter or the spam check held. To turn it down
+    /// instead, remove it. Either way the decision counts towards the reputation of the domains it links to.
+    pub async fn approve(&self, moderator_id: Uuid, target_type: ReportTarget, target_id: Uuid) -> Result<(), AppError> {
+        let community = self.target_community(target_type, target_id).await?;
+        self.require_moderator(moderator_id, &community).await?;
+
+        let mut tx = self.store.begin().await?;
+        let approved = match target_type {
+            ReportTarget::Post => tx.approve_post(target_id).await?,
+            ReportTarget::Comment => tx.approve_comment(target_id).await?,
+        };
+        if !approved {
+            return Err(AppError::conflict(format!("this {} isn't held", target_type.as_str())));
+        }
+
+        let (action, after, released) = match target_type {
+            ReportTarget::Post => {
+                let post = tx.find_post(target_id).await?;
+                if let Some(post) = &post {
+                    tx.rate_domains(&post_domains(post), true).await?;
+                }
+                (ModActionKind::PostApprove, post.as_ref().and_then(snapshot), post)
+            }
+            ReportTarget::Comment => {
+                let comment = tx.find_comment(target_id).await?;
+                if let Some(comment) = &comment {
+                    tx.rate_domains(&spam::link_domains(None, &comment.body), true).await?;
+                }
+                (ModActionKind::CommentApprove, comment.as_ref().and_then(snapshot), None)
+            }
+        };
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action,
+            target_type: target_type.into(),
+            target_id,
+            community_id: Some(community.id),
+            reason: String::new(),
+            before: None,
+            after,
+        }).await?;
+        tx.commit().await?;
+
+        if let Some(post) = released {
+            let _ = self.events.send(PostEvent::Updated(post));
+        }
+        Ok(())
+    }
+
+    /// Applies `input.action` to the item and closes all of its open reports; returns how many were closed.
+    pub async fn resolve(&self, moderator_id: Uuid, target_type: ReportTarget, target_id: Uuid, input: ResolveInput) -> Result<u64, AppError> {
+        if input.note.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::validation(format!("Notes can be at most {MAX_NOTE_LEN} characters")));
+        }
+
+        let community_id = self.repo.open_report_community(target_type, target_id).await?
+            .ok_or_else(|| AppError::not_found("no open reports for this item"))?;
+        let community = self.communities.find_by_id(community_id).await?
+            .ok_or_else(|| AppError::not_found("community not found"))?;
+        self.require_moderator(moderator_id, &community).await?;
+
+        let mut tx = self.store.begin().await?;
+        let mut taken_down = false;
+        match input.action {
+            Resolution::Dismiss => {}
+            Resolution::Remove => {
+                taken_down = self.take_down(tx.as_mut(), moderator_id, community.id, target_type, target_id, &input.note).await?;
+            }
+            Resolution::Lock => {
+                let post_id = match target_type {
+                    ReportTarget::Post => target_id,
+                    ReportTarget::Comment => tx.find_comment(target_id).await?
+                        .ok_or_else(|| AppError::not_found("comment not found"))?
+                        .post_id,
+                };
+                if tx.lock_post(post_id).await? {
+                    tx.record(NewModAction {
+                        actor_id: moderator_id,
+                        action: ModActionKind::PostLock,
+                        target_type: ModTarget::Post,
+                        target_id: post_id,
+                        community_id: Some(community.id),
+                        reason: input.note.clone(),
+                        before: None,
+                        after: None,
+                    }).await?;
+                }
+            }
+            Resolution::BanAuthor => {
+                if !self.is_site_moderator(moderator_id).await? {
+                    return Err(AppError::forbidden("only site moderators can ban users"));
+                }
+                let author_id = self.repo.target_author(target_type, target_id).await?
+                    .ok_or_else(|| AppError::not_found("author not found"))?;
+                self.sanction_in(tx.as_mut(), moderator_id, author_id, SanctionInput {
+                    kind: SanctionKind::Ban,
+                    reason: input.note.clone(),
+                    duration_hours: None,
+                }).await?;
+            }
+        }
+
+        let resolved = tx.resolve_reports(target_type, target_id, moderator_id, input.action, &input.note).await?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: ModActionKind::ReportResolve,
+            target_type: target_type.into(),
+            target_id,
+            community_id: Some(community.id),
+            reason: input.note,
+            before: None,
+            after: Some(serde_json::json!({ "resolution": input.action, "reports_closed": resolved })),
+        }).await?;
+        tx.commit().await?;
+
+        if taken_down && target_type == ReportTarget::Post {
+            let _ = self.events.send(PostEvent::Deleted(target_id));
+        }
+        Ok(resolved)
+    }
+
+    /// Sanctions are site-wide, so only site moderators hand them out; moderators themselves can't be sanctioned.
+    pub async fn sanction(&self, moderator_id: Uuid, user_id: Uuid, input: SanctionInput) -> Result<Sanction, AppError> {
+        let mut tx = self.store.begin().await?;
+        let sanction = self.sanction_in(tx.as_mut(), moderator_id, user_id, input).await?;
+        tx.commit().await?;
+        Ok(sanction)
+    }
+
+    pub async fn sanctions(&self, moderator_id: Uuid, user_id: Uuid) -> Result<Vec<Sanction>, AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("moderator role required"));
+        }
+        Ok(self.sanctions.list_for(user_id).await?)
+    }
+
+    pub async fn revoke_sanction(&self, moderator_id: Uuid, sanction_id: Uuid) -> Result<Sanction, AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("moderator role required"));
+        }
+        let mut tx = self.store.begin().await?;
+        let sanction = tx.revoke_sanction(sanction_id, moderator_id).await?
+            .ok_or_else(|| AppError::not_found("sanction not found or already revoked"))?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: match sanction.kind {
+                SanctionKind::Ban => ModActionKind::Unban,
+                SanctionKind::Suspension => ModActionKind::Unsuspend,
+                SanctionKind::Shadowban => ModActionKind::Unshadowban,
+            },
+            target_type: ModTarget::User,
+            target_id: sanction.user_id,
+            community_id: None,
+            reason: String::new(),
+            before: None,
+            after: snapshot(&sanction),
+        }).await?;
+        tx.commit().await?;
+        Ok(sanction)
+    }
+
+    /// Accounts made after someone's ban that share an IP or an uncommon email domain with them.
+    pub async fn evasion_signals(&self, moderator_id: Uuid, limit: i64) -> Result<Vec<EvasionSignal>, AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("moderator role required"));
+        }
+        let common: Vec<String> = COMMON_EMAIL_DOMAINS.iter().map(|d| d.to_string()).collect();
+        Ok(self.sanctions.evasion_signals(&common, limit).await?)
+    }
+
+    /// Takes the post or comment down and closes any open reports on it; a removed comment
+    /// stays in its thread as a tombstone while it has replies.
+    pub async fn remove(&self, moderator_id: Uuid, target_type: ReportTarget, target_id: Uuid, reason: &str) -> Result<(), AppError> {
+        let reason = reason.trim();
+        if reason.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::validation(format!("Reasons can be at most {MAX_NOTE_LEN} characters")));
+        }
+        let community = self.target_community(target_type, target_id).await?;
+        self.require_moderator(moderator_id, &community).await?;
+
+        let mut tx = self.store.begin().await?;
+        if !self.take_down(tx.as_mut(), moderator_id, community.id, target_type, target_id, reason).await? {
+            return Err(AppError::conflict(format!("this {} is already removed", target_type.as_str())));
+        }
+        tx.resolve_reports(target_type, target_id, moderator_id, Resolution::Remove, reason).await?;
+        tx.commit().await?;
+
+        if target_type == ReportTarget::Post {
+            let _ = self.events.send(PostEvent::Deleted(target_id));
+        }
+        Ok(())
+    }
+
+    /// Undoes a moderator removal. Content its author deleted stays deleted.
+    pub async fn restore(&self, moderator_id: Uuid, target_type: ReportTarget, target_id: Uuid) -> Result<(), AppError> {
+        let community = self.target_community(target_type, target_id).await?;
+        self.require_moderator(moderator_id, &community).await?;
+
+        let mut tx = self.store.begin().await?;
+        let restored = match target_type {
+            ReportTarget::Post => tx.restore_post(target_id).await?,
+            ReportTarget::Comment => tx.restore_comment(target_id).await?,
+        };
+        if !restored {
+            return Err(AppError::conflict(format!("this {} wasn't removed by a moderator", target_type.as_str())));
+        }
+
+        let (action, after, restored_post) = match target_type {
+            ReportTarget::Post => {
+                let post = tx.find_post(target_id).await?;
+                (ModActionKind::PostRestore, post.as_ref().and_then(snapshot), post)
+            }
+            ReportTarget::Comment => {
+                let comment = tx.find_comment(target_id).await?;
+                (ModActionKind::CommentRestore, comment.as_ref().and_then(snapshot), None)
+            }
+        };
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action,
+            target_type: target_type.into(),
+            target_id,
+            community_id: Some(community.id),
+            reason: String::new(),
+            before: None,
+            after,
+        }).await?;
+        tx.commit().await?;
+
+        // back into search, unless the author deleted it in the meantime
+        if let Some(post) = restored_post {
+            let _ = self.events.send(PostEvent::Updated(post));
+        }
+        Ok(())
+    }
+
+    /// Moderators can edit any post in a community they moderate; the edit is logged with both versions.
+    /// Their edits always mark the post edited, grace window or not. With `expected_version`, the
+    /// edit fails unless the post is still at that version.
+    pub async fn edit_post(&self, moderator_id: Uuid, post_id: Uuid, input: EditPostInput, expected_version: Option<i32>) -> Result<Post, AppError> {
+        let reason = input.reason.trim();
+        if reason.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::validation(format!("Reasons can be at most {MAX_NOTE_LEN} characters")));
+        }
+        let before = self.posts.find_for_viewer(post_id, Some(moderator_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        let community = self.target_community(ReportTarget::Post, post_id).await?;
+        self.require_moderator(moderator_id, &community).await?;
+        posts_service::check_version(&before, expected_version)?;
+
+        let (tags, canonical_url) = posts_service::check_update(&input.title, &input.url, &input.body, &input.tags)?;
+        let edit = PostEdit { title: input.title, short_description: input.short_description, url: input.url, canonical_url, body: input.body, tags, hold_reason: None };
+        let mut tx = self.store.begin().await?;
+        let post = tx.edit_post(post_id, moderator_id, &edit, true, expected_version).await?
+            .ok_or_else(|| AppError::precondition_failed("the post was edited since you loaded it"))?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: ModActionKind::PostEdit,
+            target_type: ModTarget::Post,
+            target_id: post_id,
+            community_id: Some(community.id),
+            reason: reason.to_string(),
+            before: snapshot(&before),
+            after: snapshot(&post),
+        }).await?;
+        tx.commit().await?;
+
+        let _ = self.events.send(PostEvent::Updated(post.clone()));
+        Ok(post)
+    }
+
+    /// Locked posts take no new comments or votes until a moderator unlocks them.
+    pub async fn set_locked(&self, moderator_id: Uuid, post_id: Uuid, locked: bool, reason: &str) -> Result<Post, AppError> {
+        let reason = reason.trim();
+        if reason.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::validation(format!("Reasons can be at most {MAX_NOTE_LEN} characters")));
+        }
+        let community = self.target_community(ReportTarget::Post, post_id).await?;
+        self.require_moderator(moderator_id, &community).await?;
+
+        let mut tx = self.store.begin().await?;
+        let (changed, action) = match locked {
+            true => (tx.lock_post(post_id).await?, ModActionKind::PostLock),
+            false => (tx.unlock_post(post_id).await?, ModActionKind::PostUnlock),
+        };
+        if !changed {
+            return Err(AppError::conflict(if locked { "this post is already locked" } else { "this post isn't locked" }));
+        }
+        let post = tx.find_post(post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action,
+            target_type: ModTarget::Post,
+            target_id: post_id,
+            community_id: Some(community.id),
+            reason: reason.to_string(),
+            before: None,
+            after: None,
+        }).await?;
+        tx.commit().await?;
+
+        let _ = self.events.send(PostEvent::Updated(post.clone()));
+        Ok(post)
+    }
+
+    /// Pins sit above the site-wide feeds, so only site moderators place them. Without a
+    /// `position` the post goes after the last pin, and with one the pins from there on move down
+    /// to make room; pinning a pinned post moves it.
+    pub async fn pin(&self, moderator_id: Uuid, post_id: Uuid, position: Option<i32>) -> Result<Post, AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("only site moderators can pin posts"));
+        }
+        if position.is_some_and(|p| p < 1) {
+            return Err(AppError::validation("Pin position must be at least 1"));
+        }
+        let community = self.target_community(ReportTarget::Post, post_id).await?;
+        if community.visibility == Visibility::Private {
+            return Err(AppError::validation("Posts in private communities can't be pinned"));
+        }
+
+        let mut tx = self.store.begin().await?;
+        if !tx.pin_post(post_id, position).await? {
+            return Err(AppError::not_found("post not found"));
+        }
+        let post = tx.find_post(post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: ModActionKind::PostPin,
+            target_type: ModTarget::Post,
+            target_id: post_id,
+            community_id: Some(community.id),
+            reason: String::new(),
+            before: None,
+            after: Some(serde_json::json!({ "position": post.pin_position })),
+        }).await?;
+        tx.commit().await?;
+
+        let _ = self.events.send(PostEvent::Updated(post.clone()));
+        Ok(post)
+    }
+
+    pub async fn unpin(&self, moderator_id: Uuid, post_id: Uuid) -> Result<(), AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("only site moderators can pin posts"));
+        }
+        let community = self.target_community(ReportTarget::Post, post_id).await?;
+
+        let mut tx = self.store.begin().await?;
+        if !tx.unpin_post(post_id).await? {
+            return Err(AppError::conflict("this post isn't pinned"));
+        }
+        let post = tx.find_post(post_id).await?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: ModActionKind::PostUnpin,
+            target_type: ModTarget::Post,
+            target_id: post_id,
+            community_id: Some(community.id),
+            reason: String::new(),
+            before: None,
+            after: None,
+        }).await?;
+        tx.commit().await?;
+
+        if let Some(post) = post {
+            let _ = self.events.send(PostEvent::Updated(post));
+        }
+        Ok(())
+    }
+
+    /// Site moderators see the whole log; community moderators must name their community.
+    pub async fn log(
+        &self,
+        user_id: Uuid,
+        community: Option<&str>,
+        mut filter: ModLogFilter,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<Vec<ModAction>, AppError> {
+        filter.community_id = match community {
+            Some(slug) => {
+                let community = self.communities.find_by_slug(&slug.to_lowercase()).await?
+                    .ok_or_else(|| AppError::not_found("community not found"))?;
+                self.require_moderator(user_id, &community).await?;
+                Some(community.id)
+            }
+            None if self.is_site_moderator(user_id).await? => None,
+            None => return Err(AppError::forbidden("moderator role required")),
+        };
+
+        Ok(self.log.list(&filter, after, limit).await?)
+    }
+
+    /// The log as anyone may see it: no private communities, no shadowbans, and no moderator
+    /// names or snapshots of what was taken down.
+    pub async fn public_log(
+        &self,
+        community: Option<&str>,
+        mut filter: ModLogFilter,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<Vec<ModAction>, AppError> {
+        if let Some(slug) = community {
+            let community = self.communities.find_by_slug(&slug.to_lowercase()).await?
+                .filter(|c| c.can_read(None))
+                .ok_or_else(|| AppError::not_found("community not found"))?;
+            filter.community_id = Some(community.id);
+        }
+        filter.actor_id = None;
+        filter.public = true;
+
+        let entries = self.log.list(&filter, after, limit).await?;
+        Ok(entries.into_iter().map(|entry| ModAction {
+            actor_id: None,
+            actor_username: None,
+            before: None,
+            after: None,
+            ..entry
+        }).collect())
+    }
+
+    /// Returns false if the item was already removed. The caller commits `tx` and tells listeners.
+    /// Only removals of held or spam-reported items count against the domains they link to; a post
+    /// taken down for harassment says nothing about its link.
+    async fn take_down(&self, tx: &mut dyn ModerationTx, moderator_id: Uuid, community_id: Uuid, target_type: ReportTarget, target_id: Uuid, reason: &str) -> Result<bool, AppError> {
+        let spam_reported = tx.spam_reported(target_type, target_id).await?;
+        let (action, before, removed) = match target_type {
+            ReportTarget::Post => {
+                let before = match tx.find_post(target_id).await? {
+                    Some(post) => Some(post),
+                    None => tx.find_held_post(target_id).await?,
+                };
+                let removed = tx.remove_post(target_id, moderator_id, reason).await?;
+                if let Some(post) = before.as_ref().filter(|p| removed && (spam_reported || p.held_at.is_some())) {
+                    tx.rate_domains(&post_domains(post), false).await?;
+                }
+                (ModActionKind::PostRemove, before.as_ref().and_then(snapshot), removed)
+            }
+            ReportTarget::Comment => {
+                let before = tx.find_comment(target_id).await?;
+                let removed = tx.remove_comment(target_id, moderator_id, reason).await?;
+                if let Some(comment) = before.as_ref().filter(|c| removed && (spam_reported || c.held_at.is_some())) {
+                    tx.rate_domains(&spam::link_domains(None, &comment.body), false).await?;
+                }
+                (ModActionKind::CommentRemove, before.as_ref().and_then(snapshot), removed)
+            }
+        };
+        if removed {
+            tx.record(NewModAction {
+                actor_id: moderator_id,
+                action,
+                target_type: target_type.into(),
+                target_id,
+                community_id: Some(community_id),
+                reason: reason.to_string(),
+                before,
+                after: None,
+            }).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Everything `sanction` does short of committing.
+    async fn sanction_in(&self, tx: &mut dyn ModerationTx, moderator_id: Uuid, user_id: Uuid, input: SanctionInput) -> Result<Sanction, AppError> {
+        if !self.is_site_moderator(moderator_id).await? {
+            return Err(AppError::forbidden("moderator role required"));
+        }
+        let reason = input.reason.trim();
+        if reason.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::validation(format!("Reasons can be at most {MAX_NOTE_LEN} characters")));
+        }
+        let expires_at = match input.duration_hours {
+            Some(hours) if hours <= 0 => return Err(AppError::validation("duration_hours must be positive")),
+            Some(hours) => Some(Utc::now() + Duration::hours(hours)),
+            None => None,
+        };
+
+        let user = self.users.find_by_id(user_id).await?
+            .ok_or_else(|| AppError::not_found("user not found"))?;
+        if user.role.is_moderator() {
+            return Err(AppError::forbidden("moderators can't be sanctioned"));
+        }
+
+        let sanction = tx.create_sanction(user.id, input.kind, reason, moderator_id, expires_at).await?;
+        tx.record(NewModAction {
+            actor_id: moderator_id,
+            action: match sanction.kind {
+                SanctionKind::Ban => ModActionKind::Ban,
+                SanctionKind::Suspension => ModActionKind::Suspend,
+                SanctionKind::Shadowban => ModActionKind::Shadowban,
+            },
+            target_type: ModTarget::User,
+            target_id: user.id,
+            community_id: None,
+            reason: reason.to_string(),
+            before: None,
+            after: snapshot(&sanction),
+        }).await?;
+        Ok(sanction)
+    }
+
+    async fn target_community(&self, target_type: ReportTarget, target_id: Uuid) -> Result<Community, AppError> {
+        let not_found = || AppError::not_found(format!("{} not found", target_type.as_str()));
+        let community_id = self.repo.target_community(target_type, target_id).await?
+            .ok_or_else(not_found)?;
+        self.communities.find_by_id(community_id).await?
+            .ok_or_else(not_found)
+    }
+
+    /// The post's community, if `user_id` may read it; otherwise the post is treated as missing.
+    async fn readable_community(&self, user_id: Uuid, post: &Post) -> Result<Community, AppError> {
+        let community = self.communities.find_by_slug(&post.community).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        if !community.can_read(self.communities.member_role(community.id, user_id).await?) {
+            return Err(AppError::not_found("post not found"));
+        }
+        Ok(community)
+    }
+
+    async fn require_moderator(&self, user_id: Uuid, community: &Community) -> Result<(), AppError> {
+        if matches!(self.communities.member_role(community.id, user_id).await?, Some(role) if role >= MemberRole::Moderator)
+            || self.is_site_moderator(user_id).await?
+        {
+            return Ok(());
+        }
+        Err(AppError::forbidden("community moderator role required"))
+    }
+
+    async fn is_site_moderator(&self, user_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.users.find_by_id(user_id).await?.is_some_and(|u| u.role.is_moderator()))
+    }
+}
+
+fn post_domains(post: &Post) -> Vec<String> {
+    spam::link_domains(post.url.as_deref(), post.body.as_deref().unwrap_or_default())
+}
+
+fn snapshot<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(value).ok()
+}
+
+fn validate_report(input: &ReportInput) -> Result<&str, AppError> {
+    let details = input.details.trim();
+    if details.chars().count() > MAX_DETAILS_LEN {
+        return Err(AppError::validation(format!("Details can be at most {MAX_DETAILS_LEN} characters")));
+    }
+    if input.reason == ReportReason::Other && details.is_empty() {
+        return Err(AppError::validation("Say what is wrong when reporting for another reason"));
+    }
+    Ok(details)
+}
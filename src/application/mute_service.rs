@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::posts_service;
+use crate::application::utils::validation;
+use crate::domain::communities::CommunityRepository;
+use crate::domain::mutes::{HiddenPost, MuteRepository, MutedDomain, MutedUser};
+use crate::domain::posts::{Post, PostRepository};
+
+#[derive(Debug, Deserialize)]
+pub struct MuteDomainInput {
+    pub domain: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Mutes {
+    pub users: Vec<MutedUser>,
+    pub domains: Vec<MutedDomain>,
+}
+
+/// A page of hidden posts; `next` follows the hides themselves so deleted posts don't end paging early.
+pub struct HiddenPage {
+    pub posts: Vec<HiddenPost>,
+    pub next: Option<(DateTime<Utc>, Uuid)>,
+}
+
+pub struct MuteService {
+    repo: Arc<dyn MuteRepository>,
+    posts: Arc<dyn PostRepository>,
+    communities: Arc<dyn CommunityRepository>,
+}
+
+impl MuteService {
+    pub fn new(repo: Arc<dyn MuteRepository>, posts: Arc<dyn PostRepository>, communities: Arc<dyn CommunityRepository>) -> Self {
+        Self { repo, posts, communities }
+    }
+
+    /// Only posts the user can read can be hidden. Hiding a post twice is not an error.
+    pub async fn hide_post(&self, user_id: Uuid, post_id: Uuid) -> Result<(), AppError> {
+        let post = self.posts.find_for_viewer(post_id, Some(user_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        posts_service::check_readable(self.communities.as_ref(), Some(user_id), &post).await?;
+        self.repo.hide_post(user_id, post_id).await?;
+        Ok(())
+    }
+
+    pub async fn unhide_post(&self, user_id: Uuid, post_id: Uuid) -> Result<(), AppError> {
+        if !self.repo.unhide_post(user_id, post_id).await? {
+            return Err(AppError::not_found("post is not hidden"));
+        }
+        Ok(())
+    }
+
+    /// Most recently hidden first.
+    pub async fn hidden(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> Result<HiddenPage, AppError> {
+        let entries = self.repo.list_hidden(user_id, after, limit).await?;
+        let next = entries.last()
+            .filter(|_| entries.len() as i64 >= limit)
+            .map(|e| (e.hidden_at, e.post_id));
+
+        let ids: Vec<Uuid> = entries.iter().map(|e| e.post_id).collect();
+        let mut posts: HashMap<Uuid, Post> = self.posts.find_by_ids(&ids).await?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        let posts = entries.into_iter()
+            .filter_map(|e| posts.remove(&e.post_id).map(|post| HiddenPost { post, hidden_at: e.hidden_at }))
+            .collect();
+        Ok(HiddenPage { posts, next })
+    }
+
+    pub async fn mute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> Result<(), AppError> {
+        if user_id == muted_user_id {
+            return Err(AppError::validation("You cannot mute yourself"));
+        }
+        self.repo.mute_user(user_id, muted_user_id).await?;
+        Ok(())
+    }
+
+    pub async fn unmute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> Result<(), AppError> {
+        if !self.repo.unmute_user(user_id, muted_user_id).await? {
+            return Err(AppError::not_found("user is not muted"));
+        }
+        Ok(())
+    }
+
+    /// Muting a domain also mutes its subdomains.
+    pub async fn mute_domain(&self, user_id: Uuid, input: MuteDomainInput) -> Result<String, AppError> {
+        let domain = validation::normalize_domain(&input.domain);
+        validation::validate_domain(&domain)
+            .map_err(|_| AppError::validation("Domain must be a hostname like example.com"))?;
+        self.repo.mute_domain(user_id, &domain).await?;
+        Ok(domain)
+    }
+
+    pub async fn unmute_domain(&self, user_id: Uuid, domain: &str) -> Result<(), AppError> {
+        let domain = validation::normalize_domain(domain);
+        if !self.repo.unmute_domain(user_id, &domain).await? {
+            return Err(AppError::not_found("domain is not muted"));
+        }
+        Ok(())
+    }
+
+    pub async fn mutes(&self, user_id: Uuid) -> Result<Mutes, AppError> {
+        Ok(Mutes {
+            users: self.repo.list_muted_users(user_id).await?,
+            domains: self.repo.list_muted_domains(user_id).await?,
+        })
+    }
+}
@@ -1,13 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration as StdDuration;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use validator::Validate;
 
-use crate::domain::posts::{Post, PostRepository};
+use tokio::sync::broadcast;
+
+use crate::domain::communities::{Community, CommunityRepository, MemberRole, DEFAULT_COMMUNITY_SLUG};
+use crate::domain::content_filter::ContentFilter;
+use crate::domain::domains::DomainRepository;
+use crate::domain::follows::FollowRepository;
+use crate::domain::posts::{FeedCursor, FeedSort, NewPost, Post, PostEdit, PostEvent, PostRepository, PostView, ViewerAnnotations};
 use crate::application::error::AppError;
-use crate::application::utils::{validation, profanity};
+use crate::application::{domain_service, tag_service};
+use crate::application::utils::{content_policy, validation, url_canon};
+use crate::application::utils::spam::SpamScorer;
+
+/// The same link can be submitted to a community again once this many days have passed.
+pub const RESUBMIT_AFTER_DAYS: i64 = 30;
 
 #[derive(Debug, Validate, Deserialize)]
 pub struct CreatePostInput {
@@ -15,89 +28,528 @@ pub struct CreatePostInput {
     pub title: String,
     pub short_description: String,
     pub url: Option<String>,
-    pub body: Option<String>
+    pub body: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Slug of the community to post in; defaults to the general community.
+    pub community: Option<String>,
+}
+
+/// A full edit through PUT. Leaving `tags` out keeps the post's tags; an empty list clears them.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePostInput {
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// A partial edit: fields left out keep their value, and `url` or `body` set to null is cleared.
+/// The result must still have exactly one of `url` and `body`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PatchPostInput {
+    pub title: Option<String>,
+    pub short_description: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub body: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Tells a field sent as null (`Some(None)`) apart from one left out (`None`).
+fn present<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 pub struct PostService {
     repo: Arc<dyn PostRepository>,
+    communities: Arc<dyn CommunityRepository>,
+    follows: Arc<dyn FollowRepository>,
+    domains: Arc<dyn DomainRepository>,
+    filter: Arc<dyn ContentFilter>,
+    spam: Arc<SpamScorer>,
+    /// Edits this soon after posting don't set `edited_at`.
+    edit_grace: Duration,
+    events: broadcast::Sender<PostEvent>,
+}
+
+/// Outcome of a submission: a link posted to the same community within
+/// `RESUBMIT_AFTER_DAYS` resolves to the existing post instead of a new one.
+#[derive(Debug)]
+pub enum Submission {
+    Created(Post),
+    Duplicate(Post),
+}
+
+/// A page of the home feed. Users who follow nothing yet get the site-wide hot feed instead.
+#[derive(Debug, Serialize)]
+pub struct HomeFeed {
+    pub posts: Vec<Post>,
+    pub personalized: bool,
+}
+
+/// The repositories `PostService` reads and writes through.
+pub struct PostRepos {
+    pub posts: Arc<dyn PostRepository>,
+    pub communities: Arc<dyn CommunityRepository>,
+    pub follows: Arc<dyn FollowRepository>,
+    pub domains: Arc<dyn DomainRepository>,
 }
 
 impl PostService {
-    pub fn new(repo: Arc<dyn PostRepository>) -> Self { Self { repo }}
+    pub fn new(
+        repos: PostRepos,
+        filter: Arc<dyn ContentFilter>,
+        spam: Arc<SpamScorer>,
+        edit_grace: Duration,
+        events: broadcast::Sender<PostEvent>,
+    ) -> Self {
+        let PostRepos { posts: repo, communities, follows, domains } = repos;
+        Self { repo, communities, follows, domains, filter, spam, edit_grace, events }
+    }
 
-    pub async fn create(&self, user_id: Uuid, input: CreatePostInput) -> Result<Post, AppError> {
+    /// A post the content filter, a domain rule or the spam check holds is created all the same, but stays out
+    /// of sight until a moderator approves it.
+    pub async fn create(&self, user_id: Uuid, mut input: CreatePostInput) -> Result<Submission, AppError> {
         input.validate().map_err(|e| AppError::validation(e.to_string()))?;
 
-        let has_url = input.url.as_ref().is_some();
-        let has_body = input.body.as_ref().is_some();
+        check_content(&input.url, &input.body)?;
 
-        if !has_url && !has_body {
-            return Err(AppError::validation("Either url or body must be provided".to_string()));
+        let tags = tag_service::normalize_tags(&input.tags)?;
+
+        let slug = input.community.as_deref().unwrap_or(DEFAULT_COMMUNITY_SLUG).to_lowercase();
+        let community = self.communities.find_by_slug(&slug).await?
+            .ok_or_else(|| AppError::not_found("community not found"))?;
+        if !community.can_post(self.communities.member_role(community.id, user_id).await?) {
+            return Err(AppError::forbidden("only members can post in this community"));
         }
+        let domain_reason = domain_service::check_link(self.domains.as_ref(), user_id, input.url.as_deref()).await?;
+        let filter_reason = self.screen(&community.slug, &mut input.title, &mut input.short_description, &mut input.body)?;
 
-        if !has_url && input.body.as_ref().unwrap().trim().is_empty() {
-            return Err(AppError::validation("Body cannot be empty if url is not provided".to_string()));
+        let canonical_url = input.url.as_deref().and_then(url_canon::canonicalize);
+        if let Some(canonical) = &canonical_url {
+            let since = Utc::now() - Duration::days(RESUBMIT_AFTER_DAYS);
+            if let Some(existing) = self.repo.find_by_canonical_url(community.id, canonical, since).await? {
+                return Ok(Submission::Duplicate(existing));
+            }
         }
 
-        validation::validate_http_url(&input.url)
-            .map_err(|e| AppError::validation(e.to_string()))?;
+        let text = [Some(&input.title), input.url.as_ref(), input.body.as_ref()].into_iter().flatten()
+            .map(String::as_str).collect::<Vec<_>>().join("\n");
+        let spam_reason = self.spam.check(user_id, &text, input.url.as_deref()).await?;
+        let hold_reason = [filter_reason, domain_reason, spam_reason].into_iter().flatten().reduce(|a, b| format!("{a}; {b}"));
 
-        if profanity::contains_profanity(&input.title) {
-            return Err(AppError::validation("Title contains inappropriate language".to_string()));
+        let post = self.repo.create(&NewPost {
+            user_id,
+            community_id: community.id,
+            title: input.title,
+            short_description: input.short_description,
+            url: input.url,
+            canonical_url,
+            body: input.body,
+            tags,
+            hold_reason,
+        }).await?;
+        if post.held_at.is_none() {
+            // nobody listening is fine
+            let _ = self.events.send(PostEvent::Created(post.clone()));
         }
 
-        if let Some(body) = &input.body {
-            if profanity::contains_profanity(body) {
-                return Err(AppError::validation("Body contains inappropriate language".to_string()));
-            }
+        Ok(Submission::Created(post))
+    }
+
+    /// Authors can edit their own posts while they are open; moderators edit them through the moderation service.
+    /// A new link goes through the domain rules again. An edit the content filter or a domain rule
+    /// holds takes the post out of sight until a moderator approves it. The replaced text is kept
+    /// as a revision, and the edit and the hold are written together. With `expected_version`, the edit
+    /// fails unless the post is still at that version.
+    pub async fn update(&self, user_id: Uuid, post_id: Uuid, input: UpdatePostInput, expected_version: Option<i32>) -> Result<Post, AppError> {
+        let existing = self.repo.find_for_viewer(post_id, Some(user_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        if existing.user_id != user_id {
+            return Err(AppError::forbidden("you can only edit your own posts"));
         }
+        check_open(&existing)?;
+        check_version(&existing, expected_version)?;
 
-        let post = self.repo.create(user_id, &input.title, &input.short_description,&input.url, &input.body).await?;
+        let UpdatePostInput { mut title, mut short_description, url, mut body, tags } = input;
+        let (tags, canonical_url) = check_update(&title, &url, &body, tags.as_deref().unwrap_or(&existing.tags))?;
+        let domain_reason = match url != existing.url {
+            true => domain_service::check_link(self.domains.as_ref(), user_id, url.as_deref()).await?,
+            false => None,
+        };
+        let filter_reason = self.screen(&existing.community, &mut title, &mut short_description, &mut body)?;
+        let hold_reason = [filter_reason, domain_reason].into_iter().flatten().reduce(|a, b| format!("{a}; {b}"));
 
+        let mark_edited = Utc::now() - existing.created_at > self.edit_grace;
+        let held = hold_reason.is_some();
+        let edit = PostEdit { title, short_description, url, canonical_url, body, tags, hold_reason };
+        let post = self.repo.update(post_id, user_id, &edit, mark_edited, expected_version).await?
+            .ok_or_else(stale)?;
+        match held {
+            true if existing.held_at.is_none() => {
+                let _ = self.events.send(PostEvent::Deleted(post_id));
+            }
+            true => {}
+            false => {
+                let _ = self.events.send(PostEvent::Updated(post.clone()));
+            }
+        }
         Ok(post)
     }
 
-    pub async fn update(&self, post_id: Uuid, title: &str, short_description: &str, url: &Option<String>, body: &Option<String>) -> Result<Post, AppError> {
-        if (title.len() < 3 || title.len() > 300) {
-            return Err(AppError::validation("Title must be between 3 and 300 characters".to_string()));
-        }
-
-        let has_url = url.as_ref().is_some();
-        let has_body = body.as_ref().is_some();
+    /// Applies a partial edit on top of the post as stored, then goes through `update`. Without
+    /// `expected_version`, the edit still fails if the post changes after the fields were merged.
+    pub async fn patch(&self, user_id: Uuid, post_id: Uuid, input: PatchPostInput, expected_version: Option<i32>) -> Result<Post, AppError> {
+        let existing = self.repo.find_for_viewer(post_id, Some(user_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        let merged = UpdatePostInput {
+            title: input.title.unwrap_or(existing.title),
+            short_description: input.short_description.or(existing.short_description).unwrap_or_default(),
+            url: input.url.unwrap_or(existing.url),
+            body: input.body.unwrap_or(existing.body),
+            tags: Some(input.tags.unwrap_or(existing.tags)),
+        };
+        let expected_version = expected_version.or(Some(existing.version));
+        self.update(user_id, post_id, merged, expected_version).await
+    }
 
-        if !has_url && !has_body {
-            return Err(AppError::validation("Either url or body must be provided".to_string()));
-        }
+    /// One post as `viewer` sees it; posts in private communities are only found by their members,
+    /// and held ones by their author and moderators.
+    pub async fn get(&self, viewer: Option<Uuid>, post_id: Uuid) -> Result<PostView, AppError> {
+        let post = self.repo.find_for_viewer(post_id, viewer).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        check_readable(self.communities.as_ref(), viewer, &post).await?;
+        let mut views = self.annotate(viewer, vec![post]).await?;
+        views.pop().ok_or_else(|| AppError::not_found("post not found"))
+    }
 
-        let post = self.repo.update(post_id, title, short_description, url, body).await?;
-        Ok(post)
+    /// Masks the fields in place; see `content_policy::screen`.
+    fn screen(&self, community: &str, title: &mut String, short_description: &mut String, body: &mut Option<String>) -> Result<Option<String>, AppError> {
+        let mut fields = vec![title, short_description];
+        fields.extend(body.as_mut());
+        content_policy::screen(self.filter.as_ref(), community, &mut fields)
     }
     
-    pub async fn delete(&self, post_id: Uuid) -> Result<(), AppError> {
-        Ok(self.repo.delete(post_id).await?)
+    /// Authors can delete their own posts; moderators remove them through the moderation service.
+    /// The post's comments and votes are kept.
+    pub async fn delete(&self, user_id: Uuid, post_id: Uuid) -> Result<(), AppError> {
+        let post = self.repo.find_for_viewer(post_id, Some(user_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        if post.user_id != user_id {
+            return Err(AppError::forbidden("you can only delete your own posts"));
+        }
+        if self.repo.delete(post_id).await? {
+            let _ = self.events.send(PostEvent::Deleted(post_id));
+        }
+        Ok(())
     }
 
+    /// Only posts the user can read take votes. Locked and archived posts take no new votes, but a vote
+    /// on them can still be withdrawn with 0. The new score goes out as an update, since search ranks by it.
     pub async fn vote_post(&self, user_id: Uuid, post_id: Uuid, value: i16) -> Result<(i32, DateTime<Utc>), AppError> {
         if value != 1 && value != -1 && value != 0 {
             return Err(AppError::validation("Vote value must be 1 (upvote), -1 (downvote), or 0 (remove vote)".to_string()));
         }
-        let result = self.repo.upsert_vote_and_recompute(user_id, post_id, value).await?;
-        Ok(result)
+        let mut post = self.repo.find_for_viewer(post_id, Some(user_id)).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        check_readable(self.communities.as_ref(), Some(user_id), &post).await?;
+        if value != 0 {
+            check_open(&post)?;
+        }
+        let (score, hot_at) = self.repo.upsert_vote_and_recompute(user_id, post_id, value).await?;
+        (post.score, post.hot_at) = (score, hot_at);
+        let _ = self.events.send(PostEvent::Updated(post));
+        Ok((score, hot_at))
     }
 
+    /// The first page starts with the pinned posts, on top of `limit` others; later pages leave them out.
     pub async fn list_new(
         &self,
+        viewer: Option<Uuid>,
         after: Option<(DateTime<Utc>, Uuid)>,
         limit: i64
     ) -> Result<Vec<Post>, AppError> {
-        Ok(self.repo.list_new(after, limit).await?)
+        let posts = self.repo.list_new(viewer, after, limit).await?;
+        self.with_pins(viewer, after.is_none(), posts).await
     }
 
+    /// Pinned posts come first, as in `list_new`.
     pub async fn list_top(
         &self,
+        viewer: Option<Uuid>,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Post>, AppError> {
+        let posts = self.repo.list_top(viewer, after, limit).await?;
+        self.with_pins(viewer, after.is_none(), posts).await
+    }
+
+    async fn with_pins(&self, viewer: Option<Uuid>, first_page: bool, posts: Vec<Post>) -> Result<Vec<Post>, AppError> {
+        if !first_page {
+            return Ok(posts);
+        }
+        let mut pinned = self.repo.list_pinned(viewer).await?;
+        pinned.extend(posts);
+        Ok(pinned)
+    }
+
+    /// Archives posts older than `archive_after` every `every`, starting right away.
+    pub fn spawn_archiver(self: Arc<Self>, archive_after: Duration, every: StdDuration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                match self.repo.archive_older_than(Utc::now() - archive_after).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(posts = n, "archived old posts"),
+                    Err(e) => tracing::error!(error = ?e, "post archive sweep failed"),
+                }
+            }
+        })
+    }
+
+    pub async fn list_by_author(
+        &self,
+        user_id: Uuid,
+        viewer: Option<Uuid>,
+        sort: FeedSort,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Post>, AppError> {
+        Ok(self.repo.list_by_author(user_id, viewer, sort, after, limit).await?)
+    }
+
+    pub async fn list_by_tag(
+        &self,
+        tag_id: Uuid,
+        viewer: Option<Uuid>,
+        sort: FeedSort,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Post>, AppError> {
+        Ok(self.repo.list_by_tag(tag_id, viewer, sort, after, limit).await?)
+    }
+
+    /// Private communities are only readable by their members.
+    pub async fn list_by_community(
+        &self,
+        viewer: Option<Uuid>,
+        community: &Community,
+        sort: FeedSort,
+        after: Option<FeedCursor>,
+        limit: i64
+    ) -> Result<Vec<Post>, AppError> {
+        let role = match viewer {
+            Some(user_id) => self.communities.member_role(community.id, user_id).await?,
+            None => None,
+        };
+        if !community.can_read(role) {
+            return Err(AppError::forbidden("this community is private"));
+        }
+        Ok(self.repo.list_by_community(community.id, viewer, sort, after, limit).await?)
+    }
+
+    /// Hot-ranked posts from followed authors and tags and joined communities; `after` is a `(hot_at, id)` cursor.
+    pub async fn home_feed(
+        &self,
+        user_id: Uuid,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<HomeFeed, AppError> {
+        if !self.follows.has_subscriptions(user_id).await? {
+            let posts = self.repo.list_hot(Some(user_id), after, limit).await?;
+            return Ok(HomeFeed { posts, personalized: false });
+        }
+        let posts = self.repo.list_home(user_id, after, limit).await?;
+        Ok(HomeFeed { posts, personalized: true })
+    }
+
+    /// Adds the viewer's vote, saved and hidden state to a page of posts with one extra query.
+    /// Anonymous viewers get the posts as they are.
+    pub async fn annotate(&self, viewer: Option<Uuid>, posts: Vec<Post>) -> Result<Vec<PostView>, AppError> {
+        let Some(user_id) = viewer else {
+            return Ok(posts.into_iter().map(|post| PostView { post, viewer: None }).collect());
+        };
+
+        let ids: Vec<Uuid> = posts.iter().map(|p| p.id).collect();
+        let mut states: HashMap<Uuid, _> = self.repo.viewer_states(user_id, &ids).await?
+            .into_iter()
+            .map(|s| (s.post_id, s))
+            .collect();
+
+        Ok(posts.into_iter()
+            .map(|post| {
+                let state = states.remove(&post.id);
+                let viewer = ViewerAnnotations {
+                    my_vote: state.as_ref().map_or(0, |s| s.my_vote),
+                    saved: state.as_ref().is_some_and(|s| s.saved),
+                    hidden: state.as_ref().is_some_and(|s| s.hidden),
+                    can_edit: post.user_id == user_id,
+                };
+                PostView { post, viewer: Some(viewer) }
+            })
+            .collect())
+    }
+
+    pub async fn list_upvoted_by(
+        &self,
+        user_id: Uuid,
         after: Option<(DateTime<Utc>, Uuid)>,
         limit: i64
     ) -> Result<Vec<Post>, AppError> {
-        Ok(self.repo.list_top(after, limit).await?)
+        Ok(self.repo.list_upvoted_by(user_id, after, limit).await?)
+    }
+}
+
+/// The post's community and `viewer`'s role in it. A post in a private community is not found
+/// by anyone but its members, and one made under a shadowban by anyone but its author.
+pub async fn check_readable(communities: &dyn CommunityRepository, viewer: Option<Uuid>, post: &Post) -> Result<(Community, Option<MemberRole>), AppError> {
+    if post.author_shadowbanned && viewer != Some(post.user_id) {
+        return Err(AppError::not_found("post not found"));
+    }
+    let community = communities.find_by_slug(&post.community).await?
+        .ok_or_else(|| AppError::not_found("post not found"))?;
+    let role = match viewer {
+        Some(user_id) => communities.member_role(community.id, user_id).await?,
+        None => None,
+    };
+    if !community.can_read(role) {
+        return Err(AppError::not_found("post not found"));
+    }
+    Ok((community, role))
+}
+
+/// Fails unless the post still takes comments and votes.
+pub fn check_open(post: &Post) -> Result<(), AppError> {
+    if post.locked_at.is_some() {
+        return Err(AppError::forbidden("this post is locked"));
+    }
+    if post.archived_at.is_some() {
+        return Err(AppError::forbidden("this post is archived"));
+    }
+    Ok(())
+}
+
+/// Fails unless `expected_version`, when given, is the post's current version.
+pub fn check_version(post: &Post, expected_version: Option<i32>) -> Result<(), AppError> {
+    match expected_version {
+        Some(version) if version != post.version => Err(stale()),
+        _ => Ok(()),
+    }
+}
+
+fn stale() -> AppError {
+    AppError::precondition_failed("the post was edited since you loaded it")
+}
+
+/// A post links somewhere or has a text body, never both; the `posts` table checks the same.
+fn check_content(url: &Option<String>, body: &Option<String>) -> Result<(), AppError> {
+    match (url, body) {
+        (Some(_), Some(_)) => return Err(AppError::validation("A post has either a url or a body, not both")),
+        (None, None) => return Err(AppError::validation("Either url or body must be provided".to_string())),
+        (None, Some(body)) if body.trim().is_empty() => {
+            return Err(AppError::validation("Body cannot be empty if url is not provided".to_string()));
+        }
+        _ => {}
     }
-}
\ No newline at end of file
+    validation::validate_http_url(url).map_err(|e| AppError::validation(e.to_string()))
+}
+
+/// Validates an edit; returns the normalized tags and the canonical form of `url`.
+pub fn check_update(title: &str, url: &Option<String>, body: &Option<String>, tags: &[String]) -> Result<(Vec<String>, Option<String>), AppError> {
+    if (title.len() < 3 || title.len() > 300) {
+        return Err(AppError::validation("Title must be between 3 and 300 characters".to_string()));
+    }
+
+    check_content(url, body)?;
+
+    let tags = tag_service::normalize_tags(tags)?;
+    let canonical_url = url.as_deref().and_then(url_canon::canonicalize);
+    Ok((tags, canonical_url))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{Duration, Utc};
+    use tokio::sync::broadcast;
+    use uuid::Uuid;
+
+    use super::{PostRepos, PostService, UpdatePostInput};
+    use crate::application::error::AppError;
+    use crate::application::test_support::{post, MemoryCommunities, MemoryPosts, Unused};
+    use crate::application::utils::spam::SpamScorer;
+    use crate::domain::communities::{CommunityRepository, MemberRole, Visibility};
+    use crate::domain::content_filter::{ContentFilter, FilterAction, FilterMatch, Verdict};
+    use crate::domain::posts::Post;
+
+    /// Holds any text containing its word.
+    struct HoldWord(&'static str);
+
+    impl ContentFilter for HoldWord {
+        fn check(&self, _: Option<&str>, text: &str) -> Verdict {
+            let matches: Vec<FilterMatch> = text.contains(self.0).then(|| FilterMatch { term: self.0.to_string(), action: FilterAction::Hold }).into_iter().collect();
+            Verdict { action: matches.first().map(|m| m.action), matches, text: text.to_string() }
+        }
+    }
+
+    fn service(posts: Arc<MemoryPosts>, communities: Arc<dyn CommunityRepository>) -> PostService {
+        let repos = PostRepos { posts, communities, follows: Arc::new(Unused), domains: Arc::new(Unused) };
+        PostService::new(repos, Arc::new(HoldWord("casino")), Arc::new(SpamScorer::new(Arc::new(Unused), 1.0)), Duration::minutes(5), broadcast::channel(16).0)
+    }
+
+    fn edit(existing: &Post, title: &str) -> UpdatePostInput {
+        UpdatePostInput { title: title.to_string(), short_description: String::new(), url: existing.url.clone(), body: None, tags: None }
+    }
+
+    #[tokio::test]
+    async fn a_held_edit_returns_the_version_it_was_stored_at() {
+        let author = Uuid::new_v4();
+        let existing = post(author, Duration::hours(1));
+        let service = service(Arc::new(MemoryPosts::with(vec![existing.clone()])), Arc::new(Unused));
+
+        let held = service.update(author, existing.id, edit(&existing, "Best casino in town"), Some(1)).await.unwrap();
+        assert!(held.held_at.is_some());
+        assert_eq!(held.version, 2);
+
+        let fixed = service.update(author, existing.id, edit(&existing, "Best cafe in town"), Some(held.version)).await.unwrap();
+        assert_eq!(fixed.version, 3);
+    }
+
+    #[tokio::test]
+    async fn only_members_vote_on_posts_in_private_communities() {
+        let (author, member, outsider) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut existing = post(author, Duration::hours(1));
+        existing.community = "staff".to_string();
+        existing.community_visibility = Visibility::Private;
+        let communities = MemoryCommunities::private("staff", vec![(author, MemberRole::Owner), (member, MemberRole::Member)]);
+        let posts = Arc::new(MemoryPosts::with(vec![existing.clone()]));
+        let service = service(posts.clone(), Arc::new(communities));
+
+        let refused = service.vote_post(outsider, existing.id, 1).await;
+        assert!(matches!(refused, Err(AppError::NotFound(_))), "{refused:?}");
+        assert_eq!(posts.posts.lock().unwrap()[0].score, 0);
+
+        let (score, _) = service.vote_post(member, existing.id, 1).await.unwrap();
+        assert_eq!(score, 1);
+    }
+
+    #[tokio::test]
+    async fn locked_and_archived_posts_cant_be_edited() {
+        let author = Uuid::new_v4();
+        let mut locked = post(author, Duration::hours(1));
+        locked.locked_at = Some(Utc::now());
+        let mut archived = post(author, Duration::days(200));
+        archived.archived_at = Some(Utc::now());
+        let service = service(Arc::new(MemoryPosts::with(vec![locked.clone(), archived.clone()])), Arc::new(Unused));
+
+        for existing in [&locked, &archived] {
+            let refused = service.update(author, existing.id, edit(existing, "A new title"), None).await;
+            assert!(matches!(refused, Err(AppError::Forbidden(_))), "{refused:?}");
+        }
+    }
+}
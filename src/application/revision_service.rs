@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::utils::text_diff;
+use crate::config::RevisionConfig;
+use crate::domain::comments::CommentRepository;
+use crate::domain::communities::{Community, CommunityRepository, MemberRole};
+use crate::domain::posts::PostRepository;
+use crate::domain::revisions::RevisionRepository;
+use crate::domain::users::UserRepository;
+
+/// One version of a post, oldest first; the last is the post as it stands.
+#[derive(Debug, Serialize)]
+pub struct PostVersion {
+    /// Starts at 1 for the version originally posted.
+    pub version: usize,
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    /// When and by whom this version replaced the one before it; unset for the original.
+    pub edited_at: Option<DateTime<Utc>>,
+    pub edited_by: Option<String>,
+    /// Unified diffs against the version before, keyed by the fields that changed.
+    pub diff: BTreeMap<&'static str, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentVersion {
+    pub version: usize,
+    pub body: String,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub edited_by: Option<String>,
+    pub diff: Option<String>,
+}
+
+pub struct RevisionService {
+    repo: Arc<dyn RevisionRepository>,
+    posts: Arc<dyn PostRepository>,
+    comments: Arc<dyn CommentRepository>,
+    communities: Arc<dyn CommunityRepository>,
+    users: Arc<dyn UserRepository>,
+    config: RevisionConfig,
+}
+
+impl RevisionService {
+    pub fn new(
+        repo: Arc<dyn RevisionRepository>,
+        posts: Arc<dyn PostRepository>,
+        comments: Arc<dyn CommentRepository>,
+        communities: Arc<dyn CommunityRepository>,
+        users: Arc<dyn UserRepository>,
+        config: RevisionConfig,
+    ) -> Self {
+        Self { repo, posts, comments, communities, users, config }
+    }
+
+    /// Every version of the post with the diffs between them. Moderators of its community see every
+    /// edit; others only when revisions are public, and then without edits made in the grace window.
+    pub async fn post_history(&self, viewer: Option<Uuid>, post_id: Uuid) -> Result<Vec<PostVersion>, AppError> {
+        let post = self.posts.find_for_viewer(post_id, viewer).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        let community = self.communities.find_by_slug(&post.community).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        let full = self.check_access(viewer, &community).await?;
+
+        let mut revisions = self.repo.post_revisions(post_id).await?;
+        if !full {
+            let grace = Duration::seconds(self.config.grace_secs);
+            revisions.retain(|r| r.replaced_at - post.created_at > grace);
+        }
+
+        // each revision was replaced by the next one, and the last by the post as it stands
+        let mut contents: Vec<_> = revisions.iter()
+            .map(|r| (r.title.clone(), r.short_description.clone(), r.url.clone(), r.body.clone()))
+            .collect();
+        contents.push((post.title, post.short_description.unwrap_or_default(), post.url, post.body));
+
+        let mut versions: Vec<PostVersion> = Vec::with_capacity(contents.len());
+        for (i, (title, short_description, url, body)) in contents.into_iter().enumerate() {
+            let (mut diff, mut edited_at, mut edited_by) = (BTreeMap::new(), None, None);
+            if let Some(j) = i.checked_sub(1) {
+                let prev = &versions[j];
+                let fields = [
+                    ("title", prev.title.as_str(), title.as_str()),
+                    ("short_description", prev.short_description.as_str(), short_description.as_str()),
+                    ("url", prev.url.as_deref().unwrap_or_default(), url.as_deref().unwrap_or_default()),
+                    ("body", prev.body.as_deref().unwrap_or_default(), body.as_deref().unwrap_or_default()),
+                ];
+                for (field, old, new) in fields {
+                    if let Some(d) = text_diff::unified(old, new) {
+                        diff.insert(field, d);
+                    }
+                }
+                edited_at = Some(revisions[j].replaced_at);
+                edited_by = revisions[j].editor_username.clone();
+            }
+            versions.push(PostVersion { version: i + 1, title, short_description, url, body, edited_at, edited_by, diff });
+        }
+        Ok(versions)
+    }
+
+    /// As `post_history`. Only moderators see the history of deleted, removed or held comments.
+    pub async fn comment_history(&self, viewer: Option<Uuid>, comment_id: Uuid) -> Result<Vec<CommentVersion>, AppError> {
+        let comment = self.comments.find_by_id(comment_id).await?
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        let post = self.posts.find_for_viewer(comment.post_id, viewer).await?
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        let community = self.communities.find_by_slug(&post.community).await?
+            .ok_or_else(|| AppError::not_found("comment not found"))?;
+        let full = self.check_access(viewer, &community).await?;
+        if !full && (!comment.is_visible() || comment.held_at.is_some()) {
+            return Err(AppError::not_found("comment not found"));
+        }
+
+        let mut revisions = self.repo.comment_revisions(comment_id).await?;
+        if !full {
+            let grace = Duration::seconds(self.config.grace_secs);
+            revisions.retain(|r| r.replaced_at - comment.created_at > grace);
+        }
+
+        let bodies = revisions.iter().map(|r| r.body.clone()).chain([comment.body]);
+        let mut versions: Vec<CommentVersion> = Vec::with_capacity(revisions.len() + 1);
+        for (i, body) in bodies.enumerate() {
+            let (diff, edited_at, edited_by) = match i.checked_sub(1) {
+                Some(j) => (
+                    text_diff::unified(&versions[j].body, &body),
+                    Some(revisions[j].replaced_at),
+                    revisions[j].editor_username.clone(),
+                ),
+                None => (None, None, None),
+            };
+            versions.push(CommentVersion { version: i + 1, body, edited_at, edited_by, diff });
+        }
+        Ok(versions)
+    }
+
+    /// Whether `viewer` sees the full history: false when revisions are public and the viewer
+    /// isn't a moderator. Fails when the viewer may see no history at all.
+    async fn check_access(&self, viewer: Option<Uuid>, community: &Community) -> Result<bool, AppError> {
+        let role = match viewer {
+            Some(user_id) => self.communities.member_role(community.id, user_id).await?,
+            None => None,
+        };
+        if !community.can_read(role) {
+            return Err(AppError::not_found("post not found"));
+        }
+        if matches!(role, Some(role) if role >= MemberRole::Moderator) || self.is_site_moderator(viewer).await? {
+            return Ok(true);
+        }
+        if self.config.public {
+            return Ok(false);
+        }
+        Err(AppError::forbidden("only moderators can see edit history"))
+    }
+
+    async fn is_site_moderator(&self, viewer: Option<Uuid>) -> Result<bool, AppError> {
+        let Some(user_id) = viewer else { return Ok(false) };
+        Ok(self.users.find_by_id(user_id).await?.is_some_and(|u| u.role.is_moderator()))
+    }
+}
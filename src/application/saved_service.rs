@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::domain::communities::CommunityRepository;
+use crate::domain::posts::{Post, PostRepository};
+use crate::domain::saved::{SavedCollection, SavedPost, SavedRepository};
+
+const MAX_COLLECTION_NAME_LEN: usize = 64;
+const MAX_COLLECTIONS_PER_USER: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionInput {
+    pub name: String,
+}
+
+/// A page of saved posts. `next` is set whenever the page was full, even if some of
+/// its posts could no longer be shown, so paging doesn't stop early.
+pub struct SavedPage {
+    pub posts: Vec<SavedPost>,
+    pub next: Option<(DateTime<Utc>, Uuid)>,
+}
+
+pub struct SavedService {
+    repo: Arc<dyn SavedRepository>,
+    posts: Arc<dyn PostRepository>,
+    communities: Arc<dyn CommunityRepository>,
+}
+
+impl SavedService {
+    pub fn new(repo: Arc<dyn SavedRepository>, posts: Arc<dyn PostRepository>, communities: Arc<dyn CommunityRepository>) -> Self {
+        Self { repo, posts, communities }
+    }
+
+    /// Saving an already saved post files it under `collection_id` (or takes it out of its collection).
+    pub async fn save(&self, user_id: Uuid, post_id: Uuid, collection_id: Option<Uuid>) -> Result<(), AppError> {
+        let post = self.posts.find_by_id(post_id).await?
+            .ok_or_else(|| AppError::not_found("post not found"))?;
+        if !self.can_read(user_id, &post).await? {
+            return Err(AppError::not_found("post not found"));
+        }
+        if let Some(collection_id) = collection_id {
+            self.find_collection(user_id, collection_id).await?;
+        }
+
+        self.repo.save(user_id, post_id, collection_id).await?;
+        Ok(())
+    }
+
+    pub async fn unsave(&self, user_id: Uuid, post_id: Uuid) -> Result<(), AppError> {
+        if !self.repo.unsave(user_id, post_id).await? {
+            return Err(AppError::not_found("post is not saved"));
+        }
+        Ok(())
+    }
+
+    /// Most recently saved first. Posts that were deleted, or whose private community the
+    /// user has since left, drop out of the list.
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        collection_id: Option<Uuid>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64
+    ) -> Result<SavedPage, AppError> {
+        if let Some(collection_id) = collection_id {
+            self.find_collection(user_id, collection_id).await?;
+        }
+
+        let entries = self.repo.list(user_id, collection_id, after, limit).await?;
+        let next = entries.last()
+            .filter(|_| entries.len() as i64 >= limit)
+            .map(|e| (e.saved_at, e.post_id));
+
+        let ids: Vec<Uuid> = entries.iter().map(|e| e.post_id).collect();
+        let mut posts: HashMap<Uuid, Post> = self.posts.find_by_ids(&ids).await?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        // find_by_ids leaves out private communities; those are kept for members only
+        let missing: Vec<Uuid> = ids.into_iter().filter(|id| !posts.contains_key(id)).collect();
+        for id in missing {
+            if let Some(post) = self.posts.find_by_id(id).await? {
+                if self.can_read(user_id, &post).await? {
+                    posts.insert(post.id, post);
+                }
+            }
+        }
+
+        let posts = entries.into_iter()
+            .filter_map(|e| posts.remove(&e.post_id).map(|post| SavedPost {
+                post,
+                collection_id: e.collection_id,
+                saved_at: e.saved_at,
+            }))
+            .collect();
+        Ok(SavedPage { posts, next })
+    }
+
+    pub async fn collections(&self, user_id: Uuid) -> Result<Vec<SavedCollection>, AppError> {
+        Ok(self.repo.list_collections(user_id).await?)
+    }
+
+    pub async fn create_collection(&self, user_id: Uuid, input: CollectionInput) -> Result<SavedCollection, AppError> {
+        let name = validate_collection_name(&input.name)?;
+        if self.repo.list_collections(user_id).await?.len() >= MAX_COLLECTIONS_PER_USER {
+            return Err(AppError::validation(format!("You can have at most {MAX_COLLECTIONS_PER_USER} collections")));
+        }
+        if self.repo.find_collection_by_name(user_id, name).await?.is_some() {
+            return Err(AppError::conflict("a collection with this name already exists"));
+        }
+        Ok(self.repo.create_collection(user_id, name).await?)
+    }
+
+    pub async fn rename_collection(&self, user_id: Uuid, collection_id: Uuid, input: CollectionInput) -> Result<SavedCollection, AppError> {
+        let name = validate_collection_name(&input.name)?;
+        let collection = self.find_collection(user_id, collection_id).await?;
+        if self.repo.find_collection_by_name(user_id, name).await?.is_some_and(|c| c.id != collection.id) {
+            return Err(AppError::conflict("a collection with this name already exists"));
+        }
+        self.repo.rename_collection(collection.id, name).await?;
+        self.find_collection(user_id, collection_id).await
+    }
+
+    /// The collection's posts stay saved.
+    pub async fn delete_collection(&self, user_id: Uuid, collection_id: Uuid) -> Result<(), AppError> {
+        let collection = self.find_collection(user_id, collection_id).await?;
+        self.repo.delete_collection(collection.id).await?;
+        Ok(())
+    }
+
+    async fn find_collection(&self, user_id: Uuid, collection_id: Uuid) -> Result<SavedCollection, AppError> {
+        self.repo.find_collection(user_id, collection_id).await?
+            .ok_or_else(|| AppError::not_found("collection not found"))
+    }
+
+    async fn can_read(&self, user_id: Uuid, post: &Post) -> Result<bool, AppError> {
+        let Some(community) = self.communities.find_by_slug(&post.community).await? else {
+            return Ok(false);
+        };
+        Ok(community.can_read(self.communities.member_role(community.id, user_id).await?))
+    }
+}
+
+fn validate_collection_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_COLLECTION_NAME_LEN {
+        return Err(AppError::validation(format!("Collection names must be between 1 and {MAX_COLLECTION_NAME_LEN} characters")));
+    }
+    Ok(name)
+}
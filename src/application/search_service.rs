@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::tag_service;
+use crate::application::utils::{identity, validation};
+use crate::domain::mutes::MuteRepository;
+use crate::domain::posts::{FeedCursor, FeedSort, PostEvent, PostRepository};
+use crate::domain::search::{SearchIndex, SearchQuery, SearchResults};
+use crate::domain::tags::TagRepository;
+
+const MAX_SEARCH_QUERY_LEN: usize = 256;
+/// Ranked results are paged by offset; keep deep pages from turning into full scans.
+const MAX_SEARCH_OFFSET: i64 = 1000;
+const REINDEX_PAGE_SIZE: i64 = 500;
+
+pub struct SearchService {
+    index: Arc<dyn SearchIndex>,
+    posts: Arc<dyn PostRepository>,
+    mutes: Arc<dyn MuteRepository>,
+    tags: Arc<dyn TagRepository>,
+}
+
+impl SearchService {
+    pub fn new(index: Arc<dyn SearchIndex>, posts: Arc<dyn PostRepository>, mutes: Arc<dyn MuteRepository>, tags: Arc<dyn TagRepository>) -> Self {
+        Self { index, posts, mutes, tags }
+    }
+
+    /// A signed-in viewer never sees posts they hid or posts by authors and domains they muted.
+    /// A tag filter naming an alias searches its canonical tag.
+    pub async fn search(&self, viewer: Option<Uuid>, mut query: SearchQuery) -> Result<SearchResults, AppError> {
+        query.text = query.text.trim().to_string();
+        if query.text.is_empty() {
+            return Err(AppError::validation("Search query cannot be empty"));
+        }
+        if query.text.len() > MAX_SEARCH_QUERY_LEN {
+            return Err(AppError::validation(format!("Search query must be at most {MAX_SEARCH_QUERY_LEN} characters")));
+        }
+        if !(0..=MAX_SEARCH_OFFSET).contains(&query.offset) {
+            return Err(AppError::validation(format!("Offset must be between 0 and {MAX_SEARCH_OFFSET}")));
+        }
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err(AppError::validation("`from` must be before `to`"));
+            }
+        }
+
+        query.author = query.author.map(|a| identity::fold(&a));
+        if let Some(tag) = query.tag.take() {
+            let slug = tag_service::slugify(&tag);
+            query.tag = Some(match self.tags.find_by_slug(&slug).await? {
+                Some(tag) => tag.slug,
+                None => slug,
+            });
+        }
+        query.domain = query.domain.map(|d| validation::normalize_domain(&d));
+
+        query.exclude = self.mutes.exclusions(viewer).await?;
+        query.viewer = viewer;
+
+        Ok(self.index.search(&query).await?)
+    }
+
+    /// Rebuilds the index from the posts table. Returns how many posts were indexed.
+    pub async fn reindex(&self) -> Result<u64, AppError> {
+        self.index.clear().await?;
+
+        // `list_new` leaves pinned posts to `list_pinned`
+        let pinned = self.posts.list_pinned(None).await?;
+        self.index.index_posts(&pinned).await?;
+
+        let mut indexed = pinned.len() as u64;
+        let mut after = None;
+        loop {
+            let page = self.posts.list_new(None, after, REINDEX_PAGE_SIZE).await?;
+            self.index.index_posts(&page).await?;
+            indexed += page.len() as u64;
+            if (page.len() as i64) < REINDEX_PAGE_SIZE {
+                return Ok(indexed);
+            }
+            after = page.last().map(|p| (p.created_at, p.id));
+        }
+    }
+
+    /// Re-indexes every post by the user, whose documents still carry their old name after a rename.
+    /// Goes to the index directly: one event per post could overrun the event channel.
+    pub async fn reindex_author(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let mut indexed = 0;
+        let mut after = None;
+        loop {
+            let page = self.posts.list_by_author(user_id, None, FeedSort::New, after, REINDEX_PAGE_SIZE).await?;
+            self.index.index_posts(&page).await?;
+            indexed += page.len() as u64;
+            if (page.len() as i64) < REINDEX_PAGE_SIZE {
+                return Ok(indexed);
+            }
+            after = page.last().map(|p| FeedCursor::at(FeedSort::New, 0, p.created_at, p.id));
+        }
+    }
+
+    /// Applies post events to the index for the lifetime of the process. Posts that aren't listed
+    /// (see `Post::is_listed`) are kept out of it, so they can't turn up in results or facets.
+    pub fn spawn_indexer(self: Arc<Self>, mut events: broadcast::Receiver<PostEvent>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let result = match events.recv().await {
+                    Ok(PostEvent::Created(post)) | Ok(PostEvent::Updated(post)) if post.is_listed() => self.index.index_posts(&[post]).await,
+                    Ok(PostEvent::Created(post)) | Ok(PostEvent::Updated(post)) => self.index.remove_post(post.id).await,
+                    Ok(PostEvent::Deleted(post_id)) => self.index.remove_post(post_id).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "search indexer fell behind; run `reindex` to catch up");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if let Err(e) = result {
+                    tracing::error!(error = ?e, "failed to update search index");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::SearchService;
+    use crate::application::test_support::{post, MemoryPosts, Unused};
+    use crate::domain::posts::Post;
+    use crate::domain::search::{SearchIndex, SearchQuery, SearchResults};
+
+    /// Keeps the ids of the posts it was given.
+    #[derive(Default)]
+    struct MemoryIndex {
+        ids: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchIndex for MemoryIndex {
+        async fn search(&self, _: &SearchQuery) -> anyhow::Result<SearchResults> {
+            unreachable!()
+        }
+        async fn index_posts(&self, posts: &[Post]) -> anyhow::Result<()> {
+            self.ids.lock().unwrap().extend(posts.iter().map(|p| p.id));
+            Ok(())
+        }
+        async fn remove_post(&self, post_id: Uuid) -> anyhow::Result<()> {
+            self.ids.lock().unwrap().retain(|id| *id != post_id);
+            Ok(())
+        }
+        async fn clear(&self) -> anyhow::Result<()> {
+            self.ids.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn reindexing_keeps_pinned_posts() {
+        let author = Uuid::new_v4();
+        let mut pinned = post(author, Duration::days(3));
+        pinned.pinned_at = Some(Utc::now());
+        pinned.pin_position = Some(1);
+        let posts = vec![pinned.clone(), post(author, Duration::days(1)), post(author, Duration::days(2))];
+        let expected: Vec<Uuid> = posts.iter().map(|p| p.id).collect();
+
+        let index = Arc::new(MemoryIndex::default());
+        let service = SearchService::new(index.clone(), Arc::new(MemoryPosts::with(posts)), Arc::new(Unused), Arc::new(Unused));
+        assert_eq!(service.reindex().await.unwrap(), 3);
+
+        let mut indexed = index.ids.lock().unwrap().clone();
+        indexed.sort();
+        let mut expected = expected;
+        expected.sort();
+        assert_eq!(indexed, expected);
+    }
+}
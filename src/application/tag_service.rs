@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::utils::identity;
+use crate::domain::posts::{PostEvent, PostRepository};
+use crate::domain::tags::{Tag, TagRepository};
+
+pub const MAX_TAGS_PER_POST: usize = 5;
+const MIN_TAG_LEN: usize = 2;
+const MAX_TAG_LEN: usize = 32;
+const MAX_AUTOCOMPLETE: i64 = 20;
+
+/// Reduces what a user typed to a tag slug: folded, letters and digits, words joined by `-`.
+pub fn slugify(raw: &str) -> String {
+    let mut slug = String::new();
+    for c in identity::fold(raw).chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Slugifies and validates the tags given for a post, dropping duplicates but keeping their order.
+pub fn normalize_tags(raw: &[String]) -> Result<Vec<String>, AppError> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw {
+        let slug = slugify(tag);
+        if !(MIN_TAG_LEN..=MAX_TAG_LEN).contains(&slug.chars().count()) {
+            return Err(AppError::validation(format!("Tag {tag:?} must be between {MIN_TAG_LEN} and {MAX_TAG_LEN} characters")));
+        }
+        if !tags.contains(&slug) {
+            tags.push(slug);
+        }
+    }
+    if tags.len() > MAX_TAGS_PER_POST {
+        return Err(AppError::validation(format!("A post can have at most {MAX_TAGS_PER_POST} tags")));
+    }
+    Ok(tags)
+}
+
+pub struct TagService {
+    repo: Arc<dyn TagRepository>,
+    posts: Arc<dyn PostRepository>,
+    post_events: broadcast::Sender<PostEvent>,
+}
+
+impl TagService {
+    pub fn new(repo: Arc<dyn TagRepository>, posts: Arc<dyn PostRepository>, post_events: broadcast::Sender<PostEvent>) -> Self {
+        Self { repo, posts, post_events }
+    }
+
+    /// Looks a tag up by slug or alias.
+    pub async fn find(&self, slug: &str) -> Result<Tag, AppError> {
+        self.repo.find_by_slug(&slugify(slug)).await?
+            .ok_or_else(|| AppError::not_found("tag not found"))
+    }
+
+    pub async fn autocomplete(&self, prefix: &str, limit: i64) -> Result<Vec<Tag>, AppError> {
+        let prefix = slugify(prefix);
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(self.repo.autocomplete(&prefix, limit.min(MAX_AUTOCOMPLETE)).await?)
+    }
+
+    /// Makes `alias` resolve to the tag `slug`, both for new posts and for lookups.
+    pub async fn add_alias(&self, moderator_id: Uuid, slug: &str, alias: &str) -> Result<Tag, AppError> {
+        let tag = self.find(slug).await?;
+        let alias = slugify(alias);
+        if !(MIN_TAG_LEN..=MAX_TAG_LEN).contains(&alias.chars().count()) {
+            return Err(AppError::validation(format!("Alias must be between {MIN_TAG_LEN} and {MAX_TAG_LEN} characters")));
+        }
+        if self.repo.find_by_slug(&alias).await?.is_some() {
+            return Err(AppError::conflict("alias is already a tag or an alias; merge the tags instead"));
+        }
+
+        self.repo.add_alias(tag.id, &alias, moderator_id).await?;
+        Ok(tag)
+    }
+
+    pub async fn remove_alias(&self, alias: &str) -> Result<(), AppError> {
+        if !self.repo.remove_alias(&slugify(alias)).await? {
+            return Err(AppError::not_found("alias not found"));
+        }
+        Ok(())
+    }
+
+    /// Folds `source` into `target`; `source`'s slug keeps working as an alias.
+    pub async fn merge(&self, moderator_id: Uuid, source: &str, target: &str) -> Result<Tag, AppError> {
+        let source = self.find(source).await?;
+        let target = self.find(target).await?;
+        if source.id == target.id {
+            return Err(AppError::validation("Cannot merge a tag into itself"));
+        }
+
+        let moved = self.repo.merge(source.id, target.id, moderator_id).await?;
+
+        // the posts' tag lists changed, so let secondary indexes pick them up again
+        for post in self.posts.find_by_ids(&moved).await? {
+            let _ = self.post_events.send(PostEvent::Updated(post));
+        }
+
+        self.find(&target.slug).await
+    }
+}
@@ -0,0 +1,355 @@
+//! In-memory stand-ins for the repositories, for service tests that don't need a database.
+//! Only the methods some test calls are implemented; the rest panic.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::domain::communities::{Community, CommunityRepository, MemberRole, Visibility};
+use crate::domain::domains::{DomainAction, DomainRepository, DomainRule, DomainStats};
+use crate::domain::follows::FollowRepository;
+use crate::domain::mutes::{FeedExclusions, HiddenEntry, MuteRepository, MutedDomain, MutedUser};
+use crate::domain::posts::{FeedCursor, FeedSort, NewPost, Post, PostEdit, PostRepository, ViewerState};
+use crate::domain::spam::{AuthorActivity, DomainReputation, SpamRepository};
+use crate::domain::tags::{Tag, TagRepository};
+
+/// A listed link post by `user_id` in the public "general" community, created `age` ago.
+pub fn post(user_id: Uuid, age: Duration) -> Post {
+    let created_at = Utc::now() - age;
+    Post {
+        id: Uuid::new_v4(),
+        user_id,
+        title: "A post".to_string(),
+        url: Some("https://example.com/a".to_string()),
+        body: None,
+        short_description: Some(String::new()),
+        score: 0,
+        created_at,
+        hot_at: created_at,
+        preview_title: None,
+        preview_site_name: None,
+        preview_description: None,
+        preview_image_url: None,
+        preview_fetched_at: None,
+        locked_at: None,
+        held_at: None,
+        pinned_at: None,
+        pin_position: None,
+        archived_at: None,
+        edited_at: None,
+        version: 1,
+        avatar: None,
+        author_username: "author".to_string(),
+        community: "general".to_string(),
+        community_visibility: Visibility::Public,
+        tags: Vec::new(),
+        author_shadowbanned: false,
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryPosts {
+    pub posts: Mutex<Vec<Post>>,
+}
+
+impl MemoryPosts {
+    pub fn with(posts: Vec<Post>) -> Self {
+        Self { posts: Mutex::new(posts) }
+    }
+
+    fn listed(&self, pinned: bool) -> Vec<Post> {
+        let mut posts: Vec<Post> = self.posts.lock().unwrap().iter()
+            .filter(|p| p.is_listed() && p.pinned_at.is_some() == pinned)
+            .cloned().collect();
+        posts.sort_by_key(|p| std::cmp::Reverse((p.created_at, p.id)));
+        posts
+    }
+}
+
+#[async_trait::async_trait]
+impl PostRepository for MemoryPosts {
+    async fn create(&self, _: &NewPost) -> anyhow::Result<Post> {
+        unimplemented!()
+    }
+    async fn list_new(&self, _: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        Ok(self.listed(false).into_iter()
+            .filter(|p| after.is_none_or(|after| (p.created_at, p.id) < after))
+            .take(limit as usize).collect())
+    }
+    async fn list_top(&self, _: Option<Uuid>, _: Option<FeedCursor>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_pinned(&self, _: Option<Uuid>) -> anyhow::Result<Vec<Post>> {
+        Ok(self.listed(true))
+    }
+    async fn list_by_author(&self, _: Uuid, _: Option<Uuid>, _: FeedSort, _: Option<FeedCursor>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_by_tag(&self, _: Uuid, _: Option<Uuid>, _: FeedSort, _: Option<FeedCursor>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_by_domain(&self, _: &str, _: Option<Uuid>, _: FeedSort, _: Option<FeedCursor>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_by_community(&self, _: Uuid, _: Option<Uuid>, _: FeedSort, _: Option<FeedCursor>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_hot(&self, _: Option<Uuid>, _: Option<(DateTime<Utc>, Uuid)>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_home(&self, _: Uuid, _: Option<(DateTime<Utc>, Uuid)>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn list_upvoted_by(&self, _: Uuid, _: Option<(DateTime<Utc>, Uuid)>, _: i64) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn update(&self, post_id: Uuid, _: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>> {
+        let mut posts = self.posts.lock().unwrap();
+        let Some(post) = posts.iter_mut().find(|p| p.id == post_id && expected_version.is_none_or(|v| v == p.version)) else {
+            return Ok(None);
+        };
+        post.title = edit.title.clone();
+        post.short_description = Some(edit.short_description.clone());
+        post.url = edit.url.clone();
+        post.body = edit.body.clone();
+        post.tags = edit.tags.clone();
+        if mark_edited {
+            post.edited_at = Some(Utc::now());
+        }
+        if edit.hold_reason.is_some() && post.held_at.is_none() {
+            post.held_at = Some(Utc::now());
+        }
+        post.version += 1;
+        Ok(Some(post.clone()))
+    }
+    async fn delete(&self, _: Uuid) -> anyhow::Result<bool> {
+        unimplemented!()
+    }
+    async fn archive_older_than(&self, _: DateTime<Utc>) -> anyhow::Result<u64> {
+        unimplemented!()
+    }
+    async fn find_by_id(&self, _: Uuid) -> anyhow::Result<Option<Post>> {
+        unimplemented!()
+    }
+    async fn find_held(&self, _: Uuid) -> anyhow::Result<Option<Post>> {
+        unimplemented!()
+    }
+    async fn find_for_viewer(&self, post_id: Uuid, viewer: Option<Uuid>) -> anyhow::Result<Option<Post>> {
+        Ok(self.posts.lock().unwrap().iter()
+            .find(|p| p.id == post_id && (p.held_at.is_none() || viewer == Some(p.user_id)))
+            .cloned())
+    }
+    async fn find_by_canonical_url(&self, _: Uuid, _: &str, _: DateTime<Utc>) -> anyhow::Result<Option<Post>> {
+        unimplemented!()
+    }
+    async fn find_by_ids(&self, _: &[Uuid]) -> anyhow::Result<Vec<Post>> {
+        unimplemented!()
+    }
+    async fn viewer_states(&self, _: Uuid, _: &[Uuid]) -> anyhow::Result<Vec<ViewerState>> {
+        unimplemented!()
+    }
+    /// Scores as if `user_id` were the only voter.
+    async fn upsert_vote_and_recompute(&self, _: Uuid, post_id: Uuid, value: i16) -> anyhow::Result<(i32, DateTime<Utc>)> {
+        let mut posts = self.posts.lock().unwrap();
+        let post = posts.iter_mut().find(|p| p.id == post_id).expect("no such post");
+        post.score = value.into();
+        Ok((post.score, post.hot_at))
+    }
+}
+
+/// A private community named `slug` and its members.
+pub struct MemoryCommunities {
+    pub community: Community,
+    pub members: Vec<(Uuid, MemberRole)>,
+}
+
+impl MemoryCommunities {
+    pub fn private(slug: &str, members: Vec<(Uuid, MemberRole)>) -> Self {
+        let community = Community {
+            id: Uuid::new_v4(),
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            description: String::new(),
+            rules: Vec::new(),
+            visibility: Visibility::Private,
+            member_count: members.len() as i64,
+            created_at: Utc::now(),
+        };
+        Self { community, members }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommunityRepository for MemoryCommunities {
+    async fn create(&self, _: &str, _: &str, _: &str, _: &[String], _: Visibility, _: Uuid) -> anyhow::Result<Option<Community>> {
+        unimplemented!()
+    }
+    async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Community>> {
+        Ok(Some(self.community.clone()).filter(|c| c.slug == slug))
+    }
+    async fn find_by_id(&self, community_id: Uuid) -> anyhow::Result<Option<Community>> {
+        Ok(Some(self.community.clone()).filter(|c| c.id == community_id))
+    }
+    async fn update(&self, _: Uuid, _: &str, _: &str, _: &[String], _: Visibility) -> anyhow::Result<Community> {
+        unimplemented!()
+    }
+    async fn member_role(&self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<Option<MemberRole>> {
+        Ok(self.members.iter()
+            .find(|(member, _)| community_id == self.community.id && *member == user_id)
+            .map(|(_, role)| *role))
+    }
+    async fn set_member(&self, _: Uuid, _: Uuid, _: MemberRole) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+    async fn remove_member(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unimplemented!()
+    }
+    async fn list_joined(&self, _: Uuid) -> anyhow::Result<Vec<Community>> {
+        unimplemented!()
+    }
+}
+
+/// Stands in for repositories a test never reaches.
+pub struct Unused;
+
+#[async_trait::async_trait]
+impl MuteRepository for Unused {
+    async fn hide_post(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn unhide_post(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn list_hidden(&self, _: Uuid, _: Option<(DateTime<Utc>, Uuid)>, _: i64) -> anyhow::Result<Vec<HiddenEntry>> {
+        unreachable!()
+    }
+    async fn mute_user(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn unmute_user(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn list_muted_users(&self, _: Uuid) -> anyhow::Result<Vec<MutedUser>> {
+        unreachable!()
+    }
+    async fn mute_domain(&self, _: Uuid, _: &str) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn unmute_domain(&self, _: Uuid, _: &str) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn list_muted_domains(&self, _: Uuid) -> anyhow::Result<Vec<MutedDomain>> {
+        unreachable!()
+    }
+    async fn exclusions(&self, _: Option<Uuid>) -> anyhow::Result<FeedExclusions> {
+        unreachable!()
+    }
+}
+
+#[async_trait::async_trait]
+impl TagRepository for Unused {
+    async fn find_by_slug(&self, _: &str) -> anyhow::Result<Option<Tag>> {
+        unreachable!()
+    }
+    async fn autocomplete(&self, _: &str, _: i64) -> anyhow::Result<Vec<Tag>> {
+        unreachable!()
+    }
+    async fn add_alias(&self, _: Uuid, _: &str, _: Uuid) -> anyhow::Result<()> {
+        unreachable!()
+    }
+    async fn remove_alias(&self, _: &str) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn merge(&self, _: Uuid, _: Uuid, _: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        unreachable!()
+    }
+}
+
+#[async_trait::async_trait]
+impl FollowRepository for Unused {
+    async fn follow_user(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn unfollow_user(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn follow_tag(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn unfollow_tag(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn followed_usernames(&self, _: Uuid) -> anyhow::Result<Vec<String>> {
+        unreachable!()
+    }
+    async fn followed_tags(&self, _: Uuid) -> anyhow::Result<Vec<String>> {
+        unreachable!()
+    }
+    async fn has_subscriptions(&self, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+}
+
+#[async_trait::async_trait]
+impl DomainRepository for Unused {
+    async fn rule_for(&self, _: &str) -> anyhow::Result<Option<DomainRule>> {
+        unreachable!()
+    }
+    async fn list_rules(&self) -> anyhow::Result<Vec<DomainRule>> {
+        unreachable!()
+    }
+    async fn upsert_rule(&self, _: &str, _: DomainAction, _: Option<i32>, _: Option<i32>, _: &str, _: Uuid) -> anyhow::Result<DomainRule> {
+        unreachable!()
+    }
+    async fn insert_rule(&self, _: &str, _: DomainAction, _: &str) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn delete_rule(&self, _: &str) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn count_recent_posts(&self, _: Uuid, _: &str, _: DateTime<Utc>) -> anyhow::Result<i64> {
+        unreachable!()
+    }
+    async fn stats(&self, _: &str) -> anyhow::Result<DomainStats> {
+        unreachable!()
+    }
+}
+
+#[async_trait::async_trait]
+impl SpamRepository for Unused {
+    async fn author_activity(&self, _: Uuid, _: DateTime<Utc>, _: DateTime<Utc>) -> anyhow::Result<Option<AuthorActivity>> {
+        unreachable!()
+    }
+    async fn reputations(&self, _: &[String]) -> anyhow::Result<Vec<DomainReputation>> {
+        unreachable!()
+    }
+}
+
+#[async_trait::async_trait]
+impl CommunityRepository for Unused {
+    async fn create(&self, _: &str, _: &str, _: &str, _: &[String], _: Visibility, _: Uuid) -> anyhow::Result<Option<Community>> {
+        unreachable!()
+    }
+    async fn find_by_slug(&self, _: &str) -> anyhow::Result<Option<Community>> {
+        unreachable!()
+    }
+    async fn find_by_id(&self, _: Uuid) -> anyhow::Result<Option<Community>> {
+        unreachable!()
+    }
+    async fn update(&self, _: Uuid, _: &str, _: &str, _: &[String], _: Visibility) -> anyhow::Result<Community> {
+        unreachable!()
+    }
+    async fn member_role(&self, _: Uuid, _: Uuid) -> anyhow::Result<Option<MemberRole>> {
+        unreachable!()
+    }
+    async fn set_member(&self, _: Uuid, _: Uuid, _: MemberRole) -> anyhow::Result<()> {
+        unreachable!()
+    }
+    async fn remove_member(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unreachable!()
+    }
+    async fn list_joined(&self, _: Uuid) -> anyhow::Result<Vec<Community>> {
+        unreachable!()
+    }
+}
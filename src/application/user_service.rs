@@ -1,9 +1,18 @@
+use std::net::IpAddr;
 use std::sync::Arc;
 
+use chrono::{Duration, Utc};
 use uuid::Uuid;
 use validator::Validate;
+use crate::application::utils::{identity, validation};
 use crate::infrastructure::auth;
-use crate::{application::error::AppError, domain::users::{User, UserRepository}};
+use crate::{application::error::AppError, domain::users::{PendingSkeleton, Role, User, UserRepository}};
+use crate::domain::sanctions::{SanctionKind, SanctionRepository};
+
+/// Minimum time between two username changes by the same user.
+pub const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+/// How long a released username stays reserved for its previous owner.
+pub const USERNAME_RESERVATION_DAYS: i64 = 90;
 
 #[derive(Debug, Validate)]
 pub struct SignupInput {
@@ -15,49 +24,197 @@ pub struct SignupInput {
     pub password: String,
 }
 
+/// Skeletons recomputed per query by `recompute_skeletons`.
+const SKELETON_BATCH_SIZE: i64 = 500;
+
+/// Result of looking a profile up by name: either the user, or the name they moved to.
+pub enum ProfileLookup {
+    Found(User),
+    Renamed(String),
+}
+
 pub struct UserService {
     pub repo: Arc<dyn UserRepository>,
+    sanctions: Arc<dyn SanctionRepository>,
 }
 
 impl UserService {
-    pub fn new(repo: Arc<dyn UserRepository>) -> Self { Self { repo } }
+    pub fn new(repo: Arc<dyn UserRepository>, sanctions: Arc<dyn SanctionRepository>) -> Self { Self { repo, sanctions } }
 
     /// Registers a user after validation & uniqueness checks.
     pub async fn signup(&self, username: String, email: String, avatar: String, password: String) -> Result<User, AppError> {
         // input.validate().map_err(|e| AppError::validation(e.to_string()))?;
 
         // normalize
-        let email = email.to_lowercase();
-        let username = username.trim();
+        let email = identity::fold(&email);
+        let username = identity::parse_username(&username);
+
+        validation::validate_username(&username.display)
+            .map_err(|e| AppError::validation(e.to_string()))?;
+
+        if identity::is_reserved(&username) {
+            return Err(AppError::conflict("username is reserved"));
+        }
 
         // uniqueness
-        if let Some(_) = self.repo.find_by_email_or_username(&email).await? {
+        if self.repo.find_by_email_or_username(&email).await?.is_some() {
             return Err(AppError::conflict("email already registerd"));
         }
 
-        if let Some(_) = self.repo.find_by_email_or_username(username).await? {
+        // the skeleton catches case variants and lookalikes ("alice", "Alice", "аlice") alike
+        if self.repo.find_by_username_skeleton(&username).await?.is_some() {
             return Err(AppError::conflict("username ready taken"));
         }
 
+        if self.repo.username_reserved_by(&username).await?.is_some() {
+            return Err(AppError::conflict("username is reserved"));
+        }
+
         let hash = auth::hash_password(&password)?;
-        let user = self.repo.create(&email, username, &avatar, &hash).await?;
+        let user = self.repo.create(&email, &username, &avatar, &hash).await?;
         Ok(user)
     }
 
     /// Returns the user if credentials are valid.
     pub async fn authenticate(&self, email: &str, password: &str) -> Result<User, AppError> {
-        let key = email.to_lowercase();
+        let key = identity::fold(email);
         let Some(user) = self.repo.find_by_email_or_username(&key).await? else {
             return Err(AppError::Unauthorized)
         };
 
         let ok = auth::verify_password(password, &user.password_hash)?;
         if !ok { return Err(AppError::Unauthorized)}
+        if let Some(ban) = self.sanctions.active_for(user.id).await?.into_iter().find(|s| s.kind == SanctionKind::Ban) {
+            return Err(AppError::forbidden(ban.describe()));
+        }
         Ok(user)
     }
 
+    pub async fn find_by_username(&self, username: &str) -> Result<User, AppError> {
+        self.repo.find_by_username(&identity::parse_username(username)).await?
+            .ok_or_else(|| AppError::not_found(format!("user {username}")))
+    }
+
+    /// Looks a profile up by name, following renames recorded in `username_history`.
+    pub async fn resolve_profile(&self, username: &str) -> Result<ProfileLookup, AppError> {
+        let lookup = identity::parse_username(username);
+        if let Some(user) = self.repo.find_by_username(&lookup).await? {
+            return Ok(ProfileLookup::Found(user));
+        }
+        match self.repo.find_by_previous_username(&lookup).await? {
+            Some(user) => Ok(ProfileLookup::Renamed(user.username)),
+            None => Err(AppError::not_found(format!("user {username}"))),
+        }
+    }
+
+    /// Renames the user, at most once per cooldown, keeping the old name reserved for them.
+    pub async fn change_username(&self, user_id: Uuid, new_username: &str) -> Result<User, AppError> {
+        let new_username = identity::parse_username(new_username);
+        validation::validate_username(&new_username.display)
+            .map_err(|e| AppError::validation(e.to_string()))?;
+
+        if identity::is_reserved(&new_username) {
+            return Err(AppError::conflict("username is reserved"));
+        }
+
+        let user = self.repo.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+        if user.username == new_username.display {
+            return Err(AppError::validation("that is already your username"));
+        }
+
+        let now = Utc::now();
+        if let Some(last) = self.repo.last_username_change(user_id).await? {
+            let next_allowed = last + Duration::days(USERNAME_CHANGE_COOLDOWN_DAYS);
+            if next_allowed > now {
+                return Err(AppError::rate_limited(format!("username can be changed again after {}", next_allowed.to_rfc3339())));
+            }
+        }
+
+        // changing only the case of your own name collides with yourself, which is fine
+        if let Some(other) = self.repo.find_by_username_skeleton(&new_username).await? {
+            if other.id != user_id {
+                return Err(AppError::conflict("username already taken"));
+            }
+        }
+
+        // a user may always take back a name they released themselves
+        if let Some(holder) = self.repo.username_reserved_by(&new_username).await? {
+            if holder != user_id {
+                return Err(AppError::conflict("username is reserved"));
+            }
+        }
+
+        let reserved_until = now + Duration::days(USERNAME_RESERVATION_DAYS);
+        Ok(self.repo.change_username(user_id, &new_username, reserved_until).await?)
+    }
+
+    /// Fails with `Forbidden` unless the user is a moderator or an admin.
+    pub async fn require_moderator(&self, user_id: Uuid) -> Result<(), AppError> {
+        let user = self.repo.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+        if !user.role.is_moderator() {
+            return Err(AppError::forbidden("moderator role required"));
+        }
+        Ok(())
+    }
+
+    /// Fails with `Forbidden` unless the user is an admin.
+    pub async fn require_admin(&self, user_id: Uuid) -> Result<(), AppError> {
+        let user = self.repo.find_by_id(user_id).await?
+            .ok_or(AppError::Unauthorized)?;
+        if user.role != Role::Admin {
+            return Err(AppError::forbidden("admin role required"));
+        }
+        Ok(())
+    }
+
     pub async fn verify_token(&self, token: &str) -> Result<Option<Uuid>, anyhow::Error> {
         let user_id = self.repo.verify_token(token).await?;
-        Ok(Some(user_id))
+        // tokens outlive anonymized accounts, so make sure the account is still there
+        Ok(self.repo.find_by_id(user_id).await?.map(|u| u.id))
+    }
+
+    /// Every authenticated request goes through here: bans refuse everything, suspensions
+    /// refuse writes. Shadowbans don't refuse anything; reads filter the user's content instead.
+    pub async fn check_standing(&self, user_id: Uuid, write: bool) -> Result<(), AppError> {
+        for sanction in self.sanctions.active_for(user_id).await? {
+            match sanction.kind {
+                SanctionKind::Ban => return Err(AppError::forbidden(sanction.describe())),
+                SanctionKind::Suspension if write => return Err(AppError::forbidden(sanction.describe())),
+                SanctionKind::Suspension | SanctionKind::Shadowban => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Remembers where the user signed up or logged in from, for ban-evasion checks. Best effort:
+    /// the signup or login already went through, so a failure is only logged.
+    pub async fn record_ip(&self, user_id: Uuid, ip: IpAddr) {
+        if let Err(e) = self.sanctions.record_ip(user_id, &ip.to_string()).await {
+            tracing::warn!(error = ?e, %user_id, "failed to record client ip");
+        }
+    }
+}
+
+/// Computes the skeletons a migration left pending, oldest account first so it is the one that keeps
+/// a skeleton several accounts share. Returns how many names were updated.
+pub async fn recompute_skeletons(repo: &dyn UserRepository) -> Result<u64, AppError> {
+    let mut updated = 0;
+    loop {
+        let page = repo.pending_skeletons(SKELETON_BATCH_SIZE).await?;
+        for PendingSkeleton { id, username } in &page {
+            repo.set_skeleton(*id, &identity::username_skeleton(username)).await?;
+        }
+        updated += page.len() as u64;
+        if (page.len() as i64) < SKELETON_BATCH_SIZE { break; }
+    }
+    loop {
+        let page = repo.pending_history_skeletons(SKELETON_BATCH_SIZE).await?;
+        for PendingSkeleton { id, username } in &page {
+            repo.set_history_skeleton(*id, &identity::username_skeleton(username)).await?;
+        }
+        updated += page.len() as u64;
+        if (page.len() as i64) < SKELETON_BATCH_SIZE { return Ok(updated); }
     }
 }
\ No newline at end of file
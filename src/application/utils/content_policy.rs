@@ -0,0 +1,21 @@
+use crate::application::error::AppError;
+use crate::domain::content_filter::{ContentFilter, FilterAction};
+
+/// Runs every field of one submission through the filter of the community it is posted in,
+/// masking each field in place. Refuses the submission if any field is rejected; otherwise
+/// returns why it must be held for review, if it must.
+pub fn screen(filter: &dyn ContentFilter, community: &str, fields: &mut [&mut String]) -> Result<Option<String>, AppError> {
+    let mut held = Vec::new();
+    for field in fields.iter_mut() {
+        let verdict = filter.check(Some(community), field);
+        match verdict.action {
+            Some(FilterAction::Reject) => {
+                return Err(AppError::validation(format!("Contains language that isn't allowed here: {}", verdict.reason())));
+            }
+            Some(FilterAction::Hold) => held.push(verdict.reason()),
+            Some(FilterAction::Mask) | None => {}
+        }
+        **field = verdict.text;
+    }
+    Ok((!held.is_empty()).then(|| format!("content filter: {}", held.join(", "))))
+}
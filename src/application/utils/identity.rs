@@ -0,0 +1,34 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+use crate::domain::users::Username;
+
+/// Names nobody may register, compared by skeleton so "Admin" and "аdmin" (Cyrillic а) are caught too.
+static RESERVED_USERNAMES: &[&str] = &[
+    "admin", "administrator", "moderator", "mod", "staff", "support", "help",
+    "api", "root", "system", "lotus", "official", "security",
+    "me", "settings", "login", "signup", "deleted", "null", "undefined",
+];
+
+/// NFKC + case folding. Used for everything a user types to identify an account: usernames and emails.
+pub fn fold(s: &str) -> String {
+    s.trim().nfkc().flat_map(char::to_lowercase).collect()
+}
+
+/// UTS #39 skeleton of the folded name: two names with the same skeleton look alike.
+pub fn username_skeleton(s: &str) -> String {
+    skeleton(&fold(s)).collect()
+}
+
+pub fn parse_username(s: &str) -> Username {
+    let display = s.trim().nfkc().collect::<String>();
+    Username {
+        normalized: fold(&display),
+        skeleton: username_skeleton(&display),
+        display,
+    }
+}
+
+pub fn is_reserved(username: &Username) -> bool {
+    RESERVED_USERNAMES.iter().any(|r| username_skeleton(r) == username.skeleton)
+}
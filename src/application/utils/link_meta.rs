@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::domain::previews::{FetchedPage, LinkPreview};
+
+const MAX_TITLE_CHARS: usize = 300;
+const MAX_SITE_NAME_CHARS: usize = 100;
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+
+/// Builds a preview from OpenGraph tags, falling back to Twitter card tags and then plain
+/// HTML (`<title>`, `<meta name="description">`). A link straight to an image previews as that image.
+pub fn extract_preview(page: &FetchedPage) -> LinkPreview {
+    let base = Url::parse(&page.url).ok();
+    let site = base.as_ref().and_then(site_of);
+
+    if page.content_type.as_deref().is_some_and(|ct| ct.trim_start().to_ascii_lowercase().starts_with("image/")) {
+        return LinkPreview { site_name: site, image_url: Some(page.url.clone()), ..Default::default() };
+    }
+
+    let html = Html::parse_document(&page.body);
+    let meta = meta_tags(&html);
+    let first = |keys: &[&str]| keys.iter().find_map(|k| meta.get(*k).cloned());
+
+    let title = first(&["og:title", "twitter:title"]).or_else(|| select_text(&html, "title"));
+    let description = first(&["og:description", "twitter:description", "description"]);
+    let site_name = first(&["og:site_name", "application-name"]).or(site);
+    let image_url = first(&["og:image:secure_url", "og:image", "og:image:url", "twitter:image", "twitter:image:src"])
+        .or_else(|| select_attr(&html, r#"link[rel~="image_src"]"#, "href"))
+        .and_then(|src| absolute_http_url(base.as_ref(), &src));
+
+    LinkPreview {
+        title: title.and_then(|t| clean(&t, MAX_TITLE_CHARS)),
+        site_name: site_name.and_then(|s| clean(&s, MAX_SITE_NAME_CHARS)),
+        description: description.and_then(|d| clean(&d, MAX_DESCRIPTION_CHARS)),
+        image_url,
+    }
+}
+
+/// `<meta>` contents keyed by lowercased `property` or `name`; the first occurrence wins.
+fn meta_tags(html: &Html) -> HashMap<String, String> {
+    let selector = Selector::parse("meta[content]").expect("valid selector");
+    let mut tags = HashMap::new();
+    for element in html.select(&selector) {
+        let attrs = element.value();
+        let Some(key) = attrs.attr("property").or_else(|| attrs.attr("name")) else { continue };
+        let Some(content) = attrs.attr("content").filter(|c| !c.trim().is_empty()) else { continue };
+        tags.entry(key.trim().to_ascii_lowercase()).or_insert_with(|| content.to_string());
+    }
+    tags
+}
+
+fn select_text(html: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    html.select(&selector).next().map(|e| e.text().collect())
+}
+
+fn select_attr(html: &Html, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    html.select(&selector).find_map(|e| e.value().attr(attr).map(str::to_string))
+}
+
+/// Image links are often relative or protocol-relative; only http(s) results are kept.
+fn absolute_http_url(base: Option<&Url>, src: &str) -> Option<String> {
+    let url = match base {
+        Some(base) => base.join(src.trim()).ok()?,
+        None => Url::parse(src.trim()).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then(|| url.into())
+}
+
+fn site_of(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+/// Collapses whitespace and cuts to `max_chars`; blank values become `None`.
+fn clean(raw: &str, max_chars: usize) -> Option<String> {
+    let collapsed = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    if collapsed.chars().count() <= max_chars {
+        return Some(collapsed);
+    }
+    let mut cut: String = collapsed.chars().take(max_chars - 1).collect();
+    cut.push('…');
+    Some(cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_preview;
+    use crate::domain::previews::{FetchedPage, LinkPreview};
+
+    fn html_page(url: &str, body: &str) -> FetchedPage {
+        FetchedPage { url: url.to_string(), content_type: Some("text/html".to_string()), body: body.to_string() }
+    }
+
+    #[test]
+    fn opengraph_wins_over_twitter_cards_and_plain_html() {
+        let page = html_page("https://www.example.com/post", r#"<head>
+            <title>Plain title</title>
+            <meta name="description" content="Plain description">
+            <meta name="twitter:title" content="Twitter title">
+            <meta property="og:title" content="OG title">
+            <meta property="og:site_name" content="Example">
+        </head>"#);
+        let preview = extract_preview(&page);
+        assert_eq!(preview.title.as_deref(), Some("OG title"));
+        assert_eq!(preview.site_name.as_deref(), Some("Example"));
+        assert_eq!(preview.description.as_deref(), Some("Plain description"));
+    }
+
+    #[test]
+    fn falls_back_to_the_title_tag_and_the_host() {
+        let preview = extract_preview(&html_page("https://www.example.com/post", "<title>\n  Just   a title </title>"));
+        assert_eq!(preview.title.as_deref(), Some("Just a title"));
+        assert_eq!(preview.site_name.as_deref(), Some("example.com"));
+        assert_eq!(preview.image_url, None);
+    }
+
+    #[test]
+    fn resolves_images_against_the_final_url() {
+        let page = html_page("https://example.com/a/b", r#"<meta property="og:image" content="../img/cover.png">"#);
+        assert_eq!(extract_preview(&page).image_url.as_deref(), Some("https://example.com/img/cover.png"));
+
+        let page = html_page("https://example.com/", r#"<meta property="og:image" content="javascript:alert(1)">"#);
+        assert_eq!(extract_preview(&page).image_url, None);
+    }
+
+    #[test]
+    fn an_image_link_previews_as_the_image() {
+        let page = FetchedPage {
+            url: "https://cdn.example.com/cat.jpg".to_string(),
+            content_type: Some("Image/JPEG".to_string()),
+            body: String::new(),
+        };
+        assert_eq!(extract_preview(&page), LinkPreview {
+            site_name: Some("cdn.example.com".to_string()),
+            image_url: Some("https://cdn.example.com/cat.jpg".to_string()),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn long_titles_are_cut_and_blank_ones_dropped() {
+        let long = "x".repeat(400);
+        let page = html_page("https://example.com/", &format!(r#"<meta property="og:title" content="{long}"><meta property="og:description" content="   ">"#));
+        let preview = extract_preview(&page);
+        let title = preview.title.unwrap();
+        assert_eq!(title.chars().count(), 300);
+        assert!(title.ends_with('…'));
+        assert_eq!(preview.description, None);
+    }
+}
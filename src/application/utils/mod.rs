@@ -1,2 +1,7 @@
-pub mod profanity;
+pub mod content_policy;
+pub mod identity;
+pub mod link_meta;
+pub mod spam;
+pub mod text_diff;
+pub mod url_canon;
 pub mod validation;
\ No newline at end of file
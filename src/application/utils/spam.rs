@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use url::Url;
+use uuid::Uuid;
+
+use crate::application::error::AppError;
+use crate::application::utils::validation;
+use crate::domain::spam::{AuthorActivity, DomainReputation, SpamRepository};
+
+/// Posting three or more times within this many minutes counts against the author.
+const VELOCITY_WINDOW_MINUTES: i64 = 10;
+const DUPLICATE_WINDOW_HOURS: i64 = 24;
+/// Texts shorter than this many words ("thanks!") are too common to call duplicates.
+const MIN_DUPLICATE_WORDS: usize = 5;
+/// A domain needs this many removals, outnumbering approvals two to one, to count as spammy.
+const MIN_BAD_REMOVALS: i32 = 3;
+const MANY_LINKS: usize = 5;
+
+/// Scores new posts and comments from signals about the author and the links they carry;
+/// content at or above the hold score goes to the moderation queue instead of the feeds.
+pub struct SpamScorer {
+    repo: Arc<dyn SpamRepository>,
+    hold_score: f64,
+}
+
+/// Each signal adds its weight to the score.
+#[derive(Debug, Clone)]
+pub struct SpamScore {
+    pub score: f64,
+    pub signals: Vec<String>,
+}
+
+impl SpamScore {
+    fn add(&mut self, weight: f64, signal: impl Into<String>) {
+        self.score += weight;
+        self.signals.push(signal.into());
+    }
+
+    pub fn reason(&self) -> String {
+        format!("spam score {:.2}: {}", self.score, self.signals.join(", "))
+    }
+}
+
+impl SpamScorer {
+    pub fn new(repo: Arc<dyn SpamRepository>, hold_score: f64) -> Self {
+        Self { repo, hold_score }
+    }
+
+    /// Returns why the content must be held, if it must. `text` is everything the author wrote,
+    /// links included; `url` is the post's link, if any.
+    pub async fn check(&self, user_id: Uuid, text: &str, url: Option<&str>) -> Result<Option<String>, AppError> {
+        let now = Utc::now();
+        let Some(activity) = self.repo.author_activity(
+            user_id,
+            now - Duration::minutes(VELOCITY_WINDOW_MINUTES),
+            now - Duration::hours(DUPLICATE_WINDOW_HOURS),
+        ).await? else {
+            return Err(AppError::not_found("user not found"));
+        };
+        let domains = link_domains(url, text);
+        let reputations = if domains.is_empty() { Vec::new() } else { self.repo.reputations(&domains).await? };
+
+        let score = self.score(&activity, &reputations, text, &domains, now);
+        if score.score >= self.hold_score {
+            tracing::info!(%user_id, score = score.score, signals = ?score.signals, "holding likely spam");
+            return Ok(Some(score.reason()));
+        }
+        Ok(None)
+    }
+
+    fn score(&self, activity: &AuthorActivity, reputations: &[DomainReputation], text: &str, domains: &[String], now: DateTime<Utc>) -> SpamScore {
+        let mut score = SpamScore { score: 0.0, signals: Vec::new() };
+
+        let age = now - activity.account_created_at;
+        if age < Duration::days(1) {
+            score.add(0.4, "account less than a day old");
+        } else if age < Duration::days(7) {
+            score.add(0.2, "account less than a week old");
+        }
+
+        if activity.karma < 0 {
+            score.add(0.3, "negative karma");
+        }
+
+        if activity.recent_count >= 5 {
+            score.add(0.5, format!("{} posts in {VELOCITY_WINDOW_MINUTES} minutes", activity.recent_count));
+        } else if activity.recent_count >= 3 {
+            score.add(0.25, format!("{} posts in {VELOCITY_WINDOW_MINUTES} minutes", activity.recent_count));
+        }
+
+        let words = word_set(text);
+        if words.len() >= MIN_DUPLICATE_WORDS {
+            let closest = activity.recent_texts.iter()
+                .map(|earlier| similarity(&words, &word_set(earlier)))
+                .fold(0.0, f64::max);
+            if closest >= 0.9 {
+                score.add(0.6, "repeats an earlier post");
+            } else if closest >= 0.7 {
+                score.add(0.3, "nearly repeats an earlier post");
+            }
+        }
+
+        for rep in reputations {
+            if rep.removed >= MIN_BAD_REMOVALS && rep.removed >= 2 * rep.approved {
+                score.add(0.6, format!("domain {} is often removed", rep.domain));
+            }
+        }
+        if domains.len() >= MANY_LINKS {
+            score.add(0.3, format!("links to {} domains", domains.len()));
+        }
+
+        score
+    }
+}
+
+/// Normalized, deduplicated domains of the post's link and of any http(s) links in `text`.
+pub fn link_domains(url: Option<&str>, text: &str) -> Vec<String> {
+    let links = text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| matches!(c, '<' | '>' | '(' | ')' | '[' | ']' | '"' | '\'' | ',' | '.')))
+        .filter(|word| word.starts_with("http://") || word.starts_with("https://"));
+
+    let mut domains = Vec::new();
+    for link in url.into_iter().chain(links) {
+        if let Some(host) = Url::parse(link).ok().and_then(|u| u.host_str().map(validation::normalize_domain)) {
+            if !domains.contains(&host) {
+                domains.push(host);
+            }
+        }
+    }
+    domains
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of the two texts' words.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{DateTime, Duration, Utc};
+    use uuid::Uuid;
+
+    use super::{link_domains, SpamScorer};
+    use crate::domain::spam::{AuthorActivity, DomainReputation, SpamRepository};
+
+    /// `score` never reads from the repository.
+    struct Unused;
+
+    #[async_trait::async_trait]
+    impl SpamRepository for Unused {
+        async fn author_activity(&self, _: Uuid, _: DateTime<Utc>, _: DateTime<Utc>) -> anyhow::Result<Option<AuthorActivity>> {
+            unreachable!()
+        }
+        async fn reputations(&self, _: &[String]) -> anyhow::Result<Vec<DomainReputation>> {
+            unreachable!()
+        }
+    }
+
+    fn scorer() -> SpamScorer {
+        SpamScorer::new(Arc::new(Unused), 1.0)
+    }
+
+    /// A month-old account in good standing that hasn't posted lately.
+    fn regular(now: DateTime<Utc>) -> AuthorActivity {
+        AuthorActivity { account_created_at: now - Duration::days(30), karma: 10, recent_count: 0, recent_texts: Vec::new() }
+    }
+
+    fn reputation(domain: &str, approved: i32, removed: i32) -> DomainReputation {
+        DomainReputation { domain: domain.to_string(), approved, removed }
+    }
+
+    #[test]
+    fn a_regular_author_scores_nothing() {
+        let now = Utc::now();
+        let score = scorer().score(&regular(now), &[], "a perfectly normal post", &[], now);
+        assert_eq!(score.score, 0.0);
+        assert!(score.signals.is_empty());
+    }
+
+    #[test]
+    fn new_accounts_negative_karma_and_bursts_add_up() {
+        let now = Utc::now();
+        let activity = AuthorActivity { account_created_at: now - Duration::hours(2), karma: -3, recent_count: 5, ..regular(now) };
+        let score = scorer().score(&activity, &[], "hello", &[], now);
+        assert!((score.score - 1.2).abs() < 1e-9, "{}", score.score);
+        assert_eq!(score.signals, ["account less than a day old", "negative karma", "5 posts in 10 minutes"]);
+
+        let activity = AuthorActivity { account_created_at: now - Duration::days(3), recent_count: 3, ..regular(now) };
+        let score = scorer().score(&activity, &[], "hello", &[], now);
+        assert!((score.score - 0.45).abs() < 1e-9, "{}", score.score);
+    }
+
+    #[test]
+    fn repeating_an_earlier_post_counts_unless_it_is_short() {
+        let now = Utc::now();
+        let text = "buy cheap watches at our store today";
+        let activity = AuthorActivity { recent_texts: vec!["Buy cheap watches at our store, today!".to_string()], ..regular(now) };
+        assert_eq!(scorer().score(&activity, &[], text, &[], now).signals, ["repeats an earlier post"]);
+
+        let activity = AuthorActivity { recent_texts: vec!["buy cheap watches at our shop today".to_string()], ..regular(now) };
+        assert_eq!(scorer().score(&activity, &[], text, &[], now).signals, ["nearly repeats an earlier post"]);
+
+        let activity = AuthorActivity { recent_texts: vec!["thanks a lot".to_string()], ..regular(now) };
+        assert!(scorer().score(&activity, &[], "thanks a lot", &[], now).signals.is_empty());
+    }
+
+    #[test]
+    fn domains_mostly_removed_count_against_the_post() {
+        let now = Utc::now();
+        let reputations = [reputation("bad.example", 1, 3), reputation("mixed.example", 2, 3), reputation("new.example", 0, 2)];
+        let score = scorer().score(&regular(now), &reputations, "", &[], now);
+        assert_eq!(score.signals, ["domain bad.example is often removed"]);
+    }
+
+    #[test]
+    fn many_links_count_against_the_post() {
+        let now = Utc::now();
+        let domains: Vec<String> = (0..5).map(|i| format!("site{i}.example")).collect();
+        assert_eq!(scorer().score(&regular(now), &[], "", &domains, now).signals, ["links to 5 domains"]);
+    }
+
+    #[test]
+    fn link_domains_are_normalized_and_deduplicated() {
+        let text = "see (https://WWW.Example.com/a), http://example.com/b and <https://other.example/c>. not ftp://x.example";
+        assert_eq!(link_domains(Some("https://news.example/post"), text), ["news.example", "example.com", "other.example"]);
+    }
+}
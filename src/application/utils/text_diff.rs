@@ -0,0 +1,41 @@
+use similar::TextDiff;
+
+/// Line-based unified diff from `old` to `new` with three lines of context; `None` when they're equal.
+pub fn unified(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    Some(TextDiff::from_lines(old, new).unified_diff().context_radius(3).missing_newline_hint(false).header("before", "after").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unified;
+
+    #[test]
+    fn equal_texts_have_no_diff() {
+        assert_eq!(unified("", ""), None);
+        assert_eq!(unified("same\ntext", "same\ntext"), None);
+    }
+
+    #[test]
+    fn changed_lines_come_with_their_context() {
+        let diff = unified("a\nb\nc\n", "a\nB\nc\n").unwrap();
+        assert_eq!(diff, "--- before\n+++ after\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+    }
+
+    #[test]
+    fn context_stops_three_lines_out() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        let diff = unified(old, new).unwrap();
+        assert_eq!(diff, "--- before\n+++ after\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n");
+    }
+
+    #[test]
+    fn a_missing_final_newline_is_not_called_out() {
+        let diff = unified("title", "new title").unwrap();
+        assert_eq!(diff, "--- before\n+++ after\n@@ -1 +1 @@\n-title\n+new title\n");
+        assert!(!diff.contains("No newline"));
+    }
+}
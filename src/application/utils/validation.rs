@@ -8,6 +8,54 @@ pub fn validate_http_url(opt: &Option<String>) -> Result<(), ValidationError> {
         if scheme != "http" && scheme != "https" {
             return Err(ValidationError::new("unsupported_scheme"));
         }
+        if parsed.host_str().is_none_or(str::is_empty) {
+            return Err(ValidationError::new("missing_host"));
+        }
+    }
+    Ok(())
+}
+
+/// Usernames end up in URLs (`/users/{username}`), so keep them to a URL-safe alphabet.
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let len = username.chars().count();
+    if !(3..=40).contains(&len) {
+        return Err(ValidationError::new("username_length"));
+    }
+    if !username.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err(ValidationError::new("username_charset"));
+    }
+    Ok(())
+}
+
+/// Community slugs appear in `/c/{slug}` URLs; lowercase ASCII keeps them unambiguous.
+pub fn validate_community_slug(slug: &str) -> Result<(), ValidationError> {
+    if !(3..=32).contains(&slug.len()) {
+        return Err(ValidationError::new("slug_length"));
+    }
+    if !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+        return Err(ValidationError::new("slug_charset"));
+    }
+    Ok(())
+}
+
+/// Lowercase host without a leading `www.`, the form stored in `posts.domain`.
+pub fn normalize_domain(raw: &str) -> String {
+    let domain = raw.trim().trim_end_matches('.').to_lowercase();
+    domain.strip_prefix("www.").map(str::to_string).unwrap_or(domain)
+}
+
+/// A bare hostname like `example.com`; internationalized names must be given in punycode.
+pub fn validate_domain(domain: &str) -> Result<(), ValidationError> {
+    if domain.len() > 253 || !domain.contains('.') {
+        return Err(ValidationError::new("domain_format"));
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty() && label.len() <= 63
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !label.starts_with('-') && !label.ends_with('-')
+    };
+    if !domain.split('.').all(valid_label) {
+        return Err(ValidationError::new("domain_format"));
     }
     Ok(())
 }
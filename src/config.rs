@@ -4,6 +4,66 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
     pub bind_addr: SocketAddr,
+    pub blob_store: BlobStoreConfig,
+    pub search: SearchConfig,
+    pub link_previews: LinkPreviewConfig,
+    pub client_ip_source: ClientIpSource,
+    /// `PUBLIC_MOD_LOG=on` serves `/api/modlog`, the moderation log without moderator names.
+    pub public_mod_log: bool,
+    /// `CONTENT_FILTER_DICTIONARY` points at a JSON dictionary to screen posts and comments with,
+    /// in place of the built-in one.
+    pub content_filter_dictionary: Option<String>,
+    pub spam: SpamConfig,
+    /// Posts older than `POST_ARCHIVE_DAYS` (default 180) are archived; `POST_ARCHIVE_DAYS=off` keeps them open.
+    pub archive_after_days: Option<i64>,
+    pub revisions: RevisionConfig,
+}
+
+/// Where uploaded files (avatars) live. `BLOB_STORE=s3` also works against MinIO via `S3_ENDPOINT`.
+pub enum BlobStoreConfig {
+    Local { root: String },
+    S3 { bucket: String, region: String, endpoint: Option<String> },
+}
+
+/// Which `SearchIndex` serves `/api/search`. The Tantivy index lives on local disk and is
+/// filled by the running server; build it once with `lotus_news_service reindex`.
+pub enum SearchConfig {
+    Postgres,
+    Tantivy { dir: String },
+}
+
+/// Background fetching of previews for link posts. `LINK_PREVIEWS=off` turns it off; the
+/// timeout covers the whole fetch including redirects, and bodies are cut at `max_bytes`.
+pub enum LinkPreviewConfig {
+    Disabled,
+    Http { timeout_secs: u64, max_bytes: usize },
+}
+
+/// New posts and comments scoring `SPAM_HOLD_SCORE` (default 1.0) or more are held for review.
+/// `SPAM_BANNED_DOMAINS` is a comma-separated list added to the domain rules as `review` on start;
+/// see `domain_service::seed_banned_domains`.
+#[derive(Debug, Clone)]
+pub struct SpamConfig {
+    pub hold_score: f64,
+    pub banned_domains: Vec<String>,
+}
+
+/// Edits within `EDIT_GRACE_SECONDS` (default 180) of posting don't mark a post or comment as edited,
+/// and are left out of the history shown to non-moderators. Moderators can always read the history;
+/// `PUBLIC_REVISIONS=on` lets everyone else read it too.
+#[derive(Debug, Clone, Copy)]
+pub struct RevisionConfig {
+    pub grace_secs: i64,
+    pub public: bool,
+}
+
+/// Where a request's client address is read from. Behind a reverse proxy (Heroku's router
+/// included) the peer is the proxy, so `CLIENT_IP_SOURCE=x-forwarded-for` takes the last
+/// address in that header instead, the one the proxy itself appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientIpSource {
+    Peer,
+    XForwardedFor,
 }
 
 impl Config {
@@ -12,6 +72,73 @@ impl Config {
         let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
         let bind_addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".into())
             .parse().expect("invalid BIND_ADDR");
-        Self { database_url: database_url, jwt_secret: jwt_secret, bind_addr: bind_addr}
+        let blob_store = match std::env::var("BLOB_STORE").as_deref() {
+            Ok("s3") => BlobStoreConfig::S3 {
+                bucket: std::env::var("S3_BUCKET").expect("S3_BUCKET must be set when BLOB_STORE=s3"),
+                region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".into()),
+                endpoint: std::env::var("S3_ENDPOINT").ok(),
+            },
+            Ok("local") | Err(_) => BlobStoreConfig::Local {
+                root: std::env::var("BLOB_DIR").unwrap_or_else(|_| "./data/blobs".into()),
+            },
+            Ok(other) => panic!("invalid BLOB_STORE {other:?}, expected local or s3"),
+        };
+        let search = match std::env::var("SEARCH_BACKEND").as_deref() {
+            Ok("tantivy") => SearchConfig::Tantivy {
+                dir: std::env::var("SEARCH_INDEX_DIR").unwrap_or_else(|_| "./data/search-index".into()),
+            },
+            Ok("postgres") | Err(_) => SearchConfig::Postgres,
+            Ok(other) => panic!("invalid SEARCH_BACKEND {other:?}, expected postgres or tantivy"),
+        };
+        let link_previews = match std::env::var("LINK_PREVIEWS").as_deref() {
+            Ok("off") => LinkPreviewConfig::Disabled,
+            Ok("on") | Err(_) => LinkPreviewConfig::Http {
+                timeout_secs: std::env::var("LINK_PREVIEW_TIMEOUT_SECS").ok()
+                    .map(|s| s.parse().expect("invalid LINK_PREVIEW_TIMEOUT_SECS"))
+                    .unwrap_or(5),
+                max_bytes: std::env::var("LINK_PREVIEW_MAX_BYTES").ok()
+                    .map(|s| s.parse().expect("invalid LINK_PREVIEW_MAX_BYTES"))
+                    .unwrap_or(512 * 1024),
+            },
+            Ok(other) => panic!("invalid LINK_PREVIEWS {other:?}, expected on or off"),
+        };
+        let client_ip_source = match std::env::var("CLIENT_IP_SOURCE").as_deref() {
+            Ok("x-forwarded-for") => ClientIpSource::XForwardedFor,
+            Ok("peer") | Err(_) => ClientIpSource::Peer,
+            Ok(other) => panic!("invalid CLIENT_IP_SOURCE {other:?}, expected peer or x-forwarded-for"),
+        };
+        let public_mod_log = match std::env::var("PUBLIC_MOD_LOG").as_deref() {
+            Ok("on") => true,
+            Ok("off") | Err(_) => false,
+            Ok(other) => panic!("invalid PUBLIC_MOD_LOG {other:?}, expected on or off"),
+        };
+        let content_filter_dictionary = std::env::var("CONTENT_FILTER_DICTIONARY").ok();
+        let spam = SpamConfig {
+            hold_score: std::env::var("SPAM_HOLD_SCORE").ok()
+                .map(|s| s.parse().expect("invalid SPAM_HOLD_SCORE"))
+                .unwrap_or(1.0),
+            banned_domains: std::env::var("SPAM_BANNED_DOMAINS").unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect(),
+        };
+        let archive_after_days = match std::env::var("POST_ARCHIVE_DAYS").as_deref() {
+            Ok("off") => None,
+            Ok(days) => Some(days.parse().ok().filter(|d| *d > 0).expect("invalid POST_ARCHIVE_DAYS")),
+            Err(_) => Some(180),
+        };
+        let revisions = RevisionConfig {
+            grace_secs: std::env::var("EDIT_GRACE_SECONDS").ok()
+                .map(|s| s.parse().ok().filter(|s| *s >= 0).expect("invalid EDIT_GRACE_SECONDS"))
+                .unwrap_or(180),
+            public: match std::env::var("PUBLIC_REVISIONS").as_deref() {
+                Ok("on") => true,
+                Ok("off") | Err(_) => false,
+                Ok(other) => panic!("invalid PUBLIC_REVISIONS {other:?}, expected on or off"),
+            },
+        };
+        Self { database_url, jwt_secret, bind_addr, blob_store, search, link_previews, client_ip_source, public_mod_log, content_filter_dictionary, spam, archive_after_days, revisions }
     }
-}
\ No newline at end of file
+}
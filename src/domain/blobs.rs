@@ -0,0 +1,14 @@
+
+#[derive(Debug, Clone)]
+pub struct Blob {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Storage for uploaded binary content, addressed by slash-separated keys like `avatars/<user>/<version>/64.png`.
+#[async_trait::async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, blob: Blob) -> anyhow::Result<()>;
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Blob>>;
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
@@ -1,5 +1,11 @@
 
-#[derive(Debug, Clone)]
+use serde::Serialize;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::domain::posts::{FeedCursor, FeedSort};
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct Comment {
     pub id: Uuid,
     pub post_id: Uuid,
@@ -7,10 +13,45 @@ pub struct Comment {
     pub parent_id: Option<Uuid>,
     pub body: String,
     pub created_at: DateTime<Utc>,
+    pub author_username: String,
+    /// Set when the author deleted the comment.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Set when a moderator removed the comment.
+    pub removed_at: Option<DateTime<Utc>>,
+    pub removal_reason: Option<String>,
+    /// Set while the content filter holds the comment for review; only its author sees it meanwhile.
+    pub held_at: Option<DateTime<Utc>>,
+    /// Time of the last edit made after the grace window.
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Direct replies, whatever their state; what `FeedSort::Top` ranks comments by.
+    pub reply_count: i64,
+    /// Set in threads on comments the viewer mustn't see, because they are held or their author is
+    /// shadowbanned; they are still returned so that their replies keep their place.
+    #[serde(skip)]
+    pub hidden: bool,
+}
+
+impl Comment {
+    pub fn is_visible(&self) -> bool {
+        self.deleted_at.is_none() && self.removed_at.is_none()
+    }
 }
 
 #[async_trait::async_trait]
 pub trait CommentRepository: Send + Sync {
-    async fn create(&self, post_id: Uuid, user_id: Uuid, parent_id: Option<Uuid>, body: &str) -> anyhow::Result<Comment>;
-    async fn list_for_post(&self, post_id: Uuid) -> anyhow::Result<Vec<Comment>>;
-}
\ No newline at end of file
+    /// A `hold_reason` holds the comment for review from the start.
+    async fn create(&self, post_id: Uuid, user_id: Uuid, parent_id: Option<Uuid>, body: &str, hold_reason: Option<&str>) -> anyhow::Result<Comment>;
+    async fn find_by_id(&self, comment_id: Uuid) -> anyhow::Result<Option<Comment>>;
+    /// Includes deleted and removed comments; `find_by_id` does too.
+    /// Held comments and those by shadowbanned users come back `hidden` unless `viewer` wrote them;
+    /// `list_by_author` leaves them out.
+    async fn list_for_post(&self, post_id: Uuid, viewer: Option<Uuid>) -> anyhow::Result<Vec<Comment>>;
+    /// Leaves out comments on posts in private communities, as the author's post history does.
+    async fn list_by_author(&self, user_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Comment>>;
+    /// Marks the comment deleted by its author; the row stays so its replies keep their place.
+    /// Returns false if it was already deleted or doesn't exist. Moderators remove comments through a `ModerationTx`.
+    async fn delete(&self, comment_id: Uuid) -> anyhow::Result<bool>;
+    /// Replaces the body, keeping the old one as a revision by `editor_id` if it changed; `mark_edited`
+    /// then sets `edited_at`. A `hold_reason` holds the comment for review.
+    async fn update(&self, comment_id: Uuid, editor_id: Uuid, body: &str, hold_reason: Option<&str>, mark_edited: bool) -> anyhow::Result<Comment>;
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Posts made without naming a community go here.
+pub const DEFAULT_COMMUNITY_SLUG: &str = "general";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Anyone can read and post.
+    Public,
+    /// Anyone can read; only members can post, and only moderators can add members.
+    Restricted,
+    /// Only members can read or post; posts never appear in site-wide feeds or search.
+    Private,
+}
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Restricted => "restricted",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+impl From<&str> for Visibility {
+    fn from(value: &str) -> Self {
+        match value {
+            "restricted" => Visibility::Restricted,
+            "private" => Visibility::Private,
+            _ => Visibility::Public,
+        }
+    }
+}
+
+impl From<String> for Visibility {
+    fn from(value: String) -> Self {
+        Visibility::from(value.as_str())
+    }
+}
+
+/// A member's role within one community; ordered so `>=` means "at least".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberRole {
+    Member,
+    Moderator,
+    Owner,
+}
+
+impl MemberRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemberRole::Member => "member",
+            MemberRole::Moderator => "moderator",
+            MemberRole::Owner => "owner",
+        }
+    }
+}
+
+impl From<&str> for MemberRole {
+    fn from(value: &str) -> Self {
+        match value {
+            "moderator" => MemberRole::Moderator,
+            "owner" => MemberRole::Owner,
+            _ => MemberRole::Member,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Community {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub rules: Vec<String>,
+    pub visibility: Visibility,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Community {
+    /// `role` is the viewer's membership, if any.
+    pub fn can_read(&self, role: Option<MemberRole>) -> bool {
+        self.visibility != Visibility::Private || role.is_some()
+    }
+
+    pub fn can_post(&self, role: Option<MemberRole>) -> bool {
+        self.visibility == Visibility::Public || role.is_some()
+    }
+}
+
+#[async_trait::async_trait]
+pub trait CommunityRepository: Send + Sync {
+    /// Creates the community with `owner_id` as its owner. Returns `None` if the slug is taken.
+    async fn create(&self, slug: &str, name: &str, description: &str, rules: &[String], visibility: Visibility, owner_id: Uuid) -> anyhow::Result<Option<Community>>;
+    async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Community>>;
+    async fn find_by_id(&self, community_id: Uuid) -> anyhow::Result<Option<Community>>;
+    async fn update(&self, community_id: Uuid, name: &str, description: &str, rules: &[String], visibility: Visibility) -> anyhow::Result<Community>;
+    async fn member_role(&self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<Option<MemberRole>>;
+    /// Adds the user or changes their role.
+    async fn set_member(&self, community_id: Uuid, user_id: Uuid, role: MemberRole) -> anyhow::Result<()>;
+    /// Returns false if the user was not a member.
+    async fn remove_member(&self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<bool>;
+    async fn list_joined(&self, user_id: Uuid) -> anyhow::Result<Vec<Community>>;
+}
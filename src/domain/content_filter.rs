@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// How bad a dictionary term is; each community maps severities to actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+/// Ordered from mildest to harshest; when several terms match, the harshest action wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Star out the term and let the text through.
+    Mask,
+    /// Accept the text but keep it out of sight until a moderator approves it.
+    Hold,
+    /// Refuse the text.
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterMatch {
+    /// The dictionary term that matched, not the text as written.
+    pub term: String,
+    pub action: FilterAction,
+}
+
+#[derive(Debug, Clone)]
+pub struct Verdict {
+    /// The harshest action among `matches`; `None` when nothing matched.
+    pub action: Option<FilterAction>,
+    pub matches: Vec<FilterMatch>,
+    /// The checked text with every `Mask` match starred out.
+    pub text: String,
+}
+
+impl Verdict {
+    /// Terms behind the verdict's action, for telling users and moderators why.
+    pub fn reason(&self) -> String {
+        let mut terms: Vec<&str> = self.matches.iter()
+            .filter(|m| Some(m.action) == self.action)
+            .map(|m| m.term.as_str())
+            .collect();
+        terms.sort_unstable();
+        terms.dedup();
+        terms.join(", ")
+    }
+}
+
+/// Screens user-written text. `community` is the slug of the community the text is posted in,
+/// whose overrides apply on top of the site-wide dictionary.
+pub trait ContentFilter: Send + Sync {
+    fn check(&self, community: Option<&str>, text: &str) -> Verdict;
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainAction {
+    /// Links to the domain are refused.
+    Block,
+    /// Links are let through, even where a rule on a parent domain says otherwise.
+    Allow,
+    /// Posts linking to the domain are held for a moderator's approval.
+    Review,
+    /// Each user may link to the domain at most `max_posts` times per `window_hours`.
+    RateLimit,
+}
+
+impl DomainAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DomainAction::Block => "block",
+            DomainAction::Allow => "allow",
+            DomainAction::Review => "review",
+            DomainAction::RateLimit => "rate_limit",
+        }
+    }
+}
+
+impl From<&str> for DomainAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "block" => DomainAction::Block,
+            "review" => DomainAction::Review,
+            "rate_limit" => DomainAction::RateLimit,
+            _ => DomainAction::Allow,
+        }
+    }
+}
+
+/// A rule covers the domain and all of its subdomains; the most specific rule wins.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainRule {
+    pub domain: String,
+    pub action: DomainAction,
+    /// Only set for `RateLimit`.
+    pub max_posts: Option<i32>,
+    pub window_hours: Option<i32>,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Posts linking to a domain or its subdomains. Counts cover posts in public communities only.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainStats {
+    pub domain: String,
+    pub post_count: i64,
+    pub author_count: i64,
+    pub total_score: i64,
+    /// Posts a moderator took down.
+    pub removed_count: i64,
+    pub first_posted_at: Option<DateTime<Utc>>,
+    pub last_posted_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait::async_trait]
+pub trait DomainRepository: Send + Sync {
+    /// The most specific rule covering `domain`: its own, or else its closest parent's.
+    async fn rule_for(&self, domain: &str) -> anyhow::Result<Option<DomainRule>>;
+    /// Every rule, by domain.
+    async fn list_rules(&self) -> anyhow::Result<Vec<DomainRule>>;
+    /// Creates the domain's rule, or replaces the one it has.
+    async fn upsert_rule(&self, domain: &str, action: DomainAction, max_posts: Option<i32>, window_hours: Option<i32>, reason: &str, created_by: Uuid) -> anyhow::Result<DomainRule>;
+    /// Adds a rule nobody in particular set, unless the domain has one already; returns false if it had.
+    async fn insert_rule(&self, domain: &str, action: DomainAction, reason: &str) -> anyhow::Result<bool>;
+    /// Returns false if the domain had no rule.
+    async fn delete_rule(&self, domain: &str) -> anyhow::Result<bool>;
+    /// Posts by `user_id` linking to `domain` or its subdomains since `since`, deleted and removed ones included.
+    async fn count_recent_posts(&self, user_id: Uuid, domain: &str, since: DateTime<Utc>) -> anyhow::Result<i64>;
+    async fn stats(&self, domain: &str) -> anyhow::Result<DomainStats>;
+}
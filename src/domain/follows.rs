@@ -0,0 +1,17 @@
+use uuid::Uuid;
+
+#[async_trait::async_trait]
+pub trait FollowRepository: Send + Sync {
+    /// Returns false if the user was already followed.
+    async fn follow_user(&self, follower_id: Uuid, followee_id: Uuid) -> anyhow::Result<bool>;
+    /// Returns false if the user was not followed.
+    async fn unfollow_user(&self, follower_id: Uuid, followee_id: Uuid) -> anyhow::Result<bool>;
+    async fn follow_tag(&self, user_id: Uuid, tag_id: Uuid) -> anyhow::Result<bool>;
+    async fn unfollow_tag(&self, user_id: Uuid, tag_id: Uuid) -> anyhow::Result<bool>;
+    /// Usernames the user follows, alphabetical.
+    async fn followed_usernames(&self, user_id: Uuid) -> anyhow::Result<Vec<String>>;
+    /// Slugs of the tags the user follows, alphabetical.
+    async fn followed_tags(&self, user_id: Uuid) -> anyhow::Result<Vec<String>>;
+    /// Whether the user follows anyone or any tag, or has joined any community.
+    async fn has_subscriptions(&self, user_id: Uuid) -> anyhow::Result<bool>;
+}
@@ -1,3 +1,19 @@
 pub mod users;
 pub mod posts;
-pub mod votes;
\ No newline at end of file
+pub mod votes;
+pub mod comments;
+pub mod blobs;
+pub mod search;
+pub mod tags;
+pub mod communities;
+pub mod follows;
+pub mod saved;
+pub mod mutes;
+pub mod previews;
+pub mod reports;
+pub mod sanctions;
+pub mod mod_log;
+pub mod content_filter;
+pub mod spam;
+pub mod domains;
+pub mod revisions;
\ No newline at end of file
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::comments::Comment;
+use crate::domain::communities::MemberRole;
+use crate::domain::posts::{Post, PostEdit};
+use crate::domain::reports::{ReportTarget, Resolution};
+use crate::domain::sanctions::{Sanction, SanctionKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModActionKind {
+    PostEdit,
+    PostRemove,
+    PostRestore,
+    PostLock,
+    PostUnlock,
+    /// Pinned the post above the site-wide feeds, or moved it among the pins; `after` holds the position.
+    PostPin,
+    PostUnpin,
+    /// Released a post the content filter held for review.
+    PostApprove,
+    CommentRemove,
+    CommentRestore,
+    CommentApprove,
+    Ban,
+    Suspend,
+    Shadowban,
+    Unban,
+    Unsuspend,
+    Unshadowban,
+    /// A community member's role changed; `after` is empty when they were removed from the community.
+    RoleChange,
+    ReportResolve,
+}
+
+impl ModActionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModActionKind::PostEdit => "post_edit",
+            ModActionKind::PostRemove => "post_remove",
+            ModActionKind::PostRestore => "post_restore",
+            ModActionKind::PostLock => "post_lock",
+            ModActionKind::PostUnlock => "post_unlock",
+            ModActionKind::PostPin => "post_pin",
+            ModActionKind::PostUnpin => "post_unpin",
+            ModActionKind::PostApprove => "post_approve",
+            ModActionKind::CommentRemove => "comment_remove",
+            ModActionKind::CommentRestore => "comment_restore",
+            ModActionKind::CommentApprove => "comment_approve",
+            ModActionKind::Ban => "ban",
+            ModActionKind::Suspend => "suspend",
+            ModActionKind::Shadowban => "shadowban",
+            ModActionKind::Unban => "unban",
+            ModActionKind::Unsuspend => "unsuspend",
+            ModActionKind::Unshadowban => "unshadowban",
+            ModActionKind::RoleChange => "role_change",
+            ModActionKind::ReportResolve => "report_resolve",
+        }
+    }
+}
+
+impl TryFrom<&str> for ModActionKind {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "post_edit" => ModActionKind::PostEdit,
+            "post_remove" => ModActionKind::PostRemove,
+            "post_restore" => ModActionKind::PostRestore,
+            "post_lock" => ModActionKind::PostLock,
+            "post_unlock" => ModActionKind::PostUnlock,
+            "post_pin" => ModActionKind::PostPin,
+            "post_unpin" => ModActionKind::PostUnpin,
+            "post_approve" => ModActionKind::PostApprove,
+            "comment_remove" => ModActionKind::CommentRemove,
+            "comment_restore" => ModActionKind::CommentRestore,
+            "comment_approve" => ModActionKind::CommentApprove,
+            "ban" => ModActionKind::Ban,
+            "suspend" => ModActionKind::Suspend,
+            "shadowban" => ModActionKind::Shadowban,
+            "unban" => ModActionKind::Unban,
+            "unsuspend" => ModActionKind::Unsuspend,
+            "unshadowban" => ModActionKind::Unshadowban,
+            "role_change" => ModActionKind::RoleChange,
+            "report_resolve" => ModActionKind::ReportResolve,
+            other => return Err(anyhow::anyhow!("unknown moderator action {other:?}")),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModTarget {
+    Post,
+    Comment,
+    User,
+}
+
+impl ModTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModTarget::Post => "post",
+            ModTarget::Comment => "comment",
+            ModTarget::User => "user",
+        }
+    }
+}
+
+impl TryFrom<&str> for ModTarget {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "post" => Ok(ModTarget::Post),
+            "comment" => Ok(ModTarget::Comment),
+            "user" => Ok(ModTarget::User),
+            other => Err(anyhow::anyhow!("unknown moderation target {other:?}")),
+        }
+    }
+}
+
+impl From<ReportTarget> for ModTarget {
+    fn from(value: ReportTarget) -> Self {
+        match value {
+            ReportTarget::Post => ModTarget::Post,
+            ReportTarget::Comment => ModTarget::Comment,
+        }
+    }
+}
+
+/// One entry of the moderation log. Entries are never changed or deleted once written.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModAction {
+    pub id: Uuid,
+    /// `None` in the public log.
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: ModActionKind,
+    pub target_type: ModTarget,
+    pub target_id: Uuid,
+    /// Slug of the community the action happened in; `None` for site-wide actions such as bans.
+    pub community: Option<String>,
+    pub reason: Option<String>,
+    /// The target as it was before and after the action, where that means anything.
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewModAction {
+    pub actor_id: Uuid,
+    pub action: ModActionKind,
+    pub target_type: ModTarget,
+    pub target_id: Uuid,
+    pub community_id: Option<Uuid>,
+    pub reason: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// Every field left as `None` matches anything.
+#[derive(Debug, Clone, Default)]
+pub struct ModLogFilter {
+    pub community_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<ModActionKind>,
+    pub target_type: Option<ModTarget>,
+    pub target_id: Option<Uuid>,
+    /// Leaves out private communities, and shadowbans: those only work while nobody can tell.
+    pub public: bool,
+}
+
+#[async_trait::async_trait]
+pub trait ModLogRepository: Send + Sync {
+    async fn record(&self, action: NewModAction) -> anyhow::Result<()>;
+    /// Newest first; paginated on `(created_at, id)`.
+    async fn list(&self, filter: &ModLogFilter, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<ModAction>>;
+}
+
+/// Starts moderation transactions.
+#[async_trait::async_trait]
+pub trait ModerationStore: Send + Sync {
+    async fn begin(&self) -> anyhow::Result<Box<dyn ModerationTx>>;
+}
+
+/// One moderator action in the making. The change, its log entry and the domain reputation it
+/// counts towards are written together or not at all; dropping it without `commit` rolls it back.
+/// State changes return false when there is nothing to change: the post is already removed,
+/// isn't held, doesn't exist, and so on.
+#[async_trait::async_trait]
+pub trait ModerationTx: Send {
+    /// `PostRepository::find_by_id`, as the transaction sees it.
+    async fn find_post(&mut self, post_id: Uuid) -> anyhow::Result<Option<Post>>;
+    /// `PostRepository::find_held`, as the transaction sees it.
+    async fn find_held_post(&mut self, post_id: Uuid) -> anyhow::Result<Option<Post>>;
+    /// `CommentRepository::find_by_id`, as the transaction sees it.
+    async fn find_comment(&mut self, comment_id: Uuid) -> anyhow::Result<Option<Comment>>;
+    /// `reason` may be empty.
+    async fn remove_post(&mut self, post_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool>;
+    /// Undoes `remove_post`. A post its author deleted stays deleted.
+    async fn restore_post(&mut self, post_id: Uuid) -> anyhow::Result<bool>;
+    /// Releases a held post.
+    async fn approve_post(&mut self, post_id: Uuid) -> anyhow::Result<bool>;
+    async fn lock_post(&mut self, post_id: Uuid) -> anyhow::Result<bool>;
+    async fn unlock_post(&mut self, post_id: Uuid) -> anyhow::Result<bool>;
+    /// Pins the post at `position`, or after the last pin when `None`; a pinned post just moves.
+    /// Pins already at `position` or after it move down one.
+    /// False if the post is deleted, removed or held.
+    async fn pin_post(&mut self, post_id: Uuid, position: Option<i32>) -> anyhow::Result<bool>;
+    async fn unpin_post(&mut self, post_id: Uuid) -> anyhow::Result<bool>;
+    /// `PostRepository::update`, on a moderator's behalf.
+    async fn edit_post(&mut self, post_id: Uuid, editor_id: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>>;
+    async fn remove_comment(&mut self, comment_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool>;
+    /// Undoes `remove_comment`. A comment its author deleted stays deleted.
+    async fn restore_comment(&mut self, comment_id: Uuid) -> anyhow::Result<bool>;
+    async fn approve_comment(&mut self, comment_id: Uuid) -> anyhow::Result<bool>;
+    /// `CommunityRepository::set_member`, inside the transaction.
+    async fn set_member(&mut self, community_id: Uuid, user_id: Uuid, role: MemberRole) -> anyhow::Result<()>;
+    /// `CommunityRepository::remove_member`, inside the transaction.
+    async fn remove_member(&mut self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<bool>;
+    /// Whether the item has an open report for spam.
+    async fn spam_reported(&mut self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<bool>;
+    /// Closes every open report on the item; returns how many.
+    async fn resolve_reports(&mut self, target_type: ReportTarget, target_id: Uuid, resolved_by: Uuid, resolution: Resolution, note: &str) -> anyhow::Result<u64>;
+    async fn create_sanction(&mut self, user_id: Uuid, kind: SanctionKind, reason: &str, created_by: Uuid, expires_at: Option<DateTime<Utc>>) -> anyhow::Result<Sanction>;
+    /// `None` if there is no unrevoked sanction with that id.
+    async fn revoke_sanction(&mut self, sanction_id: Uuid, revoked_by: Uuid) -> anyhow::Result<Option<Sanction>>;
+    async fn record(&mut self, action: NewModAction) -> anyhow::Result<()>;
+    /// Counts one moderator decision against each of `domains`.
+    async fn rate_domains(&mut self, domains: &[String], approved: bool) -> anyhow::Result<()>;
+    async fn commit(self: Box<Self>) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModActionKind, ModTarget};
+
+    #[test]
+    fn stored_names_read_back() {
+        use ModActionKind::*;
+        for kind in [
+            PostEdit, PostRemove, PostRestore, PostLock, PostUnlock, PostPin, PostUnpin, PostApprove,
+            CommentRemove, CommentRestore, CommentApprove, Ban, Suspend, Shadowban, Unban, Unsuspend,
+            Unshadowban, RoleChange, ReportResolve,
+        ] {
+            assert_eq!(ModActionKind::try_from(kind.as_str()).unwrap(), kind);
+        }
+        for target in [ModTarget::Post, ModTarget::Comment, ModTarget::User] {
+            assert_eq!(ModTarget::try_from(target.as_str()).unwrap(), target);
+        }
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert!(ModActionKind::try_from("post_delete").is_err());
+        assert!(ModActionKind::try_from("").is_err());
+        assert!(ModTarget::try_from("community").is_err());
+    }
+}
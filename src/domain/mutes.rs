@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::posts::Post;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MutedUser {
+    pub user_id: Uuid,
+    pub username: String,
+    pub muted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MutedDomain {
+    pub domain: String,
+    pub muted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HiddenEntry {
+    pub post_id: Uuid,
+    pub hidden_at: DateTime<Utc>,
+}
+
+/// A hidden post as listed under `/me/hidden`; paginated on `(hidden_at, id)`.
+#[derive(Debug, Serialize)]
+pub struct HiddenPost {
+    #[serde(flatten)]
+    pub post: Post,
+    pub hidden_at: DateTime<Utc>,
+}
+
+/// Everything one user has hidden or muted, in the forms search backends filter on.
+#[derive(Debug, Clone, Default)]
+pub struct FeedExclusions {
+    pub post_ids: Vec<Uuid>,
+    /// Normalized usernames of muted and shadowbanned authors.
+    pub authors: Vec<String>,
+    /// Muted domains; their subdomains are excluded too.
+    pub domains: Vec<String>,
+}
+
+impl FeedExclusions {
+    pub fn is_empty(&self) -> bool {
+        self.post_ids.is_empty() && self.authors.is_empty() && self.domains.is_empty()
+    }
+}
+
+/// Hides and mutes only affect what the user themselves sees. The `bool` results report
+/// whether anything changed.
+#[async_trait::async_trait]
+pub trait MuteRepository: Send + Sync {
+    async fn hide_post(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool>;
+    async fn unhide_post(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool>;
+    async fn list_hidden(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<HiddenEntry>>;
+    async fn mute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> anyhow::Result<bool>;
+    async fn unmute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> anyhow::Result<bool>;
+    async fn list_muted_users(&self, user_id: Uuid) -> anyhow::Result<Vec<MutedUser>>;
+    async fn mute_domain(&self, user_id: Uuid, domain: &str) -> anyhow::Result<bool>;
+    async fn unmute_domain(&self, user_id: Uuid, domain: &str) -> anyhow::Result<bool>;
+    async fn list_muted_domains(&self, user_id: Uuid) -> anyhow::Result<Vec<MutedDomain>>;
+    /// What search leaves out for `user_id`; shadowbanned authors are left out for everyone but themselves.
+    async fn exclusions(&self, user_id: Option<Uuid>) -> anyhow::Result<FeedExclusions>;
+}
@@ -1,8 +1,10 @@
 
-use serde::{de::value, Serialize};
+use serde::{de::value, Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::domain::communities::Visibility;
+
 #[derive(Debug, Serialize, sqlx::FromRow, Clone)]
 pub struct Post {
     pub id: Uuid,
@@ -13,17 +15,188 @@ pub struct Post {
     pub short_description: Option<String>,
     pub score: i32,
     pub created_at: DateTime<Utc>,
+    /// Sort key for hot ranking: `created_at` pushed later by 12.5h per 10x of score.
+    pub hot_at: DateTime<Utc>,
+    /// Link preview, fetched from the page in the background after the post is created.
+    pub preview_title: Option<String>,
+    pub preview_site_name: Option<String>,
+    pub preview_description: Option<String>,
+    pub preview_image_url: Option<String>,
+    /// Time of the last fetch attempt for the current link, successful or not.
+    #[serde(skip)]
+    pub preview_fetched_at: Option<DateTime<Utc>>,
+    /// Set while a moderator has the post locked; nobody can comment on it or vote on it.
+    pub locked_at: Option<DateTime<Utc>>,
+    /// Set while the post waits for a moderator's approval; held posts stay out of every feed.
+    pub held_at: Option<DateTime<Utc>>,
+    /// Set while the post is pinned above the site-wide feeds.
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// Order among pinned posts, lowest first.
+    pub pin_position: Option<i32>,
+    /// Set once the post is old enough to be archived; archived posts are read-only, like locked ones.
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Time of the last edit made after the grace window; quick fixes right after posting don't count.
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Starts at 1 and goes up with every edit, and whenever the post is locked, pinned, held, archived,
+    /// removed or their reverse; votes leave it alone. Served as the post's ETag.
+    pub version: i32,
     pub avatar: Option<String>,
     pub author_username: String,
+    /// Slug of the community the post was made in.
+    pub community: String,
+    #[serde(skip)]
+    #[sqlx(try_from = "String")]
+    pub community_visibility: Visibility,
+    /// Canonical tag slugs, alphabetical.
+    pub tags: Vec<String>,
+    /// Only the author sees posts made under a shadowban.
+    #[serde(skip)]
+    pub author_shadowbanned: bool,
+}
+
+impl Post {
+    /// Whether the post may show up outside its community: in the live feed and in search.
+    pub fn is_listed(&self) -> bool {
+        self.community_visibility != Visibility::Private && self.held_at.is_none()
+    }
+}
+
+/// A post as shown to one viewer; the viewer-specific fields are left out for anonymous requests.
+#[derive(Debug, Serialize)]
+pub struct PostView {
+    #[serde(flatten)]
+    pub post: Post,
+    #[serde(flatten)]
+    pub viewer: Option<ViewerAnnotations>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewerAnnotations {
+    /// -1, 0 or 1.
+    pub my_vote: i16,
+    pub saved: bool,
+    pub hidden: bool,
+    pub can_edit: bool,
+}
+
+/// The viewer's own relation to one post, as loaded by `PostRepository::viewer_states`.
+#[derive(Debug, Clone)]
+pub struct ViewerState {
+    pub post_id: Uuid,
+    pub my_vote: i16,
+    pub saved: bool,
+    pub hidden: bool,
+}
+
+/// Emitted by `PostService` after a write commits; secondary indexes follow these.
+#[derive(Debug, Clone)]
+pub enum PostEvent {
+    Created(Post),
+    Updated(Post),
+    Deleted(Uuid),
+}
+
+/// Ordering for post and comment feeds. `New` paginates on `(created_at, id)`; `Top` on
+/// `(score, created_at, id)`, where a comment's score is its number of direct replies.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSort {
+    #[default]
+    New,
+    Top,
+}
+
+/// Sort key of the last item on the previous page of a sortable feed; `score` is only set for `Top`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub score: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl FeedCursor {
+    /// The cursor following an item in a feed sorted by `sort`.
+    pub fn at(sort: FeedSort, score: i64, created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { score: (sort == FeedSort::Top).then_some(score), created_at, id }
+    }
+}
+
+/// A post to insert through `PostRepository::create`. `tags` are normalized slugs and
+/// `canonical_url` is `url` in canonical form.
+#[derive(Debug, Clone)]
+pub struct NewPost {
+    pub user_id: Uuid,
+    pub community_id: Uuid,
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub canonical_url: Option<String>,
+    pub body: Option<String>,
+    pub tags: Vec<String>,
+    /// Holds the post for review from the start.
+    pub hold_reason: Option<String>,
+}
+
+/// The editable fields of a post, written by `PostRepository::update` and `ModerationTx::edit_post`.
+#[derive(Debug, Clone)]
+pub struct PostEdit {
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub canonical_url: Option<String>,
+    pub body: Option<String>,
+    pub tags: Vec<String>,
+    /// Holds the post for review along with the edit, unless it is held already.
+    pub hold_reason: Option<String>,
 }
 
 #[async_trait::async_trait]
 pub trait PostRepository: Send + Sync {
-    async fn create(&self, user_id: Uuid, title: &str, short_description: &str, url: &Option<String>, body: &Option<String>) -> anyhow::Result<Post>;
-    async fn list_new(&self, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
-    async fn list_top(&self, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
-    async fn update(&self, post_id: Uuid, title: &str, short_description: &str, url: &Option<String>, body: &Option<String>) -> anyhow::Result<Post>;
-    async fn delete(&self, post_id: Uuid) -> anyhow::Result<()>;
-    async fn search_by_title(&self, query: &str, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Unknown tags are created and aliases resolve to their canonical tag.
+    async fn create(&self, post: &NewPost) -> anyhow::Result<Post>;
+    /// Site-wide feeds (`list_new`, `list_top`, `list_by_author`, `list_by_tag`, `list_by_domain`) leave out posts in private communities.
+    /// `list_new` and `list_top` also leave out what `viewer` has hidden or muted, and pinned posts,
+    /// which `list_pinned` returns under the same rules.
+    /// Feeds leave out posts by shadowbanned authors, except where the author is the one reading them
+    /// (`viewer`, or the `user_id` of `list_home` and `list_upvoted_by`).
+    async fn list_new(&self, viewer: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    async fn list_top(&self, viewer: Option<Uuid>, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Pinned posts by `pin_position`, then most recently pinned first.
+    async fn list_pinned(&self, viewer: Option<Uuid>) -> anyhow::Result<Vec<Post>>;
+    /// The author also sees their held posts here.
+    async fn list_by_author(&self, user_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    async fn list_by_tag(&self, tag_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Posts linking to `domain` or any of its subdomains.
+    async fn list_by_domain(&self, domain: &str, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Includes posts of private communities; callers check membership first.
+    async fn list_by_community(&self, community_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Site-wide hot feed, paginated on `(hot_at, id)`.
+    async fn list_hot(&self, viewer: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Hot-ranked posts from followed authors, followed tags and joined communities, paginated on `(hot_at, id)`.
+    async fn list_home(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    async fn list_upvoted_by(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>>;
+    /// Replaces the post's tags with `tags`, resolved as in `create`. When the title, description,
+    /// link or body change, the version they replace is kept as a revision by `editor_id`, and
+    /// `mark_edited` sets `edited_at`. With `expected_version`, nothing is written and `None` is
+    /// returned unless the post is still at that version.
+    async fn update(&self, post_id: Uuid, editor_id: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>>;
+    /// Marks the post deleted by its author. Deleted and removed posts are kept, with their votes and
+    /// comments, but drop out of every read below.
+    /// Returns false if it was already deleted or doesn't exist. Moderators remove posts through a `ModerationTx`.
+    async fn delete(&self, post_id: Uuid) -> anyhow::Result<bool>;
+    /// Archives every unpinned post created before `cutoff`; returns how many were archived.
+    async fn archive_older_than(&self, cutoff: DateTime<Utc>) -> anyhow::Result<u64>;
+    async fn find_by_id(&self, post_id: Uuid) -> anyhow::Result<Option<Post>>;
+    /// A post held for review, which `find_by_id` leaves out.
+    async fn find_held(&self, post_id: Uuid) -> anyhow::Result<Option<Post>>;
+    /// As `find_by_id`, but a held post is found by its author and by moderators of its community or the site.
+    async fn find_for_viewer(&self, post_id: Uuid, viewer: Option<Uuid>) -> anyhow::Result<Option<Post>>;
+    /// Newest post in the community linking to `canonical_url` that was submitted after `since`.
+    async fn find_by_canonical_url(&self, community_id: Uuid, canonical_url: &str, since: DateTime<Utc>) -> anyhow::Result<Option<Post>>;
+    /// Loads the given posts in no particular order; unknown ids and posts in private communities are skipped.
+    async fn find_by_ids(&self, post_ids: &[Uuid]) -> anyhow::Result<Vec<Post>>;
+    /// One entry per id in `post_ids`, fetched in a single round trip.
+    async fn viewer_states(&self, user_id: Uuid, post_ids: &[Uuid]) -> anyhow::Result<Vec<ViewerState>>;
+    /// A `value` of 0 withdraws the user's vote.
     async fn upsert_vote_and_recompute(&self, user_id: Uuid, post_id: Uuid, value: i16) -> anyhow::Result<(i32, DateTime<Utc>)>;
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// What a link post shows about the page it points to.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct LinkPreview {
+    pub title: Option<String>,
+    pub site_name: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+}
+
+impl LinkPreview {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.site_name.is_none() && self.description.is_none() && self.image_url.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    /// Where the page was finally fetched from, after redirects; relative links resolve against it.
+    pub url: String,
+    pub content_type: Option<String>,
+    /// Possibly cut short by the fetcher's size cap; the `<head>` is what matters.
+    pub body: String,
+}
+
+/// Fetches pages that users link to. Implementations must refuse to reach private networks.
+#[async_trait::async_trait]
+pub trait PageFetcher: Send + Sync {
+    async fn fetch(&self, url: &str) -> anyhow::Result<FetchedPage>;
+}
+
+/// Where link previews are stored once fetched.
+#[async_trait::async_trait]
+pub trait PreviewRepository: Send + Sync {
+    /// Stores the preview fetched for `url`, unless the post has since been edited to link elsewhere.
+    /// `None` records a failed attempt. A blank `short_description` is filled from the preview.
+    async fn set_preview(&self, post_id: Uuid, url: &str, preview: Option<&LinkPreview>) -> anyhow::Result<()>;
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Post,
+    Comment,
+}
+
+impl ReportTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportTarget::Post => "post",
+            ReportTarget::Comment => "comment",
+        }
+    }
+}
+
+impl TryFrom<&str> for ReportTarget {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "post" => Ok(ReportTarget::Post),
+            "comment" => Ok(ReportTarget::Comment),
+            other => Err(anyhow::anyhow!("unknown report target {other:?}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Hate,
+    Violence,
+    Sexual,
+    Misinformation,
+    OffTopic,
+    /// Needs `details` explaining what is wrong.
+    Other,
+}
+
+impl ReportReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportReason::Spam => "spam",
+            ReportReason::Harassment => "harassment",
+            ReportReason::Hate => "hate",
+            ReportReason::Violence => "violence",
+            ReportReason::Sexual => "sexual",
+            ReportReason::Misinformation => "misinformation",
+            ReportReason::OffTopic => "off_topic",
+            ReportReason::Other => "other",
+        }
+    }
+}
+
+impl TryFrom<&str> for ReportReason {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "spam" => Ok(ReportReason::Spam),
+            "harassment" => Ok(ReportReason::Harassment),
+            "hate" => Ok(ReportReason::Hate),
+            "violence" => Ok(ReportReason::Violence),
+            "sexual" => Ok(ReportReason::Sexual),
+            "misinformation" => Ok(ReportReason::Misinformation),
+            "off_topic" => Ok(ReportReason::OffTopic),
+            "other" => Ok(ReportReason::Other),
+            other => Err(anyhow::anyhow!("unknown report reason {other:?}")),
+        }
+    }
+}
+
+/// What a moderator did about a reported item; recorded on every report it closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// Close the reports and leave the item alone.
+    Dismiss,
+    Remove,
+    /// Lock the post, or the post a reported comment belongs to, against new comments.
+    Lock,
+    /// Ban the item's author site-wide; site moderators only.
+    BanAuthor,
+}
+
+impl Resolution {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::Dismiss => "dismiss",
+            Resolution::Remove => "remove",
+            Resolution::Lock => "lock",
+            Resolution::BanAuthor => "ban_author",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReasonCount {
+    pub reason: ReportReason,
+    pub count: i64,
+}
+
+/// One entry of the moderation queue: all open reports against one item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedItem {
+    pub target_type: ReportTarget,
+    pub target_id: Uuid,
+    /// Slug of the community the item was posted in.
+    pub community: String,
+    /// The post itself, or the post a comment belongs to; `None` once the item is gone.
+    pub post_id: Option<Uuid>,
+    pub author_username: Option<String>,
+    /// The post title, or the start of the comment.
+    pub excerpt: Option<String>,
+    /// Already taken down by a moderator.
+    pub removed: bool,
+    /// Deleted by its author since it was reported.
+    pub deleted: bool,
+    pub report_count: i64,
+    /// Most common first.
+    pub reasons: Vec<ReasonCount>,
+    /// The latest non-empty `details` left by reporters.
+    pub details: Vec<String>,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+}
+
+/// A post or comment the content filter is holding until a moderator approves or removes it.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeldItem {
+    pub target_type: ReportTarget,
+    pub target_id: Uuid,
+    pub community: String,
+    /// The post itself, or the post a comment belongs to.
+    pub post_id: Uuid,
+    pub author_username: String,
+    /// The post title, or the start of the comment.
+    pub excerpt: String,
+    pub hold_reason: Option<String>,
+    pub held_at: DateTime<Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait ReportRepository: Send + Sync {
+    /// Files the report, or updates the reporter's open report on the same item.
+    async fn upsert(&self, reporter_id: Uuid, target_type: ReportTarget, target_id: Uuid, community_id: Uuid, reason: ReportReason, details: &str) -> anyhow::Result<()>;
+    /// Open reports grouped per item, most recently reported first; paginated on `(last_reported_at, target_id)`.
+    /// `community_id` narrows the queue to one community.
+    async fn queue(&self, community_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<ReportedItem>>;
+    /// Community the item's open reports were filed in, or `None` if it has none.
+    async fn open_report_community(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>>;
+    /// Community of the post or comment, including deleted and removed ones.
+    async fn target_community(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>>;
+    /// Author of the reported post or comment, including deleted and removed ones.
+    async fn target_author(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>>;
+    /// Held posts and comments that are neither deleted nor removed, oldest first so nothing waits
+    /// forever; paginated on `(held_at, target_id)`. `community_id` narrows it to one community.
+    async fn held_queue(&self, community_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<HeldItem>>;
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// A post as it was before an edit replaced it.
+#[derive(Debug, Clone)]
+pub struct PostRevision {
+    pub post_id: Uuid,
+    pub title: String,
+    pub short_description: String,
+    pub url: Option<String>,
+    pub body: Option<String>,
+    /// Who made the edit that replaced this version; `None` once their account is gone.
+    pub editor_username: Option<String>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// A comment as it was before an edit replaced it.
+#[derive(Debug, Clone)]
+pub struct CommentRevision {
+    pub comment_id: Uuid,
+    pub body: String,
+    pub editor_username: Option<String>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// Revisions are written by `PostRepository::update` and `CommentRepository::update`; this reads them back.
+#[async_trait::async_trait]
+pub trait RevisionRepository: Send + Sync {
+    /// Oldest first, so the first is the version originally posted.
+    async fn post_revisions(&self, post_id: Uuid) -> anyhow::Result<Vec<PostRevision>>;
+    async fn comment_revisions(&self, comment_id: Uuid) -> anyhow::Result<Vec<CommentRevision>>;
+}
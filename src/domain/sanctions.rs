@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SanctionKind {
+    /// The account can't log in or use its tokens at all.
+    Ban,
+    /// The account can still read, but every write is refused.
+    Suspension,
+    /// Nothing changes for the user, but their posts and comments are hidden from everyone else.
+    Shadowban,
+}
+
+impl SanctionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SanctionKind::Ban => "ban",
+            SanctionKind::Suspension => "suspension",
+            SanctionKind::Shadowban => "shadowban",
+        }
+    }
+}
+
+impl From<&str> for SanctionKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "ban" => SanctionKind::Ban,
+            "suspension" => SanctionKind::Suspension,
+            _ => SanctionKind::Shadowban,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Sanction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: SanctionKind,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// `None` lasts until revoked.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Sanction {
+    /// What the sanctioned user is told when a request is refused.
+    pub fn describe(&self) -> String {
+        let what = match self.kind {
+            SanctionKind::Ban => "this account is banned",
+            SanctionKind::Suspension => "this account is suspended",
+            SanctionKind::Shadowban => "this account is restricted",
+        };
+        let until = match self.expires_at {
+            Some(at) => format!(" until {}", at.to_rfc3339()),
+            None => String::new(),
+        };
+        match self.reason.as_deref() {
+            Some(reason) => format!("{what}{until}: {reason}"),
+            None => format!("{what}{until}"),
+        }
+    }
+}
+
+/// A live account that looks like it may belong to someone who is banned.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvasionSignal {
+    pub user_id: Uuid,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+    /// `shared_ip` or `email_domain`.
+    pub signal: String,
+    /// The IP address or email domain the two accounts have in common.
+    pub matched: String,
+    pub banned_username: String,
+}
+
+#[async_trait::async_trait]
+pub trait SanctionRepository: Send + Sync {
+    /// Sanctions in force right now: not revoked and not expired.
+    async fn active_for(&self, user_id: Uuid) -> anyhow::Result<Vec<Sanction>>;
+    /// Every sanction the user ever got, newest first.
+    async fn list_for(&self, user_id: Uuid) -> anyhow::Result<Vec<Sanction>>;
+    async fn record_ip(&self, user_id: Uuid, ip: &str) -> anyhow::Result<()>;
+    /// Accounts created after a ban that share an IP, or an email domain outside `common_domains`,
+    /// with the banned account; newest accounts first.
+    async fn evasion_signals(&self, common_domains: &[String], limit: i64) -> anyhow::Result<Vec<EvasionSignal>>;
+}
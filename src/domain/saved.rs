@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::posts::Post;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SavedCollection {
+    pub id: Uuid,
+    pub name: String,
+    pub post_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedEntry {
+    pub post_id: Uuid,
+    pub collection_id: Option<Uuid>,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// A saved post as listed under `/me/saved`; paginated on `(saved_at, id)`.
+#[derive(Debug, Serialize)]
+pub struct SavedPost {
+    #[serde(flatten)]
+    pub post: Post,
+    pub collection_id: Option<Uuid>,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait SavedRepository: Send + Sync {
+    /// Saves the post, or moves it to `collection_id` if it was already saved.
+    async fn save(&self, user_id: Uuid, post_id: Uuid, collection_id: Option<Uuid>) -> anyhow::Result<()>;
+    /// Returns false if the post was not saved.
+    async fn unsave(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool>;
+    /// Newest first; `collection_id` narrows the list to one collection.
+    async fn list(&self, user_id: Uuid, collection_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<SavedEntry>>;
+    async fn create_collection(&self, user_id: Uuid, name: &str) -> anyhow::Result<SavedCollection>;
+    async fn find_collection(&self, user_id: Uuid, collection_id: Uuid) -> anyhow::Result<Option<SavedCollection>>;
+    async fn find_collection_by_name(&self, user_id: Uuid, name: &str) -> anyhow::Result<Option<SavedCollection>>;
+    async fn list_collections(&self, user_id: Uuid) -> anyhow::Result<Vec<SavedCollection>>;
+    async fn rename_collection(&self, collection_id: Uuid, name: &str) -> anyhow::Result<()>;
+    /// Posts in the collection stay saved, just no longer filed.
+    async fn delete_collection(&self, collection_id: Uuid) -> anyhow::Result<()>;
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::mutes::FeedExclusions;
+use crate::domain::posts::Post;
+
+/// A search request as typed by the user plus optional filters.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Words, `"quoted phrases"`, `prefix*` and `-excluded` terms; see [`parse_terms`].
+    pub text: String,
+    /// Normalized username of the author.
+    pub author: Option<String>,
+    /// Matches the domain itself and any of its subdomains.
+    pub domain: Option<String>,
+    /// Canonical tag slug.
+    pub tag: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: i64,
+    pub offset: i64,
+    /// The viewer's hidden posts and muted authors and domains.
+    pub exclude: FeedExclusions,
+    /// The signed-in user searching, who still finds their own comments while shadowbanned.
+    pub viewer: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub post: Post,
+    pub rank: f32,
+    /// HTML-escaped excerpt with matches wrapped in `<mark>`.
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchFacets {
+    /// Most common link domains among all matches, not just the current page.
+    pub domains: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub facets: SearchFacets,
+}
+
+/// Where posts are searched. Implementations that keep their own copy of the data are fed
+/// through `index_posts`/`remove_post`; ones that read the primary database can ignore them.
+#[async_trait::async_trait]
+pub trait SearchIndex: Send + Sync {
+    async fn search(&self, query: &SearchQuery) -> anyhow::Result<SearchResults>;
+    async fn index_posts(&self, posts: &[Post]) -> anyhow::Result<()>;
+    async fn remove_post(&self, post_id: Uuid) -> anyhow::Result<()>;
+    /// Removes every document; used before a full reindex.
+    async fn clear(&self) -> anyhow::Result<()>;
+}
+
+/// One piece of a parsed search query. Words are lowercased and stripped to letters and digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryTerm {
+    Word(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+    Exclude(String),
+}
+
+/// Splits what a user types into the search box into terms every backend understands.
+pub fn parse_terms(raw: &str) -> Vec<QueryTerm> {
+    let mut terms = Vec::new();
+    let mut rest = raw.trim();
+
+    while !rest.is_empty() {
+        if let Some(after_quote) = rest.strip_prefix('"') {
+            let (phrase, tail) = after_quote.split_once('"').unwrap_or((after_quote, ""));
+            let words: Vec<String> = phrase.split_whitespace().filter_map(clean_word).collect();
+            if !words.is_empty() {
+                terms.push(QueryTerm::Phrase(words));
+            }
+            rest = tail.trim_start();
+            continue;
+        }
+
+        let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        rest = tail.trim_start();
+
+        let term = if let Some(w) = word.strip_prefix('-') {
+            clean_word(w).map(QueryTerm::Exclude)
+        } else if let Some(w) = word.strip_suffix('*') {
+            clean_word(w).map(QueryTerm::Prefix)
+        } else {
+            clean_word(word).map(QueryTerm::Word)
+        };
+        terms.extend(term);
+    }
+
+    // a query made only of exclusions matches nothing useful
+    if terms.iter().all(|t| matches!(t, QueryTerm::Exclude(_))) {
+        return vec![];
+    }
+    terms
+}
+
+fn clean_word(word: &str) -> Option<String> {
+    let cleaned: String = word.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    (!cleaned.is_empty()).then_some(cleaned)
+}
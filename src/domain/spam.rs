@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// What the spam check needs to know about an author, read in one round trip.
+#[derive(Debug, Clone)]
+pub struct AuthorActivity {
+    pub account_created_at: DateTime<Utc>,
+    /// Sum of the scores of the author's live posts.
+    pub karma: i64,
+    /// Posts and comments written since the velocity cutoff.
+    pub recent_count: i64,
+    /// The author's latest posts (title, link and body) and comments since the duplicate cutoff, newest first.
+    pub recent_texts: Vec<String>,
+}
+
+/// How moderators have treated content linking to a domain.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainReputation {
+    pub domain: String,
+    /// Held content a moderator let through.
+    pub approved: i32,
+    /// Content a moderator took down.
+    pub removed: i32,
+}
+
+#[async_trait::async_trait]
+pub trait SpamRepository: Send + Sync {
+    /// `None` if the user doesn't exist. Activity counts from `velocity_since`; texts from `texts_since`.
+    async fn author_activity(&self, user_id: Uuid, velocity_since: DateTime<Utc>, texts_since: DateTime<Utc>) -> anyhow::Result<Option<AuthorActivity>>;
+    /// Domains nobody has made a decision about yet are left out.
+    async fn reputations(&self, domains: &[String]) -> anyhow::Result<Vec<DomainReputation>>;
+}
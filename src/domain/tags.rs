@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Tag {
+    pub id: Uuid,
+    pub slug: String,
+    pub post_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait TagRepository: Send + Sync {
+    /// Finds a tag by its slug or by one of its aliases.
+    async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Tag>>;
+    /// Tags whose slug or alias starts with `prefix`, most used first.
+    async fn autocomplete(&self, prefix: &str, limit: i64) -> anyhow::Result<Vec<Tag>>;
+    async fn add_alias(&self, tag_id: Uuid, alias_slug: &str, created_by: Uuid) -> anyhow::Result<()>;
+    /// Returns false if no such alias existed.
+    async fn remove_alias(&self, alias_slug: &str) -> anyhow::Result<bool>;
+    /// Moves every post and follower from `source` onto `target`, deletes `source` and keeps its slug as an alias
+    /// of `target`. Returns the ids of posts that were tagged with `source`.
+    async fn merge(&self, source_id: Uuid, target_id: Uuid, merged_by: Uuid) -> anyhow::Result<Vec<Uuid>>;
+}
@@ -11,6 +11,34 @@ pub struct User {
     pub username: String,
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
+    /// Set while an account deletion is pending; the account is anonymized once the grace period ends.
+    pub deletion_requested_at: Option<DateTime<Utc>>,
+    pub role: Role,
+}
+
+/// Site-wide role. Admins can do everything moderators can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub fn is_moderator(self) -> bool {
+        matches!(self, Role::Moderator | Role::Admin)
+    }
+}
+
+impl From<&str> for Role {
+    fn from(value: &str) -> Self {
+        match value {
+            "moderator" => Role::Moderator,
+            "admin" => Role::Admin,
+            _ => Role::User,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -34,9 +62,70 @@ impl From<User> for UserPublic {
     }
 }
 
+/// A username in the forms we store: as displayed, folded for lookups, and its confusable skeleton for uniqueness.
+#[derive(Debug, Clone)]
+pub struct Username {
+    pub display: String,
+    pub normalized: String,
+    pub skeleton: String,
+}
+
+/// A stored name whose confusable skeleton a migration left for the app to compute.
+#[derive(Debug, Clone)]
+pub struct PendingSkeleton {
+    /// The user's id, or the `username_history` row's.
+    pub id: Uuid,
+    pub username: String,
+}
+
+/// What anyone can see about a user; unlike `UserPublic` it leaves out the email.
+#[derive(Debug, Serialize)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub username: String,
+    pub avatar: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<User> for UserProfile {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            avatar: user.avatar,
+            created_at: user.created_at,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait UserRepository: Send + Sync {
-    async fn create(&self, email: &str, username: &str, avatar: &str, password_hash: &str) -> anyhow::Result<User>;
+    async fn create(&self, email: &str, username: &Username, avatar: &str, password_hash: &str) -> anyhow::Result<User>;
+    /// `key` must already be folded; it is matched against the email and the normalized username.
     async fn find_by_email_or_username(&self, key: &str) -> anyhow::Result<Option<User>>;
+    async fn find_by_username(&self, username: &Username) -> anyhow::Result<Option<User>>;
+    /// Finds a live account whose name is confusable with `username`.
+    async fn find_by_username_skeleton(&self, username: &Username) -> anyhow::Result<Option<User>>;
+    async fn find_by_id(&self, user_id: Uuid) -> anyhow::Result<Option<User>>;
+    async fn update_avatar(&self, user_id: Uuid, avatar: &str) -> anyhow::Result<()>;
+    async fn set_deletion_requested(&self, user_id: Uuid, at: Option<DateTime<Utc>>) -> anyhow::Result<()>;
+    /// Tombstones every account whose deletion was requested before `cutoff`, forgetting its former
+    /// usernames. Returns the avatar URL each of them had, empty for none, for their images to be deleted.
+    async fn anonymize_requested_before(&self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<String>>;
+    /// Renames the user and records the old name in `username_history`, held until `reserved_until`.
+    async fn change_username(&self, user_id: Uuid, new_username: &Username, reserved_until: DateTime<Utc>) -> anyhow::Result<User>;
+    async fn last_username_change(&self, user_id: Uuid) -> anyhow::Result<Option<DateTime<Utc>>>;
+    /// Returns the id of the user still holding a reservation on a name confusable with `username`, if any.
+    async fn username_reserved_by(&self, username: &Username) -> anyhow::Result<Option<Uuid>>;
+    /// Follows `username_history` from a former name to the account that used it most recently.
+    async fn find_by_previous_username(&self, username: &Username) -> anyhow::Result<Option<User>>;
     async fn verify_token(&self, token: &str) -> anyhow::Result<Uuid>;
+    /// Accounts whose skeleton is still pending, oldest first.
+    async fn pending_skeletons(&self, limit: i64) -> anyhow::Result<Vec<PendingSkeleton>>;
+    /// Stores the account's skeleton; if another live account already has it, stores a placeholder no skeleton
+    /// can collide with instead, so the older account keeps the name.
+    async fn set_skeleton(&self, user_id: Uuid, skeleton: &str) -> anyhow::Result<()>;
+    /// `username_history` rows whose `old_username` skeleton is still pending.
+    async fn pending_history_skeletons(&self, limit: i64) -> anyhow::Result<Vec<PendingSkeleton>>;
+    async fn set_history_skeleton(&self, history_id: Uuid, skeleton: &str) -> anyhow::Result<()>;
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
+use serde::Serialize;
 use uuid::Uuid;
 
-
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Vote {
     pub user_id: Uuid,
     pub post_id: Uuid,
@@ -12,4 +12,5 @@ pub struct Vote {
 pub trait VoteRepository: Send + Sync {
     async fn vote(&self, vote: Vote) -> Result<(), anyhow::Error>;
     async fn get_score(&self, post_id: Uuid) -> Result<i16, anyhow::Error>;
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<Vote>, anyhow::Error>;
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use aws_sdk_s3::{config::Region, primitives::ByteStream, Client};
+
+use crate::config::BlobStoreConfig;
+use crate::domain::blobs::{Blob, BlobStore};
+
+pub async fn from_config(cfg: &BlobStoreConfig) -> anyhow::Result<Arc<dyn BlobStore>> {
+    Ok(match cfg {
+        BlobStoreConfig::Local { root } => Arc::new(LocalBlobStore::new(root).await?),
+        BlobStoreConfig::S3 { bucket, region, endpoint } => Arc::new(S3BlobStore::new(bucket, region, endpoint.as_deref()).await),
+    })
+}
+
+/// Keeps blobs as plain files under `root`; the content type is derived from the key's extension.
+pub struct LocalBlobStore { root: PathBuf }
+
+impl LocalBlobStore {
+    pub async fn new(root: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let root = root.into();
+        tokio::fs::create_dir_all(&root).await?;
+        Ok(Self { root })
+    }
+
+    fn path_for(&self, key: &str) -> anyhow::Result<PathBuf> {
+        // keys come from our own code, but never let one escape the root
+        let rel = Path::new(key);
+        if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            anyhow::bail!("invalid blob key {key:?}");
+        }
+        Ok(self.root.join(rel))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, blob: Blob) -> anyhow::Result<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // write then rename so readers never see a half-written file
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, &blob.bytes).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Blob>> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(Blob { bytes, content_type: content_type_for(key).to_string() })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn content_type_for(key: &str) -> &'static str {
+    match Path::new(key).extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// S3 or any S3-compatible service. With an explicit endpoint (e.g. a local MinIO at
+/// `http://localhost:9000`) path-style addressing is used. Credentials come from the usual AWS_* env vars.
+pub struct S3BlobStore {
+    client: Client,
+    bucket: String,
+}
+
+impl S3BlobStore {
+    pub async fn new(bucket: &str, region: &str, endpoint: Option<&str>) -> Self {
+        let shared = aws_config::from_env()
+            .region(Region::new(region.to_string()))
+            .load()
+            .await;
+
+        let mut builder = aws_sdk_s3::config::Builder::from(&shared);
+        if let Some(endpoint) = endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+
+        Self { client: Client::from_conf(builder.build()), bucket: bucket.to_string() }
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, blob: Blob) -> anyhow::Result<()> {
+        self.client.put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(blob.content_type)
+            .body(ByteStream::from(blob.bytes))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Blob>> {
+        let out = match self.client.get_object().bucket(&self.bucket).key(key).send().await {
+            Ok(out) => out,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let content_type = out.content_type().unwrap_or_else(|| content_type_for(key)).to_string();
+        let bytes = out.body.collect().await?.into_bytes().to_vec();
+        Ok(Some(Blob { bytes, content_type }))
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.client.delete_object().bucket(&self.bucket).key(key).send().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+use crate::domain::content_filter::{ContentFilter, FilterAction, FilterMatch, Severity, Verdict};
+
+/// The dictionary file. Terms are single words; a trailing `*` also matches longer words starting
+/// with the term ("fuck*" catches "fucking"). Community entries are keyed by slug.
+///
+/// ```json
+/// {
+///   "actions": { "low": "mask", "medium": "hold", "high": "reject" },
+///   "terms": { "low": ["damn"], "high": ["fuck*"] },
+///   "communities": {
+///     "kids": { "actions": { "low": "reject" } },
+///     "rants": { "allow": ["damn"], "terms": { "medium": ["meh"] } }
+///   }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+struct DictionaryFile {
+    #[serde(default)]
+    actions: HashMap<Severity, FilterAction>,
+    #[serde(default)]
+    terms: HashMap<Severity, Vec<String>>,
+    #[serde(default)]
+    communities: HashMap<String, CommunityFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunityFile {
+    #[serde(default)]
+    actions: HashMap<Severity, FilterAction>,
+    #[serde(default)]
+    terms: HashMap<Severity, Vec<String>>,
+    /// Site-wide terms that don't apply in this community.
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+/// A run of one letter: "hello" is h×1 e×1 l×2 o×1.
+type Runs = Vec<(char, usize)>;
+
+#[derive(Debug)]
+struct Term {
+    /// As written in the dictionary, for reporting.
+    text: String,
+    runs: Runs,
+    prefix: bool,
+    severity: Severity,
+}
+
+#[derive(Debug)]
+struct Overrides {
+    actions: HashMap<Severity, FilterAction>,
+    terms: Vec<Term>,
+    allow: HashSet<String>,
+}
+
+/// Matches whole words after folding case, accents, look-alike letters and leetspeak, so
+/// "hello" never trips "hell" but "H3LL", "hėll" and "heeell" do.
+#[derive(Debug)]
+pub struct DictionaryFilter {
+    actions: HashMap<Severity, FilterAction>,
+    terms: Vec<Term>,
+    communities: HashMap<String, Overrides>,
+}
+
+impl DictionaryFilter {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let file: DictionaryFile = serde_json::from_str(json)?;
+
+        let mut actions = HashMap::from([
+            (Severity::Low, FilterAction::Mask),
+            (Severity::Medium, FilterAction::Hold),
+            (Severity::High, FilterAction::Reject),
+        ]);
+        actions.extend(file.actions);
+
+        let mut communities = HashMap::new();
+        for (slug, community) in file.communities {
+            let overrides = Overrides {
+                actions: community.actions,
+                terms: compile_terms(community.terms)?,
+                allow: community.allow.iter().map(|t| fold_word(t.trim_end_matches('*'))).collect(),
+            };
+            communities.insert(slug.to_lowercase(), overrides);
+        }
+
+        Ok(Self { actions, terms: compile_terms(file.terms)?, communities })
+    }
+
+    fn action(&self, overrides: Option<&Overrides>, severity: Severity) -> FilterAction {
+        overrides.and_then(|o| o.actions.get(&severity))
+            .or_else(|| self.actions.get(&severity))
+            .copied()
+            .unwrap_or(FilterAction::Reject)
+    }
+}
+
+impl ContentFilter for DictionaryFilter {
+    fn check(&self, community: Option<&str>, text: &str) -> Verdict {
+        let overrides = community.and_then(|slug| self.communities.get(&slug.to_lowercase()));
+        let terms: Vec<&Term> = self.terms.iter()
+            .filter(|t| !overrides.is_some_and(|o| o.allow.contains(&runs_to_string(&t.runs))))
+            .chain(overrides.into_iter().flat_map(|o| o.terms.iter()))
+            .collect();
+
+        let mut chars: Vec<char> = text.chars().collect();
+        let mut matches = Vec::new();
+        for word in words(&chars) {
+            let hit = word.candidates.iter()
+                .flat_map(|(runs, span)| terms.iter().filter(|t| t.matches(runs)).map(move |t| (*t, span.clone())))
+                .max_by_key(|(t, _)| self.action(overrides, t.severity));
+            if let Some((term, span)) = hit {
+                let action = self.action(overrides, term.severity);
+                if action == FilterAction::Mask {
+                    chars[span].fill('*');
+                }
+                matches.push(FilterMatch { term: term.text.clone(), action });
+            }
+        }
+
+        Verdict {
+            action: matches.iter().map(|m| m.action).max(),
+            matches,
+            text: chars.into_iter().collect(),
+        }
+    }
+}
+
+impl Term {
+    /// A letter stretched to three or more ("fuuuck") matches the same letter written fewer times.
+    fn matches(&self, word: &Runs) -> bool {
+        let run_ok = |(wc, wn): (char, usize), (tc, tn): (char, usize)| wc == tc && (wn == tn || (wn > tn && wn >= 3));
+        if self.prefix {
+            let Some((&last, init)) = self.runs.split_last() else { return false };
+            word.len() >= self.runs.len()
+                && init.iter().zip(word).all(|(t, w)| run_ok(*w, *t))
+                && word[init.len()].0 == last.0 && word[init.len()].1 >= last.1
+        } else {
+            word.len() == self.runs.len() && self.runs.iter().zip(word).all(|(t, w)| run_ok(*w, *t))
+        }
+    }
+}
+
+fn compile_terms(terms: HashMap<Severity, Vec<String>>) -> anyhow::Result<Vec<Term>> {
+    let mut compiled = Vec::new();
+    for (severity, list) in terms {
+        for text in list {
+            let (word, prefix) = match text.strip_suffix('*') {
+                Some(word) => (word, true),
+                None => (text.as_str(), false),
+            };
+            let folded = fold_word(word);
+            if folded.is_empty() || !folded.chars().all(char::is_alphanumeric) {
+                anyhow::bail!("content filter term {text:?} must be a single word");
+            }
+            compiled.push(Term { runs: runs(&folded), text, prefix, severity });
+        }
+    }
+    Ok(compiled)
+}
+
+/// A word of the checked text: its foldings, each with the span of original chars it covers.
+/// Besides the whole word there is one without symbols at its edges, so "hell!" is read as
+/// "hell" while "$hit" still reads as "shit". Three or more single letters one separator apart
+/// ("f u c k", "F.U.C.K") are read as one more word. Numbers aren't words, so "455" stays "455".
+struct Word {
+    candidates: Vec<(Runs, std::ops::Range<usize>)>,
+}
+
+fn words(chars: &[char]) -> Vec<Word> {
+    let mut words = Vec::new();
+    // positions of the single letters read so far, one separator apart
+    let mut spelled: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if fold_char(chars[i]).is_none() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && fold_char(chars[i]).is_some() {
+            i += 1;
+        }
+
+        if i - start == 1 && spelled.last().is_some_and(|&prev| start - prev == 2) {
+            spelled.push(start);
+        } else {
+            flush_spelled(chars, &mut spelled, &mut words);
+            if i - start == 1 {
+                spelled.push(start);
+            }
+        }
+
+        if !has_letter(&chars[start..i]) {
+            continue;
+        }
+        let mut candidates = vec![(runs(&fold_span(&chars[start..i])), start..i)];
+        let inner_start = (start..i).find(|&j| chars[j].is_alphanumeric());
+        let inner_end = (start..i).rev().find(|&j| chars[j].is_alphanumeric()).map(|j| j + 1);
+        if let (Some(s), Some(e)) = (inner_start, inner_end) {
+            if (s, e) != (start, i) {
+                candidates.push((runs(&fold_span(&chars[s..e])), s..e));
+            }
+        }
+        words.push(Word { candidates });
+    }
+    flush_spelled(chars, &mut spelled, &mut words);
+    words
+}
+
+fn flush_spelled(chars: &[char], spelled: &mut Vec<usize>, words: &mut Vec<Word>) {
+    if spelled.len() >= 3 && spelled.iter().any(|&j| chars[j].is_alphabetic()) {
+        let folded: String = spelled.iter().filter_map(|&j| fold_char(chars[j])).collect();
+        let span = spelled[0]..spelled[spelled.len() - 1] + 1;
+        words.push(Word { candidates: vec![(runs(&folded), span)] });
+    }
+    spelled.clear();
+}
+
+fn has_letter(chars: &[char]) -> bool {
+    chars.iter().any(|c| c.is_alphabetic())
+}
+
+fn fold_span(chars: &[char]) -> String {
+    chars.iter().filter_map(|c| fold_char(*c)).collect()
+}
+
+fn fold_word(word: &str) -> String {
+    word.chars().filter_map(fold_char).collect()
+}
+
+/// Folds one char to the letters it stands for, or `None` if it separates words.
+fn fold_char(c: char) -> Option<String> {
+    if let Some(letter) = leet(c) {
+        return Some(letter.to_string());
+    }
+    if !c.is_alphanumeric() {
+        return None;
+    }
+    let folded: String = if c.is_ascii() {
+        c.to_ascii_lowercase().to_string()
+    } else {
+        let bare: String = c.to_string().nfkd().filter(|c| !is_combining_mark(*c)).collect();
+        // Cyrillic "а" and Greek "ο" look just like Latin "a" and "o"
+        skeleton(&bare).flat_map(char::to_lowercase).collect()
+    };
+    Some(folded.chars().map(|c| leet(c).unwrap_or(c)).collect())
+}
+
+fn leet(c: char) -> Option<char> {
+    Some(match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        '8' => 'b',
+        _ => return None,
+    })
+}
+
+fn runs(word: &str) -> Runs {
+    let mut runs: Runs = Vec::new();
+    for c in word.chars() {
+        match runs.last_mut() {
+            Some((last, n)) if *last == c => *n += 1,
+            _ => runs.push((c, 1)),
+        }
+    }
+    runs
+}
+
+fn runs_to_string(runs: &Runs) -> String {
+    runs.iter().map(|(c, n)| c.to_string().repeat(*n)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DictionaryFilter;
+    use crate::domain::content_filter::{ContentFilter, FilterAction};
+
+    fn filter() -> DictionaryFilter {
+        DictionaryFilter::from_json(r#"{
+            "terms": { "low": ["hell", "ass"], "medium": ["shit"], "high": ["cunt", "fuck*"] },
+            "communities": {
+                "kids": { "actions": { "low": "reject" } },
+                "rants": { "allow": ["hell"], "terms": { "medium": ["meh"] } }
+            }
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn words_containing_a_term_pass() {
+        for text in ["hello", "shell", "Scunthorpe", "shiitake", "class", "455 points"] {
+            let verdict = filter().check(None, text);
+            assert_eq!(verdict.action, None, "{text}");
+            assert_eq!(verdict.text, text);
+        }
+    }
+
+    #[test]
+    fn disguised_terms_match() {
+        for text in ["hell", "H3LL", "hėll", "heeell", "h e l l", "$hit", "fuuuck"] {
+            let verdict = filter().check(None, text);
+            assert!(verdict.action.is_some(), "{text}");
+        }
+    }
+
+    #[test]
+    fn masks_only_the_word() {
+        let verdict = filter().check(None, "what the hell! said she");
+        assert_eq!(verdict.action, Some(FilterAction::Mask));
+        assert_eq!(verdict.text, "what the ****! said she");
+        assert_eq!(verdict.matches[0].term, "hell");
+    }
+
+    #[test]
+    fn prefix_terms_match_longer_words() {
+        for text in ["fuck", "fucking", "FUCKER"] {
+            assert_eq!(filter().check(None, text).action, Some(FilterAction::Reject), "{text}");
+        }
+        assert_eq!(filter().check(None, "fuc").action, None);
+    }
+
+    #[test]
+    fn community_overrides_apply() {
+        assert_eq!(filter().check(Some("rants"), "hell").action, None);
+        assert_eq!(filter().check(Some("rants"), "meh").action, Some(FilterAction::Hold));
+        assert_eq!(filter().check(None, "meh").action, None);
+        assert_eq!(filter().check(Some("Kids"), "hell").action, Some(FilterAction::Reject));
+        assert_eq!(filter().check(Some("kids"), "shit").action, Some(FilterAction::Hold));
+    }
+
+    #[test]
+    fn harshest_action_wins() {
+        let verdict = filter().check(None, "hell shit");
+        assert_eq!(verdict.action, Some(FilterAction::Hold));
+        assert_eq!(verdict.reason(), "shit");
+        assert_eq!(verdict.text, "**** shit");
+    }
+}
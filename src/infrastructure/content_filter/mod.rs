@@ -0,0 +1,17 @@
+pub mod dictionary;
+
+use std::sync::Arc;
+
+use crate::domain::content_filter::ContentFilter;
+
+/// Used when `CONTENT_FILTER_DICTIONARY` isn't set.
+const DEFAULT_DICTIONARY: &str = include_str!("default_dictionary.json");
+
+pub fn from_config(dictionary_path: Option<&str>) -> anyhow::Result<Arc<dyn ContentFilter>> {
+    let json = match dictionary_path {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("can't read content filter dictionary {path}: {e}"))?,
+        None => DEFAULT_DICTIONARY.to_string(),
+    };
+    Ok(Arc::new(dictionary::DictionaryFilter::from_json(&json)?))
+}
@@ -1,4 +1,8 @@
 pub mod db;
 pub mod repositories;
 pub mod auth;
-pub mod observability;
\ No newline at end of file
+pub mod observability;
+pub mod blob_store;
+pub mod search;
+pub mod page_fetcher;
+pub mod content_filter;
\ No newline at end of file
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::domain::previews::{FetchedPage, PageFetcher};
+
+/// Serves canned pages instead of going to the network, and remembers what was asked for.
+/// Unknown URLs fail the way an unreachable site would.
+#[derive(Default)]
+pub struct FakePageFetcher {
+    pages: Mutex<HashMap<String, FetchedPage>>,
+    requested: Mutex<Vec<String>>,
+}
+
+impl FakePageFetcher {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn with_html(self, url: &str, html: &str) -> Self {
+        self.insert(FetchedPage {
+            url: url.to_string(),
+            content_type: Some("text/html; charset=utf-8".to_string()),
+            body: html.to_string(),
+        });
+        self
+    }
+
+    /// Registers `page` under its own `url`; a redirect is a page whose `url` differs from the one asked for.
+    pub fn insert(&self, page: FetchedPage) {
+        self.pages.lock().unwrap().insert(page.url.clone(), page);
+    }
+
+    pub fn requested(&self) -> Vec<String> {
+        self.requested.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl PageFetcher for FakePageFetcher {
+    async fn fetch(&self, url: &str) -> anyhow::Result<FetchedPage> {
+        self.requested.lock().unwrap().push(url.to_string());
+        self.pages.lock().unwrap().get(url).cloned()
+            .ok_or_else(|| anyhow::anyhow!("no page registered for {url}"))
+    }
+}
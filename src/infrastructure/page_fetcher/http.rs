@@ -0,0 +1,198 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, LOCATION};
+use reqwest::{redirect, Client, Response};
+use url::{Host, Url};
+
+use crate::domain::previews::{FetchedPage, PageFetcher};
+
+const MAX_REDIRECTS: usize = 5;
+const USER_AGENT: &str = concat!("LotusNewsBot/", env!("CARGO_PKG_VERSION"), " (link previews)");
+
+/// Fetches over HTTP(S) without ever connecting to loopback, private or otherwise
+/// non-public addresses. Hostnames are checked when they resolve, so DNS answers pointing
+/// inside the network are refused too; redirects are followed by hand and checked hop by hop.
+pub struct HttpPageFetcher {
+    client: Client,
+    timeout: Duration,
+    max_bytes: usize,
+}
+
+impl HttpPageFetcher {
+    pub fn new(timeout: Duration, max_bytes: usize) -> anyhow::Result<Self> {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(timeout)
+            .connect_timeout(timeout.min(Duration::from_secs(3)))
+            .redirect(redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicOnlyResolver))
+            // a proxy would resolve names itself, around the check above
+            .no_proxy()
+            .build()?;
+        Ok(Self { client, timeout, max_bytes })
+    }
+
+    async fn fetch_following_redirects(&self, url: &str) -> anyhow::Result<FetchedPage> {
+        let mut url = Url::parse(url)?;
+        for _ in 0..=MAX_REDIRECTS {
+            check_target(&url)?;
+            let response = self.client.get(url.clone())
+                .header(ACCEPT, "text/html,application/xhtml+xml;q=0.9,*/*;q=0.1")
+                .send().await?;
+
+            if response.status().is_redirection() {
+                let location = response.headers().get(LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                    .ok_or_else(|| anyhow!("redirect from {url} without a location"))?;
+                url = url.join(location)?;
+                continue;
+            }
+            if !response.status().is_success() {
+                bail!("{url} responded with {}", response.status());
+            }
+
+            let content_type = response.headers().get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = if content_type.as_deref().is_some_and(is_html) {
+                read_capped(response, self.max_bytes).await?
+            } else {
+                String::new()
+            };
+            return Ok(FetchedPage { url: url.into(), content_type, body });
+        }
+        bail!("too many redirects")
+    }
+}
+
+#[async_trait]
+impl PageFetcher for HttpPageFetcher {
+    async fn fetch(&self, url: &str) -> anyhow::Result<FetchedPage> {
+        tokio::time::timeout(self.timeout, self.fetch_following_redirects(url)).await
+            .map_err(|_| anyhow!("fetching {url} timed out"))?
+    }
+}
+
+fn is_html(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    mime == "text/html" || mime == "application/xhtml+xml"
+}
+
+/// Reads at most `max_bytes`; anything past that is dropped rather than treated as an error.
+async fn read_capped(mut response: Response, max_bytes: usize) -> anyhow::Result<String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        let room = max_bytes - body.len();
+        body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        if body.len() >= max_bytes {
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Literal IPs never reach the resolver, so they are checked here.
+fn check_target(url: &Url) -> anyhow::Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("unsupported scheme {}", url.scheme());
+    }
+    match url.host() {
+        Some(Host::Ipv4(ip)) if !is_public(IpAddr::V4(ip)) => bail!("{ip} is not a public address"),
+        Some(Host::Ipv6(ip)) if !is_public(IpAddr::V6(ip)) => bail!("{ip} is not a public address"),
+        Some(Host::Domain(domain)) if domain == "localhost" || domain.ends_with(".localhost") => bail!("{domain} is not a public host"),
+        None => bail!("{url} has no host"),
+        _ => Ok(()),
+    }
+}
+
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0)).await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped().or_else(|| nat64_embedded(ip)).or_else(|| six_to_four_embedded(ip)) {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+        || (a == 192 && b == 0 && c == 0) // IETF protocol assignments
+        || (a == 198 && (b == 18 || b == 19)) // benchmarking
+        || a >= 240) // reserved
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00 // unique local
+        || (segments[0] & 0xffc0) == 0xfe80 // link local
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)) // documentation
+}
+
+/// `64:ff9b::/96` addresses are IPv4 addresses reached through NAT64.
+fn nat64_embedded(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let s = ip.segments();
+    (s[..6] == [0x64, 0xff9b, 0, 0, 0, 0]).then(|| Ipv4Addr::new((s[6] >> 8) as u8, s[6] as u8, (s[7] >> 8) as u8, s[7] as u8))
+}
+
+/// `2002::/16` addresses route to the IPv4 address in their next 32 bits through a 6to4 relay.
+fn six_to_four_embedded(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let s = ip.segments();
+    (s[0] == 0x2002).then(|| Ipv4Addr::new((s[1] >> 8) as u8, s[1] as u8, (s[2] >> 8) as u8, s[2] as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_public;
+
+    #[test]
+    fn only_public_addresses_are_reachable() {
+        let blocked = [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0",
+            "::1", "::", "fc00::1", "fe80::1",
+            "::ffff:127.0.0.1", // IPv4-mapped
+            "64:ff9b::a9fe:a9fe", // NAT64 of 169.254.169.254
+            "2002:7f00:1::", // 6to4 of 127.0.0.1
+            "2002:c0a8:101::1", // 6to4 of 192.168.1.1
+        ];
+        for ip in blocked {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} should be blocked");
+        }
+        for ip in ["93.184.216.34", "2606:2800:220:1::", "::ffff:93.184.216.34", "2002:5db8:d822::1"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} should be allowed");
+        }
+    }
+}
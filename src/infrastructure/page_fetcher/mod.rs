@@ -0,0 +1,18 @@
+#[cfg(test)]
+pub mod fake;
+pub mod http;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::LinkPreviewConfig;
+use crate::domain::previews::PageFetcher;
+
+pub fn from_config(cfg: &LinkPreviewConfig) -> anyhow::Result<Option<Arc<dyn PageFetcher>>> {
+    Ok(match cfg {
+        LinkPreviewConfig::Disabled => None,
+        LinkPreviewConfig::Http { timeout_secs, max_bytes } => {
+            Some(Arc::new(http::HttpPageFetcher::new(Duration::from_secs(*timeout_secs), *max_bytes)?))
+        }
+    })
+}
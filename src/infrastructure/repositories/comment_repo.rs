@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::comments::{Comment, CommentRepository};
+use crate::domain::posts::{FeedCursor, FeedSort};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgCommentRepository { pub pool: DbPool }
+
+#[async_trait]
+impl CommentRepository for PgCommentRepository {
+    async fn create(&self, post_id: Uuid, user_id: Uuid, parent_id: Option<Uuid>, body: &str, hold_reason: Option<&str>) -> anyhow::Result<Comment> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO comments (id, post_id, user_id, parent_id, body, held_at, hold_reason)
+                VALUES ($1, $2, $3, $4, $5, CASE WHEN $6::text IS NULL THEN NULL ELSE NOW() END, $6)
+            "#,
+            id, post_id, user_id, parent_id, body, hold_reason
+        )
+        .execute(&self.pool).await?;
+
+        let comment = self.find_by_id(id).await?
+            .ok_or_else(|| anyhow::anyhow!("comment {id} vanished after insert"))?;
+        Ok(comment)
+    }
+
+    async fn find_by_id(&self, comment_id: Uuid) -> anyhow::Result<Option<Comment>> {
+        find_comment(&self.pool, comment_id).await
+    }
+
+    async fn list_for_post(&self, post_id: Uuid, viewer: Option<Uuid>) -> anyhow::Result<Vec<Comment>> {
+        let comments = sqlx::query_as!(
+            Comment,
+            r#"SELECT c.id, c.post_id, c.user_id, c.parent_id, c.body, c.created_at, u.username as author_username, c.deleted_at, c.removed_at, c.removal_reason, c.held_at, c.edited_at,
+                      (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                      (c.user_id IS DISTINCT FROM $2 AND (c.held_at IS NOT NULL OR c.user_id IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))) AS "hidden!"
+                FROM comments c
+                JOIN users u ON c.user_id = u.id
+                WHERE c.post_id = $1
+                ORDER BY c.created_at ASC
+            "#,
+            post_id, viewer
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(comments)
+    }
+
+    async fn list_by_author(&self, user_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Comment>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let comments = match sort {
+            FeedSort::New => sqlx::query_as!(
+                Comment,
+                r#"SELECT c.id, c.post_id, c.user_id, c.parent_id, c.body, c.created_at, u.username as author_username, c.deleted_at, c.removed_at, c.removal_reason, c.held_at, c.edited_at,
+                      (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!", false AS "hidden!"
+                    FROM comments c
+                    JOIN users u ON c.user_id = u.id
+                    JOIN posts p ON p.id = c.post_id
+                    JOIN communities cm ON cm.id = p.community_id
+                    WHERE c.user_id = $1 AND cm.visibility <> 'private' AND c.deleted_at IS NULL AND c.removed_at IS NULL AND (c.held_at IS NULL OR c.user_id = $5)
+                      AND (c.user_id = $5 OR c.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (c.created_at, c.id) < ($2, $3::uuid))
+                    ORDER BY c.created_at DESC, c.id DESC LIMIT $4
+                "#,
+                user_id, created_at, id, limit, viewer
+            ).fetch_all(&self.pool).await?,
+            FeedSort::Top => sqlx::query_as!(
+                Comment,
+                r#"SELECT c.id, c.post_id, c.user_id, c.parent_id, c.body, c.created_at, u.username as author_username, c.deleted_at, c.removed_at, c.removal_reason, c.held_at, c.edited_at,
+                      (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!", false AS "hidden!"
+                    FROM comments c
+                    JOIN users u ON c.user_id = u.id
+                    JOIN posts p ON p.id = c.post_id
+                    JOIN communities cm ON cm.id = p.community_id
+                    WHERE c.user_id = $1 AND cm.visibility <> 'private' AND c.deleted_at IS NULL AND c.removed_at IS NULL AND (c.held_at IS NULL OR c.user_id = $5)
+                      AND (c.user_id = $5 OR c.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR ((SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id), c.created_at, c.id) < ($6::int8, $2, $3::uuid))
+                    ORDER BY (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) DESC, c.created_at DESC, c.id DESC LIMIT $4
+                "#,
+                user_id, created_at, id, limit, viewer, score
+            ).fetch_all(&self.pool).await?,
+        };
+        Ok(comments)
+    }
+
+    async fn delete(&self, comment_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE comments SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+            comment_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update(&self, comment_id: Uuid, editor_id: Uuid, body: &str, hold_reason: Option<&str>, mark_edited: bool) -> anyhow::Result<Comment> {
+        let mut tx = self.pool.begin().await?;
+
+        let revised = sqlx::query!(
+            r#"INSERT INTO comment_revisions (id, comment_id, editor_id, body)
+                SELECT $1, id, $2, body FROM comments WHERE id = $3 AND body <> $4
+            "#,
+            Uuid::new_v4(), editor_id, comment_id, body
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() > 0;
+
+        sqlx::query!(
+            r#"UPDATE comments
+                SET body = $2,
+                    edited_at = CASE WHEN $3 THEN NOW() ELSE edited_at END,
+                    held_at = CASE WHEN $4::text IS NULL THEN held_at ELSE COALESCE(held_at, NOW()) END,
+                    hold_reason = COALESCE($4, hold_reason)
+                WHERE id = $1
+            "#,
+            comment_id, body, revised && mark_edited, hold_reason
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let comment = self.find_by_id(comment_id).await?
+            .ok_or_else(|| anyhow::anyhow!("comment {comment_id} vanished after update"))?;
+        Ok(comment)
+    }
+}
+
+pub(crate) async fn remove_comment<'e>(db: impl PgExecutor<'e>, comment_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE comments SET removed_at = NOW(), removed_by = $2, removal_reason = NULLIF($3, '') WHERE id = $1 AND removed_at IS NULL",
+        comment_id, removed_by, reason
+    )
+    .execute(db).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn restore_comment<'e>(db: impl PgExecutor<'e>, comment_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE comments SET removed_at = NULL, removed_by = NULL, removal_reason = NULL WHERE id = $1 AND removed_at IS NOT NULL",
+        comment_id
+    )
+    .execute(db).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn approve_comment<'e>(db: impl PgExecutor<'e>, comment_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE comments SET held_at = NULL, hold_reason = NULL WHERE id = $1 AND held_at IS NOT NULL",
+        comment_id
+    )
+    .execute(db).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn find_comment<'e>(db: impl PgExecutor<'e>, comment_id: Uuid) -> anyhow::Result<Option<Comment>> {
+    let comment = sqlx::query_as!(
+        Comment,
+        r#"SELECT c.id, c.post_id, c.user_id, c.parent_id, c.body, c.created_at, u.username as author_username, c.deleted_at, c.removed_at, c.removal_reason, c.held_at, c.edited_at,
+                  (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!", false AS "hidden!"
+            FROM comments c
+            JOIN users u ON c.user_id = u.id
+            WHERE c.id = $1
+        "#,
+        comment_id
+    )
+    .fetch_optional(db).await?;
+    Ok(comment)
+}
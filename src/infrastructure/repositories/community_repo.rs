@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::communities::{Community, CommunityRepository, MemberRole, Visibility};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgCommunityRepository { pub pool: DbPool }
+
+#[async_trait]
+impl CommunityRepository for PgCommunityRepository {
+    async fn create(&self, slug: &str, name: &str, description: &str, rules: &[String], visibility: Visibility, owner_id: Uuid) -> anyhow::Result<Option<Community>> {
+        let id = Uuid::new_v4();
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query!(
+            r#"INSERT INTO communities (id, slug, name, description, rules, visibility, created_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (slug) DO NOTHING
+            "#,
+            id, slug, name, description, rules, visibility.as_str(), owner_id
+        )
+        .execute(&mut *tx).await?;
+        if inserted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "INSERT INTO community_members (community_id, user_id, role) VALUES ($1, $2, 'owner')",
+            id, owner_id
+        )
+        .execute(&mut *tx).await?;
+        tx.commit().await?;
+
+        let community = self.find_by_id(id).await?
+            .ok_or_else(|| anyhow::anyhow!("community {id} vanished after insert"))?;
+        Ok(Some(community))
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Community>> {
+        let row = sqlx::query_as!(
+            CommunityRow,
+            r#"SELECT c.id, c.slug, c.name, c.description, c.rules, c.visibility, c.created_at,
+                    (SELECT COUNT(*) FROM community_members m WHERE m.community_id = c.id) AS "member_count!"
+                FROM communities c
+                WHERE c.slug = $1
+            "#,
+            slug
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn find_by_id(&self, community_id: Uuid) -> anyhow::Result<Option<Community>> {
+        let row = sqlx::query_as!(
+            CommunityRow,
+            r#"SELECT c.id, c.slug, c.name, c.description, c.rules, c.visibility, c.created_at,
+                    (SELECT COUNT(*) FROM community_members m WHERE m.community_id = c.id) AS "member_count!"
+                FROM communities c
+                WHERE c.id = $1
+            "#,
+            community_id
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(row.map(Into::into))
+    }
+
+    async fn update(&self, community_id: Uuid, name: &str, description: &str, rules: &[String], visibility: Visibility) -> anyhow::Result<Community> {
+        sqlx::query!(
+            "UPDATE communities SET name = $2, description = $3, rules = $4, visibility = $5 WHERE id = $1",
+            community_id, name, description, rules, visibility.as_str()
+        )
+        .execute(&self.pool).await?;
+
+        self.find_by_id(community_id).await?
+            .ok_or_else(|| anyhow::anyhow!("community {community_id} not found"))
+    }
+
+    async fn member_role(&self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<Option<MemberRole>> {
+        let role = sqlx::query_scalar!(
+            "SELECT role FROM community_members WHERE community_id = $1 AND user_id = $2",
+            community_id, user_id
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(role.map(|r| r.as_str().into()))
+    }
+
+    async fn set_member(&self, community_id: Uuid, user_id: Uuid, role: MemberRole) -> anyhow::Result<()> {
+        set_member(&self.pool, community_id, user_id, role).await
+    }
+
+    async fn remove_member(&self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        remove_member(&self.pool, community_id, user_id).await
+    }
+
+    async fn list_joined(&self, user_id: Uuid) -> anyhow::Result<Vec<Community>> {
+        let rows = sqlx::query_as!(
+            CommunityRow,
+            r#"SELECT c.id, c.slug, c.name, c.description, c.rules, c.visibility, c.created_at,
+                    (SELECT COUNT(*) FROM community_members m WHERE m.community_id = c.id) AS "member_count!"
+                FROM community_members joined
+                JOIN communities c ON c.id = joined.community_id
+                WHERE joined.user_id = $1
+                ORDER BY c.slug
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CommunityRow {
+    id: Uuid,
+    slug: String,
+    name: String,
+    description: String,
+    rules: Vec<String>,
+    visibility: String,
+    created_at: DateTime<Utc>,
+    member_count: i64,
+}
+
+impl From<CommunityRow> for Community {
+    fn from(value: CommunityRow) -> Self {
+        Self {
+            id: value.id,
+            slug: value.slug,
+            name: value.name,
+            description: value.description,
+            rules: value.rules,
+            visibility: value.visibility.as_str().into(),
+            member_count: value.member_count,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub(crate) async fn set_member<'e>(db: impl PgExecutor<'e>, community_id: Uuid, user_id: Uuid, role: MemberRole) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"INSERT INTO community_members (community_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (community_id, user_id) DO UPDATE SET role = EXCLUDED.role
+        "#,
+        community_id, user_id, role.as_str()
+    )
+    .execute(db).await?;
+    Ok(())
+}
+
+pub(crate) async fn remove_member<'e>(db: impl PgExecutor<'e>, community_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM community_members WHERE community_id = $1 AND user_id = $2",
+        community_id, user_id
+    )
+    .execute(db).await?;
+    Ok(result.rows_affected() > 0)
+}
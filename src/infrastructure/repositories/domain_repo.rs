@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::domains::{DomainAction, DomainRepository, DomainRule, DomainStats};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgDomainRepository { pub pool: DbPool }
+
+struct DomainRuleRow {
+    domain: String,
+    action: String,
+    max_posts: Option<i32>,
+    window_hours: Option<i32>,
+    reason: Option<String>,
+    created_by: Option<Uuid>,
+    updated_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl DomainRepository for PgDomainRepository {
+    async fn rule_for(&self, domain: &str) -> anyhow::Result<Option<DomainRule>> {
+        let row = sqlx::query_as!(
+            DomainRuleRow,
+            r#"SELECT domain, action, max_posts, window_hours, reason, created_by, updated_at
+                FROM domain_rules
+                WHERE $1 = domain OR $1 LIKE '%.' || domain
+                ORDER BY length(domain) DESC
+                LIMIT 1
+            "#,
+            domain
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(row.map(DomainRule::from))
+    }
+
+    async fn list_rules(&self) -> anyhow::Result<Vec<DomainRule>> {
+        let rows = sqlx::query_as!(
+            DomainRuleRow,
+            "SELECT domain, action, max_posts, window_hours, reason, created_by, updated_at FROM domain_rules ORDER BY domain"
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(DomainRule::from).collect())
+    }
+
+    async fn upsert_rule(&self, domain: &str, action: DomainAction, max_posts: Option<i32>, window_hours: Option<i32>, reason: &str, created_by: Uuid) -> anyhow::Result<DomainRule> {
+        let row = sqlx::query_as!(
+            DomainRuleRow,
+            r#"INSERT INTO domain_rules (domain, action, max_posts, window_hours, reason, created_by)
+                VALUES ($1, $2, $3, $4, NULLIF($5, ''), $6)
+                ON CONFLICT (domain) DO UPDATE
+                SET action = EXCLUDED.action, max_posts = EXCLUDED.max_posts, window_hours = EXCLUDED.window_hours,
+                    reason = EXCLUDED.reason, created_by = EXCLUDED.created_by, updated_at = NOW()
+                RETURNING domain, action, max_posts, window_hours, reason, created_by, updated_at
+            "#,
+            domain, action.as_str(), max_posts, window_hours, reason, created_by
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(row.into())
+    }
+
+    async fn insert_rule(&self, domain: &str, action: DomainAction, reason: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            r#"INSERT INTO domain_rules (domain, action, reason)
+                VALUES ($1, $2, NULLIF($3, ''))
+                ON CONFLICT (domain) DO NOTHING
+            "#,
+            domain, action.as_str(), reason
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_rule(&self, domain: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query!("DELETE FROM domain_rules WHERE domain = $1", domain)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn count_recent_posts(&self, user_id: Uuid, domain: &str, since: DateTime<Utc>) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM posts
+                WHERE user_id = $1 AND (domain = $2 OR domain LIKE '%.' || $2) AND created_at > $3
+            "#,
+            user_id, domain, since
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(count)
+    }
+
+    async fn stats(&self, domain: &str) -> anyhow::Result<DomainStats> {
+        let stats = sqlx::query_as!(
+            DomainStats,
+            r#"SELECT $1::text AS "domain!",
+                      COUNT(*) FILTER (WHERE p.removed_at IS NULL) AS "post_count!",
+                      COUNT(DISTINCT p.user_id) FILTER (WHERE p.removed_at IS NULL) AS "author_count!",
+                      COALESCE(SUM(p.score) FILTER (WHERE p.removed_at IS NULL), 0)::int8 AS "total_score!",
+                      COUNT(*) FILTER (WHERE p.removed_at IS NOT NULL) AS "removed_count!",
+                      MIN(p.created_at) AS first_posted_at,
+                      MAX(p.created_at) AS last_posted_at
+                FROM posts p
+                JOIN communities c ON c.id = p.community_id
+                WHERE (p.domain = $1 OR p.domain LIKE '%.' || $1)
+                  AND p.deleted_at IS NULL AND p.held_at IS NULL AND c.visibility <> 'private'
+            "#,
+            domain
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(stats)
+    }
+}
+
+impl From<DomainRuleRow> for DomainRule {
+    fn from(row: DomainRuleRow) -> Self {
+        Self {
+            domain: row.domain,
+            action: row.action.as_str().into(),
+            max_posts: row.max_posts,
+            window_hours: row.window_hours,
+            reason: row.reason,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+        }
+    }
+}
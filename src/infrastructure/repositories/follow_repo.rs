@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::follows::FollowRepository;
+use crate::infrastructure::db::DbPool;
+
+pub struct PgFollowRepository { pub pool: DbPool }
+
+#[async_trait]
+impl FollowRepository for PgFollowRepository {
+    async fn follow_user(&self, follower_id: Uuid, followee_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO follows (follower_id, followee_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            follower_id, followee_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unfollow_user(&self, follower_id: Uuid, followee_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM follows WHERE follower_id = $1 AND followee_id = $2",
+            follower_id, followee_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn follow_tag(&self, user_id: Uuid, tag_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO tag_follows (user_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id, tag_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unfollow_tag(&self, user_id: Uuid, tag_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM tag_follows WHERE user_id = $1 AND tag_id = $2",
+            user_id, tag_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn followed_usernames(&self, user_id: Uuid) -> anyhow::Result<Vec<String>> {
+        let names = sqlx::query_scalar!(
+            r#"SELECT u.username
+                FROM follows f
+                JOIN users u ON u.id = f.followee_id
+                WHERE f.follower_id = $1 AND u.deleted_at IS NULL
+                ORDER BY u.username_normalized
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(names)
+    }
+
+    async fn followed_tags(&self, user_id: Uuid) -> anyhow::Result<Vec<String>> {
+        let slugs = sqlx::query_scalar!(
+            r#"SELECT t.slug
+                FROM tag_follows tf
+                JOIN tags t ON t.id = tf.tag_id
+                WHERE tf.user_id = $1
+                ORDER BY t.slug
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(slugs)
+    }
+
+    async fn has_subscriptions(&self, user_id: Uuid) -> anyhow::Result<bool> {
+        let any = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM follows WHERE follower_id = $1)
+                   OR EXISTS (SELECT 1 FROM tag_follows WHERE user_id = $1)
+                   OR EXISTS (SELECT 1 FROM community_members WHERE user_id = $1) AS "any!"
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(any)
+    }
+}
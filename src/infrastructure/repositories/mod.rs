@@ -1,3 +1,16 @@
 pub mod posts_repo;
 pub mod user_repo;
-pub mod vote_repo;
\ No newline at end of file
+pub mod vote_repo;
+pub mod comment_repo;
+pub mod tag_repo;
+pub mod community_repo;
+pub mod follow_repo;
+pub mod saved_repo;
+pub mod mute_repo;
+pub mod report_repo;
+pub mod sanction_repo;
+pub mod mod_log_repo;
+pub mod spam_repo;
+pub mod domain_repo;
+pub mod revision_repo;
+pub mod moderation_repo;
\ No newline at end of file
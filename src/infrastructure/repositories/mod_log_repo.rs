@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::mod_log::{ModAction, ModLogFilter, ModLogRepository, NewModAction};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgModLogRepository { pub pool: DbPool }
+
+struct ModActionRow {
+    id: Uuid,
+    actor_id: Uuid,
+    actor_username: String,
+    action: String,
+    target_type: String,
+    target_id: Uuid,
+    community: Option<String>,
+    reason: Option<String>,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+    created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl ModLogRepository for PgModLogRepository {
+    async fn record(&self, action: NewModAction) -> anyhow::Result<()> {
+        insert_action(&self.pool, action).await
+    }
+
+    async fn list(&self, filter: &ModLogFilter, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<ModAction>> {
+        let (created_at, id) = after.unzip();
+        let rows = sqlx::query_as!(
+            ModActionRow,
+            r#"SELECT m.id, m.actor_id, u.username AS actor_username, m.action, m.target_type, m.target_id,
+                      c.slug AS "community?", m.reason, m.before, m.after, m.created_at
+                FROM mod_actions m
+                JOIN users u ON u.id = m.actor_id
+                LEFT JOIN communities c ON c.id = m.community_id
+                WHERE ($1::uuid IS NULL OR m.community_id = $1)
+                  AND ($2::uuid IS NULL OR m.actor_id = $2)
+                  AND ($3::text IS NULL OR m.action = $3)
+                  AND ($4::text IS NULL OR m.target_type = $4)
+                  AND ($5::uuid IS NULL OR m.target_id = $5)
+                  AND (NOT $6 OR (
+                      m.action NOT IN ('shadowban', 'unshadowban')
+                      AND (c.id IS NULL OR c.visibility <> 'private')
+                  ))
+                  AND ($7::timestamptz IS NULL OR (m.created_at, m.id) < ($7, $8::uuid))
+                ORDER BY m.created_at DESC, m.id DESC
+                LIMIT $9
+            "#,
+            filter.community_id, filter.actor_id, filter.action.map(|a| a.as_str()),
+            filter.target_type.map(|t| t.as_str()), filter.target_id, filter.public,
+            created_at, id, limit
+        )
+        .fetch_all(&self.pool).await?;
+        rows.into_iter().map(ModAction::try_from).collect()
+    }
+}
+
+impl TryFrom<ModActionRow> for ModAction {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ModActionRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            actor_id: Some(row.actor_id),
+            actor_username: Some(row.actor_username),
+            action: row.action.as_str().try_into()?,
+            target_type: row.target_type.as_str().try_into()?,
+            target_id: row.target_id,
+            community: row.community,
+            reason: row.reason,
+            before: row.before,
+            after: row.after,
+            created_at: row.created_at,
+        })
+    }
+}
+
+pub(crate) async fn insert_action<'e>(db: impl PgExecutor<'e>, action: NewModAction) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"INSERT INTO mod_actions (id, actor_id, action, target_type, target_id, community_id, reason, before, after)
+            VALUES ($1, $2, $3, $4, $5, $6, NULLIF($7, ''), $8, $9)
+        "#,
+        Uuid::new_v4(), action.actor_id, action.action.as_str(), action.target_type.as_str(), action.target_id,
+        action.community_id, action.reason, action.before, action.after
+    )
+    .execute(db).await?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::comments::Comment;
+use crate::domain::communities::MemberRole;
+use crate::domain::mod_log::{ModerationStore, ModerationTx, NewModAction};
+use crate::domain::posts::{Post, PostEdit};
+use crate::domain::reports::{ReportTarget, Resolution};
+use crate::domain::sanctions::{Sanction, SanctionKind};
+use crate::infrastructure::db::DbPool;
+use crate::infrastructure::repositories::{comment_repo, community_repo, mod_log_repo, posts_repo, report_repo, sanction_repo, spam_repo};
+
+pub struct PgModerationStore { pub pool: DbPool }
+
+struct PgModerationTx { tx: Transaction<'static, Postgres> }
+
+#[async_trait]
+impl ModerationStore for PgModerationStore {
+    async fn begin(&self) -> anyhow::Result<Box<dyn ModerationTx>> {
+        Ok(Box::new(PgModerationTx { tx: self.pool.begin().await? }))
+    }
+}
+
+#[async_trait]
+impl ModerationTx for PgModerationTx {
+    async fn find_post(&mut self, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+        posts_repo::find_post(&mut *self.tx, post_id).await
+    }
+
+    async fn find_held_post(&mut self, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+        posts_repo::find_held_post(&mut *self.tx, post_id).await
+    }
+
+    async fn find_comment(&mut self, comment_id: Uuid) -> anyhow::Result<Option<Comment>> {
+        comment_repo::find_comment(&mut *self.tx, comment_id).await
+    }
+
+    async fn remove_post(&mut self, post_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool> {
+        posts_repo::remove_post(&mut *self.tx, post_id, removed_by, reason).await
+    }
+
+    async fn restore_post(&mut self, post_id: Uuid) -> anyhow::Result<bool> {
+        posts_repo::restore_post(&mut *self.tx, post_id).await
+    }
+
+    async fn approve_post(&mut self, post_id: Uuid) -> anyhow::Result<bool> {
+        posts_repo::approve_post(&mut *self.tx, post_id).await
+    }
+
+    async fn lock_post(&mut self, post_id: Uuid) -> anyhow::Result<bool> {
+        posts_repo::lock_post(&mut *self.tx, post_id).await
+    }
+
+    async fn unlock_post(&mut self, post_id: Uuid) -> anyhow::Result<bool> {
+        posts_repo::unlock_post(&mut *self.tx, post_id).await
+    }
+
+    async fn pin_post(&mut self, post_id: Uuid, position: Option<i32>) -> anyhow::Result<bool> {
+        posts_repo::pin_post(&mut self.tx, post_id, position).await
+    }
+
+    async fn unpin_post(&mut self, post_id: Uuid) -> anyhow::Result<bool> {
+        posts_repo::unpin_post(&mut *self.tx, post_id).await
+    }
+
+    async fn edit_post(&mut self, post_id: Uuid, editor_id: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>> {
+        posts_repo::update_post(&mut self.tx, post_id, editor_id, edit, mark_edited, expected_version).await
+    }
+
+    async fn remove_comment(&mut self, comment_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool> {
+        comment_repo::remove_comment(&mut *self.tx, comment_id, removed_by, reason).await
+    }
+
+    async fn restore_comment(&mut self, comment_id: Uuid) -> anyhow::Result<bool> {
+        comment_repo::restore_comment(&mut *self.tx, comment_id).await
+    }
+
+    async fn approve_comment(&mut self, comment_id: Uuid) -> anyhow::Result<bool> {
+        comment_repo::approve_comment(&mut *self.tx, comment_id).await
+    }
+
+    async fn set_member(&mut self, community_id: Uuid, user_id: Uuid, role: MemberRole) -> anyhow::Result<()> {
+        community_repo::set_member(&mut *self.tx, community_id, user_id, role).await
+    }
+
+    async fn remove_member(&mut self, community_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        community_repo::remove_member(&mut *self.tx, community_id, user_id).await
+    }
+
+    async fn spam_reported(&mut self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<bool> {
+        report_repo::spam_reported(&mut *self.tx, target_type, target_id).await
+    }
+
+    async fn resolve_reports(&mut self, target_type: ReportTarget, target_id: Uuid, resolved_by: Uuid, resolution: Resolution, note: &str) -> anyhow::Result<u64> {
+        report_repo::resolve_reports(&mut *self.tx, target_type, target_id, resolved_by, resolution, note).await
+    }
+
+    async fn create_sanction(&mut self, user_id: Uuid, kind: SanctionKind, reason: &str, created_by: Uuid, expires_at: Option<DateTime<Utc>>) -> anyhow::Result<Sanction> {
+        sanction_repo::insert_sanction(&mut *self.tx, user_id, kind, reason, created_by, expires_at).await
+    }
+
+    async fn revoke_sanction(&mut self, sanction_id: Uuid, revoked_by: Uuid) -> anyhow::Result<Option<Sanction>> {
+        sanction_repo::revoke_sanction(&mut *self.tx, sanction_id, revoked_by).await
+    }
+
+    async fn record(&mut self, action: NewModAction) -> anyhow::Result<()> {
+        mod_log_repo::insert_action(&mut *self.tx, action).await
+    }
+
+    async fn rate_domains(&mut self, domains: &[String], approved: bool) -> anyhow::Result<()> {
+        if domains.is_empty() {
+            return Ok(());
+        }
+        spam_repo::record_decision(&mut *self.tx, domains, approved).await
+    }
+
+    async fn commit(self: Box<Self>) -> anyhow::Result<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+}
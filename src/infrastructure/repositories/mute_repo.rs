@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::mutes::{FeedExclusions, HiddenEntry, MuteRepository, MutedDomain, MutedUser};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgMuteRepository { pub pool: DbPool }
+
+#[async_trait]
+impl MuteRepository for PgMuteRepository {
+    async fn hide_post(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO hidden_posts (user_id, post_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id, post_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unhide_post(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM hidden_posts WHERE user_id = $1 AND post_id = $2",
+            user_id, post_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_hidden(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<HiddenEntry>> {
+        let (hidden_at, post_id) = after.unzip();
+        let entries = sqlx::query_as!(
+            HiddenEntry,
+            r#"SELECT post_id, hidden_at
+                FROM hidden_posts
+                WHERE user_id = $1
+                  AND ($2::timestamptz IS NULL OR (hidden_at, post_id) < ($2, $3::uuid))
+                ORDER BY hidden_at DESC, post_id DESC
+                LIMIT $4
+            "#,
+            user_id, hidden_at, post_id, limit
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(entries)
+    }
+
+    async fn mute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO muted_users (user_id, muted_user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id, muted_user_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unmute_user(&self, user_id: Uuid, muted_user_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM muted_users WHERE user_id = $1 AND muted_user_id = $2",
+            user_id, muted_user_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_muted_users(&self, user_id: Uuid) -> anyhow::Result<Vec<MutedUser>> {
+        let users = sqlx::query_as!(
+            MutedUser,
+            r#"SELECT u.id AS user_id, u.username, m.muted_at
+                FROM muted_users m
+                JOIN users u ON u.id = m.muted_user_id
+                WHERE m.user_id = $1
+                ORDER BY u.username_normalized
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(users)
+    }
+
+    async fn mute_domain(&self, user_id: Uuid, domain: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "INSERT INTO muted_domains (user_id, domain) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id, domain
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unmute_domain(&self, user_id: Uuid, domain: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM muted_domains WHERE user_id = $1 AND domain = $2",
+            user_id, domain
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_muted_domains(&self, user_id: Uuid) -> anyhow::Result<Vec<MutedDomain>> {
+        let domains = sqlx::query_as!(
+            MutedDomain,
+            "SELECT domain, muted_at FROM muted_domains WHERE user_id = $1 ORDER BY domain",
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(domains)
+    }
+
+    async fn exclusions(&self, user_id: Option<Uuid>) -> anyhow::Result<FeedExclusions> {
+        let row = sqlx::query!(
+            r#"SELECT
+                ARRAY(SELECT post_id FROM hidden_posts WHERE user_id = $1) AS "post_ids!",
+                ARRAY(
+                    SELECT u.username_normalized FROM muted_users m JOIN users u ON u.id = m.muted_user_id WHERE m.user_id = $1
+                    UNION
+                    SELECT u.username_normalized FROM active_sanctions s JOIN users u ON u.id = s.user_id
+                    WHERE s.kind = 'shadowban' AND u.id IS DISTINCT FROM $1
+                ) AS "authors!",
+                ARRAY(SELECT domain FROM muted_domains WHERE user_id = $1) AS "domains!"
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(FeedExclusions { post_ids: row.post_ids, authors: row.authors, domains: row.domains })
+    }
+}
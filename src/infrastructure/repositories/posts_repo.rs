@@ -3,155 +3,372 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use log::debug;
 use uuid::Uuid;
-use sqlx::{Pool, Postgres, Transaction};
+use sqlx::{PgConnection, PgExecutor, Postgres, Transaction};
 use crate::infrastructure::db::DbPool;
 
-use crate::domain::posts::{Post, PostRepository};
+use crate::domain::posts::{FeedCursor, FeedSort, NewPost, Post, PostEdit, PostRepository, ViewerState};
+use crate::domain::previews::{LinkPreview, PreviewRepository};
 
 pub struct PgPostRepository { pub pool: DbPool }
 
 #[async_trait]
 impl PostRepository for PgPostRepository {
-    async fn create(&self, user_id: Uuid, title: &str, short_description: &str, url: &Option<String>, body: &Option<String>) -> anyhow::Result<Post> {
+    async fn create(&self, post: &NewPost) -> anyhow::Result<Post> {
         let id = Uuid::new_v4();
+        let mut tx = self.pool.begin().await?;
         sqlx::query!(
-            r#"INSERT INTO posts (id, user_id, title, short_description, url, body)
-                VALUES ($1, $2, $3, $4, $5, $6)
+            r#"INSERT INTO posts (id, user_id, community_id, title, short_description, url, canonical_url, body, held_at, hold_reason)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CASE WHEN $9::text IS NULL THEN NULL ELSE NOW() END, $9)
             "#,
-            id, user_id, title, short_description, url.as_deref(), body.as_deref()
+            id, post.user_id, post.community_id, post.title, post.short_description, post.url.as_deref(), post.canonical_url.as_deref(), post.body.as_deref(), post.hold_reason.as_deref()
         )
-        .execute(&self.pool).await?;
+        .execute(&mut *tx).await?;
 
-        let post = sqlx::query_as!(
-            Post,
-            r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                FROM posts p
-                JOIN users u ON p.user_id = u.id
-                WHERE p.id = $1
-            "#,
-            id
-        )
-        .fetch_one(&self.pool).await?;
+        set_post_tags(&mut tx, id, &post.tags).await?;
+
+        let post = sqlx::query_as::<_, Post>("SELECT p.* FROM post_view p WHERE p.id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx).await?;
 
+        tx.commit().await?;
         Ok(post)
     }
 
-    async fn list_new(&self, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
-        let posts = if let Some((created_at, id)) = after {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    WHERE (p.created_at, p.id) < ($1, $2)
-                    ORDER BY p.created_at DESC, p.id DESC LIMIT $3
-                "#,
-                created_at, id, limit
-            ).fetch_all(&self.pool).await?
-        } else {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    ORDER BY p.created_at DESC, p.id DESC LIMIT $1
-                "#,
-                limit
-            ).fetch_all(&self.pool).await?
+    async fn list_new(&self, viewer: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (created_at, id) = after.unzip();
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.pinned_at IS NULL AND p.community_visibility <> 'private'
+                  AND (p.user_id = $3 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($1::timestamptz IS NULL OR (p.created_at, p.id) < ($1, $2::uuid))
+                  AND ($3::uuid IS NULL OR NOT (
+                      EXISTS (SELECT 1 FROM hidden_posts h WHERE h.user_id = $3 AND h.post_id = p.id)
+                      OR EXISTS (SELECT 1 FROM muted_users m WHERE m.user_id = $3 AND m.muted_user_id = p.user_id)
+                      OR EXISTS (SELECT 1 FROM muted_domains md WHERE md.user_id = $3 AND (p.domain = md.domain OR p.domain LIKE '%.' || md.domain))
+                  ))
+                ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+            "#
+        )
+        .bind(created_at).bind(id).bind(viewer).bind(limit)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
+
+    async fn list_top(&self, viewer: Option<Uuid>, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.pinned_at IS NULL AND p.community_visibility <> 'private'
+                  AND (p.user_id = $3 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($1::timestamptz IS NULL OR (p.score, p.created_at, p.id) < ($5::int8, $1, $2::uuid))
+                  AND ($3::uuid IS NULL OR NOT (
+                      EXISTS (SELECT 1 FROM hidden_posts h WHERE h.user_id = $3 AND h.post_id = p.id)
+                      OR EXISTS (SELECT 1 FROM muted_users m WHERE m.user_id = $3 AND m.muted_user_id = p.user_id)
+                      OR EXISTS (SELECT 1 FROM muted_domains md WHERE md.user_id = $3 AND (p.domain = md.domain OR p.domain LIKE '%.' || md.domain))
+                  ))
+                ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $4
+            "#
+        )
+        .bind(created_at).bind(id).bind(viewer).bind(limit).bind(score)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
+
+    async fn list_pinned(&self, viewer: Option<Uuid>) -> anyhow::Result<Vec<Post>> {
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.pinned_at IS NOT NULL AND p.community_visibility <> 'private'
+                  AND (p.user_id = $1 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($1::uuid IS NULL OR NOT (
+                      EXISTS (SELECT 1 FROM hidden_posts h WHERE h.user_id = $1 AND h.post_id = p.id)
+                      OR EXISTS (SELECT 1 FROM muted_users m WHERE m.user_id = $1 AND m.muted_user_id = p.user_id)
+                      OR EXISTS (SELECT 1 FROM muted_domains md WHERE md.user_id = $1 AND (p.domain = md.domain OR p.domain LIKE '%.' || md.domain))
+                  ))
+                ORDER BY p.pin_position, p.pinned_at DESC
+            "#
+        )
+        .bind(viewer)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
+
+    async fn list_by_author(&self, user_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let posts = match sort {
+            FeedSort::New => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.user_id = $1 AND p.community_visibility <> 'private'
+                      AND (p.user_id = $5 OR (p.held_at IS NULL AND p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban')))
+                      AND ($2::timestamptz IS NULL OR (p.created_at, p.id) < ($2, $3::uuid))
+                    ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(user_id).bind(created_at).bind(id).bind(limit).bind(viewer)
+        .fetch_all(&self.pool).await?,
+            FeedSort::Top => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.user_id = $1 AND p.community_visibility <> 'private'
+                      AND (p.user_id = $5 OR (p.held_at IS NULL AND p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban')))
+                      AND ($2::timestamptz IS NULL OR (p.score, p.created_at, p.id) < ($6::int8, $2, $3::uuid))
+                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(user_id).bind(created_at).bind(id).bind(limit).bind(viewer).bind(score)
+        .fetch_all(&self.pool).await?,
         };
         Ok(posts)
     }
 
-    async fn list_top(&self, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
-        let posts = if let Some((created_at, id)) = after {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    WHERE (p.created_at, p.id) < ($1, $2)
-                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $3
-                "#,
-                created_at, id, limit
-            ).fetch_all(&self.pool).await?
-        } else {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $1
-                "#,
-                limit
-            ).fetch_all(&self.pool).await?
+    async fn list_by_tag(&self, tag_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let posts = match sort {
+            FeedSort::New => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_tags tagged
+                    JOIN post_view p ON p.id = tagged.post_id
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND tagged.tag_id = $1 AND p.community_visibility <> 'private'
+                      AND (p.user_id = $5 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.created_at, p.id) < ($2, $3::uuid))
+                    ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(tag_id).bind(created_at).bind(id).bind(limit).bind(viewer)
+        .fetch_all(&self.pool).await?,
+            FeedSort::Top => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_tags tagged
+                    JOIN post_view p ON p.id = tagged.post_id
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND tagged.tag_id = $1 AND p.community_visibility <> 'private'
+                      AND (p.user_id = $6 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.score, p.created_at, p.id) < ($5::int8, $2, $3::uuid))
+                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(tag_id).bind(created_at).bind(id).bind(limit).bind(score).bind(viewer)
+        .fetch_all(&self.pool).await?,
         };
         Ok(posts)
     }
 
-    async fn update(&self, post_id: Uuid, title: &str, short_description: &str, url: &Option<String>, body: &Option<String>) -> anyhow::Result<Post> {
-        let post = sqlx::query_as!(
-            Post,
-            r#"
-                UPDATE posts p
-                SET title = $1, short_description = $2, url = $3, body = $4
-                FROM users u
-                WHERE p.id = $5 AND p.user_id = u.id -- Thêm u.id vào WHERE nếu cần JOIN
-                RETURNING p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-            "#,
-            title,
-            short_description,
-            url.as_deref(),
-            body.as_deref(),
-            post_id
+    async fn list_by_domain(&self, domain: &str, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let posts = match sort {
+            FeedSort::New => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND (p.domain = $1 OR p.domain LIKE '%.' || $1) AND p.community_visibility <> 'private'
+                      AND (p.user_id = $5 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.created_at, p.id) < ($2, $3::uuid))
+                    ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(domain).bind(created_at).bind(id).bind(limit).bind(viewer)
+        .fetch_all(&self.pool).await?,
+            FeedSort::Top => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND (p.domain = $1 OR p.domain LIKE '%.' || $1) AND p.community_visibility <> 'private'
+                      AND (p.user_id = $5 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.score, p.created_at, p.id) < ($6::int8, $2, $3::uuid))
+                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(domain).bind(created_at).bind(id).bind(limit).bind(viewer).bind(score)
+        .fetch_all(&self.pool).await?,
+        };
+        Ok(posts)
+    }
+
+    async fn list_by_community(&self, community_id: Uuid, viewer: Option<Uuid>, sort: FeedSort, after: Option<FeedCursor>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (score, created_at, id) = (after.and_then(|a| a.score), after.map(|a| a.created_at), after.map(|a| a.id));
+        let posts = match sort {
+            FeedSort::New => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.community_id = $1
+                      AND (p.user_id = $5 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.created_at, p.id) < ($2, $3::uuid))
+                    ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(community_id).bind(created_at).bind(id).bind(limit).bind(viewer)
+        .fetch_all(&self.pool).await?,
+            FeedSort::Top => sqlx::query_as::<_, Post>(
+                r#"SELECT p.*
+                    FROM post_view p
+                    WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.community_id = $1
+                      AND (p.user_id = $6 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                      AND ($2::timestamptz IS NULL OR (p.score, p.created_at, p.id) < ($5::int8, $2, $3::uuid))
+                    ORDER BY p.score DESC, p.created_at DESC, p.id DESC LIMIT $4
+                "#
+            )
+        .bind(community_id).bind(created_at).bind(id).bind(limit).bind(score).bind(viewer)
+        .fetch_all(&self.pool).await?,
+        };
+        Ok(posts)
+    }
+
+    async fn list_hot(&self, viewer: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (hot_at, id) = after.unzip();
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.community_visibility <> 'private'
+                  AND (p.user_id = $4 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($1::timestamptz IS NULL OR (p.hot_at, p.id) < ($1, $2::uuid))
+                ORDER BY p.hot_at DESC, p.id DESC LIMIT $3
+            "#
         )
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(hot_at).bind(id).bind(limit).bind(viewer)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
 
+    async fn list_home(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (hot_at, id) = after.unzip();
+        let posts = sqlx::query_as::<_, Post>(
+            r#"WITH joined AS (SELECT community_id FROM community_members WHERE user_id = $1)
+                SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND (p.community_id IN (SELECT community_id FROM joined)
+                       OR p.user_id IN (SELECT followee_id FROM follows WHERE follower_id = $1)
+                       OR EXISTS (
+                           SELECT 1 FROM post_tags pt
+                           JOIN tag_follows tf ON tf.tag_id = pt.tag_id
+                           WHERE pt.post_id = p.id AND tf.user_id = $1
+                       ))
+                  -- a followed author's posts in a private community stay hidden from non-members
+                  AND (p.community_visibility <> 'private' OR p.community_id IN (SELECT community_id FROM joined))
+                  AND (p.user_id = $1 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($2::timestamptz IS NULL OR (p.hot_at, p.id) < ($2, $3::uuid))
+                ORDER BY p.hot_at DESC, p.id DESC LIMIT $4
+            "#
+        )
+        .bind(user_id).bind(hot_at).bind(id).bind(limit)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
+
+    async fn list_upvoted_by(&self, user_id: Uuid, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
+        let (created_at, id) = after.unzip();
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM votes v
+                JOIN post_view p ON p.id = v.post_id
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND v.user_id = $1 AND v.value = 1
+                  AND (p.user_id = $1 OR p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))
+                  AND ($2::timestamptz IS NULL OR (p.created_at, p.id) < ($2, $3::uuid))
+                ORDER BY p.created_at DESC, p.id DESC LIMIT $4
+            "#
+        )
+        .bind(user_id).bind(created_at).bind(id).bind(limit)
+        .fetch_all(&self.pool).await?;
+        Ok(posts)
+    }
+
+    async fn update(&self, post_id: Uuid, editor_id: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>> {
+        let mut tx = self.pool.begin().await?;
+        let post = update_post(&mut tx, post_id, editor_id, edit, mark_edited, expected_version).await?;
+        // a stale version rolls back the tags and the revision along with it
+        if post.is_some() {
+            tx.commit().await?;
+        }
         Ok(post)
     }
 
-    async fn delete(&self, post_id: Uuid) -> anyhow::Result<()> {
-        let post = sqlx::query_as!(
-            Post,
-            r#"
-                DELETE FROM posts
-                WHERE id = $1
-            "#,
+    async fn delete(&self, post_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE posts SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
             post_id
         )
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected() > 0)
+    }
 
-        Ok(())
+    async fn archive_older_than(&self, cutoff: DateTime<Utc>) -> anyhow::Result<u64> {
+        let result = sqlx::query!(
+            "UPDATE posts SET archived_at = NOW(), version = version + 1 WHERE archived_at IS NULL AND pinned_at IS NULL AND created_at < $1",
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
     }
 
-    async fn search_by_title(&self, query: &str, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<Post>> {
-        let posts = if let Some((created_at, id)) = after {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    WHERE p.title ILIKE $1 AND (p.created_at, p.id) < ($2, $3)
-                    ORDER BY p.created_at DESC, p.id DESC LIMIT $4
-                "#,
-                format!("%{}%", query), created_at, id, limit
-            ).fetch_all(&self.pool).await?
-        } else {
-            sqlx::query_as!(
-                Post,
-                r#"SELECT p.id, p.user_id, p.title, p.url, p.body, p.short_description, p.score, p.created_at, u.avatar, u.username as author_username
-                    FROM posts p
-                    JOIN users u ON p.user_id = u.id
-                    WHERE p.title ILIKE $1
-                    ORDER BY p.created_at DESC, p.id DESC LIMIT $2
-                "#,
-                format!("%{}%", query), limit
-            ).fetch_all(&self.pool).await?
-        };
+    async fn find_by_id(&self, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+        find_post(&self.pool, post_id).await
+    }
+
+    async fn find_held(&self, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+        find_held_post(&self.pool, post_id).await
+    }
+
+    async fn find_for_viewer(&self, post_id: Uuid, viewer: Option<Uuid>) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.id = $1
+                  AND (p.held_at IS NULL OR p.user_id = $2
+                    OR EXISTS (SELECT 1 FROM users u WHERE u.id = $2 AND u.role IN ('moderator', 'admin'))
+                    OR EXISTS (SELECT 1 FROM community_members m
+                               WHERE m.community_id = p.community_id AND m.user_id = $2 AND m.role IN ('moderator', 'owner')))
+            "#
+        )
+        .bind(post_id)
+        .bind(viewer)
+        .fetch_optional(&self.pool).await?;
+        Ok(post)
+    }
+
+    async fn find_by_ids(&self, post_ids: &[Uuid]) -> anyhow::Result<Vec<Post>> {
+        let posts = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.id = ANY($1) AND p.community_visibility <> 'private'
+            "#
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool).await?;
         Ok(posts)
     }
 
+    async fn find_by_canonical_url(&self, community_id: Uuid, canonical_url: &str, since: DateTime<Utc>) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"SELECT p.*
+                FROM post_view p
+                WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.community_id = $1 AND p.canonical_url = $2 AND p.created_at > $3
+                  AND p.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban')
+                ORDER BY p.created_at DESC
+                LIMIT 1
+            "#
+        )
+        .bind(community_id).bind(canonical_url).bind(since)
+        .fetch_optional(&self.pool).await?;
+        Ok(post)
+    }
+
+    async fn viewer_states(&self, user_id: Uuid, post_ids: &[Uuid]) -> anyhow::Result<Vec<ViewerState>> {
+        let states = sqlx::query_as!(
+            ViewerState,
+            r#"SELECT ids.post_id AS "post_id!",
+                      COALESCE(v.value, 0::int2) AS "my_vote!",
+                      EXISTS (SELECT 1 FROM saved_posts s WHERE s.user_id = $1 AND s.post_id = ids.post_id) AS "saved!",
+                      EXISTS (SELECT 1 FROM hidden_posts h WHERE h.user_id = $1 AND h.post_id = ids.post_id) AS "hidden!"
+                FROM UNNEST($2::uuid[]) AS ids(post_id)
+                LEFT JOIN votes v ON v.user_id = $1 AND v.post_id = ids.post_id
+            "#,
+            user_id, post_ids
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(states)
+    }
+
     async fn upsert_vote_and_recompute(&self, user_id: Uuid, post_id: Uuid, value: i16) -> anyhow::Result<(i32, DateTime<Utc>)> {
         let mut tx = self.pool.begin().await?;
 
@@ -166,7 +383,11 @@ impl PostRepository for PgPostRepository {
         .fetch_optional(&mut *tx)
         .await?;
 
-        if let Some(record) = existing {
+        if value == 0 {
+            sqlx::query!("DELETE FROM votes WHERE user_id = $1 AND post_id = $2", user_id, post_id)
+                .execute(&mut *tx)
+                .await?;
+        } else if let Some(record) = existing {
             if record.value == value {
                 sqlx::query!(
                     "DELETE FROM votes WHERE user_id = $1 AND post_id = $2", 
@@ -197,18 +418,20 @@ impl PostRepository for PgPostRepository {
         }
 
         // Upsert the vote
-        sqlx::query!(
-            r#"
-            INSERT INTO votes (user_id, post_id, value)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (user_id, post_id) DO UPDATE SET value = EXCLUDED.value
-            "#,
-            user_id,
-            post_id,
-            value
-        )
-        .execute(&mut *tx)
-        .await?;
+        if value != 0 {
+            sqlx::query!(
+                r#"
+                INSERT INTO votes (user_id, post_id, value)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, post_id) DO UPDATE SET value = EXCLUDED.value
+                "#,
+                user_id,
+                post_id,
+                value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
 
         // Recompute the post score
         let score_record = sqlx::query!(
@@ -228,7 +451,8 @@ impl PostRepository for PgPostRepository {
         let updated_post = sqlx::query!(
             r#"
             UPDATE posts
-            SET score = $1
+            SET score = $1::int,
+                hot_at = created_at + make_interval(secs => sign($1::int::float8) * log(greatest(abs($1::int), 1)::float8) * 45000)
             WHERE id = $2
             RETURNING created_at
             "#,
@@ -243,4 +467,223 @@ impl PostRepository for PgPostRepository {
         Ok((new_score.unwrap_or(0), updated_post.created_at))
     }
     
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl PreviewRepository for PgPostRepository {
+    async fn set_preview(&self, post_id: Uuid, url: &str, preview: Option<&LinkPreview>) -> anyhow::Result<()> {
+        let preview = preview.cloned().unwrap_or_default();
+        sqlx::query!(
+            r#"UPDATE posts
+                SET preview_title = $3, preview_site_name = $4, preview_description = $5, preview_image_url = $6,
+                    preview_fetched_at = NOW(),
+                    short_description = CASE WHEN btrim(short_description) = '' THEN coalesce($5, '') ELSE short_description END
+                WHERE id = $1 AND url = $2
+            "#,
+            post_id, url, preview.title, preview.site_name, preview.description, preview.image_url
+        )
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+/// Replaces the post's tags. Slugs that are aliases resolve to their canonical tag; unknown slugs become new tags.
+async fn set_post_tags(tx: &mut Transaction<'_, Postgres>, post_id: Uuid, tags: &[String]) -> anyhow::Result<()> {
+    sqlx::query!("DELETE FROM post_tags WHERE post_id = $1", post_id)
+        .execute(&mut **tx).await?;
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let new_ids: Vec<Uuid> = tags.iter().map(|_| Uuid::new_v4()).collect();
+    sqlx::query!(
+        r#"INSERT INTO tags (id, slug)
+            SELECT id, slug FROM unnest($1::uuid[], $2::text[]) AS input(id, slug)
+            WHERE NOT EXISTS (SELECT 1 FROM tag_aliases a WHERE a.alias_slug = input.slug)
+            ON CONFLICT (slug) DO NOTHING
+        "#,
+        &new_ids, tags
+    )
+    .execute(&mut **tx).await?;
+
+    sqlx::query!(
+        r#"INSERT INTO post_tags (post_id, tag_id)
+            SELECT $1, COALESCE(a.tag_id, t.id)
+            FROM unnest($2::text[]) AS input(slug)
+            LEFT JOIN tag_aliases a ON a.alias_slug = input.slug
+            LEFT JOIN tags t ON t.slug = input.slug
+            ON CONFLICT DO NOTHING
+        "#,
+        post_id, tags
+    )
+    .execute(&mut **tx).await?;
+    Ok(())
+}
+
+pub(crate) async fn remove_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid, removed_by: Uuid, reason: &str) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET removed_at = NOW(), removed_by = $2, removal_reason = NULLIF($3, ''), version = version + 1 WHERE id = $1 AND removed_at IS NULL",
+        post_id, removed_by, reason
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn restore_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET removed_at = NULL, removed_by = NULL, removal_reason = NULL, version = version + 1 WHERE id = $1 AND removed_at IS NOT NULL",
+        post_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn lock_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET locked_at = NOW(), version = version + 1 WHERE id = $1 AND locked_at IS NULL",
+        post_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn unlock_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET locked_at = NULL, version = version + 1 WHERE id = $1 AND locked_at IS NOT NULL",
+        post_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Pins at a taken `position` move the others from there on down one.
+pub(crate) async fn pin_post(conn: &mut PgConnection, post_id: Uuid, position: Option<i32>) -> anyhow::Result<bool> {
+    let pinned = sqlx::query!(
+        r#"UPDATE posts
+            SET pinned_at = COALESCE(pinned_at, NOW()),
+                pin_position = COALESCE($2, (SELECT COALESCE(MAX(pin_position), 0) + 1 FROM posts WHERE pinned_at IS NOT NULL AND id <> $1)),
+                version = version + 1
+            WHERE id = $1 AND deleted_at IS NULL AND removed_at IS NULL AND held_at IS NULL
+        "#,
+        post_id, position
+    )
+    .execute(&mut *conn)
+    .await?
+    .rows_affected() > 0;
+
+    if let (true, Some(position)) = (pinned, position) {
+        sqlx::query!(
+            r#"UPDATE posts SET pin_position = pin_position + 1, version = version + 1
+                WHERE pinned_at IS NOT NULL AND id <> $1 AND pin_position >= $2
+            "#,
+            post_id, position
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(pinned)
+}
+
+pub(crate) async fn unpin_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET pinned_at = NULL, pin_position = NULL, version = version + 1 WHERE id = $1 AND pinned_at IS NOT NULL",
+        post_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn approve_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE posts SET held_at = NULL, hold_reason = NULL, version = version + 1 WHERE id = $1 AND held_at IS NOT NULL",
+        post_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn find_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+    let post = sqlx::query_as::<_, Post>(
+        r#"SELECT p.*
+            FROM post_view p
+            WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.id = $1
+        "#
+    )
+    .bind(post_id)
+    .fetch_optional(db).await?;
+    Ok(post)
+}
+
+pub(crate) async fn find_held_post<'e>(db: impl PgExecutor<'e>, post_id: Uuid) -> anyhow::Result<Option<Post>> {
+    let post = sqlx::query_as::<_, Post>(
+        r#"SELECT p.*
+            FROM post_view p
+            WHERE p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NOT NULL AND p.id = $1
+        "#
+    )
+    .bind(post_id)
+    .fetch_optional(db).await?;
+    Ok(post)
+}
+
+/// `PostRepository::update` inside `tx`; nothing is committed.
+pub(crate) async fn update_post(tx: &mut Transaction<'_, Postgres>, post_id: Uuid, editor_id: Uuid, edit: &PostEdit, mark_edited: bool, expected_version: Option<i32>) -> anyhow::Result<Option<Post>> {
+    set_post_tags(tx, post_id, &edit.tags).await?;
+
+    let revised = sqlx::query!(
+        r#"INSERT INTO post_revisions (id, post_id, editor_id, title, short_description, url, body)
+            SELECT $1, id, $2, title, short_description, url, body
+            FROM posts
+            WHERE id = $3 AND (title, short_description, url, body) IS DISTINCT FROM ($4, $5, $6, $7)
+        "#,
+        Uuid::new_v4(), editor_id, post_id, edit.title, edit.short_description, edit.url.as_deref(), edit.body.as_deref()
+    )
+    .execute(&mut **tx)
+    .await?
+    .rows_affected() > 0;
+
+    let updated = sqlx::query!(
+        r#"
+            UPDATE posts p
+            SET title = $1, short_description = $2, url = $3, body = $4, canonical_url = $6,
+                edited_at = CASE WHEN $7 THEN NOW() ELSE p.edited_at END,
+                held_at = CASE WHEN $9::text IS NOT NULL AND p.held_at IS NULL THEN NOW() ELSE p.held_at END,
+                hold_reason = CASE WHEN $9::text IS NOT NULL AND p.held_at IS NULL THEN $9 ELSE p.hold_reason END,
+                version = p.version + 1,
+                -- a new link needs a new preview
+                preview_title = CASE WHEN p.url IS DISTINCT FROM $3 THEN NULL ELSE p.preview_title END,
+                preview_site_name = CASE WHEN p.url IS DISTINCT FROM $3 THEN NULL ELSE p.preview_site_name END,
+                preview_description = CASE WHEN p.url IS DISTINCT FROM $3 THEN NULL ELSE p.preview_description END,
+                preview_image_url = CASE WHEN p.url IS DISTINCT FROM $3 THEN NULL ELSE p.preview_image_url END,
+                preview_fetched_at = CASE WHEN p.url IS DISTINCT FROM $3 THEN NULL ELSE p.preview_fetched_at END
+            WHERE p.id = $5 AND ($8::int4 IS NULL OR p.version = $8)
+        "#,
+        edit.title,
+        edit.short_description,
+        edit.url.as_deref(),
+        edit.body.as_deref(),
+        post_id,
+        edit.canonical_url.as_deref(),
+        revised && mark_edited,
+        expected_version,
+        edit.hold_reason.as_deref()
+    )
+    .execute(&mut **tx)
+    .await?
+    .rows_affected() > 0;
+
+    let post = match updated {
+        true => sqlx::query_as::<_, Post>("SELECT p.* FROM post_view p WHERE p.id = $1")
+            .bind(post_id)
+            .fetch_optional(&mut **tx)
+            .await?,
+        false => None,
+    };
+    Ok(post)
+}
@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::reports::{HeldItem, ReasonCount, ReportReason, ReportRepository, ReportTarget, ReportedItem, Resolution};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgReportRepository { pub pool: DbPool }
+
+struct ReportedItemRow {
+    target_type: String,
+    target_id: Uuid,
+    community: String,
+    post_id: Option<Uuid>,
+    author_username: Option<String>,
+    excerpt: Option<String>,
+    removed: bool,
+    deleted: bool,
+    report_count: i64,
+    reasons: Vec<String>,
+    reason_counts: Vec<i64>,
+    details: Vec<String>,
+    first_reported_at: DateTime<Utc>,
+    last_reported_at: DateTime<Utc>,
+}
+
+struct HeldItemRow {
+    target_type: String,
+    target_id: Uuid,
+    community: String,
+    post_id: Uuid,
+    author_username: String,
+    excerpt: String,
+    hold_reason: Option<String>,
+    held_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl ReportRepository for PgReportRepository {
+    async fn upsert(&self, reporter_id: Uuid, target_type: ReportTarget, target_id: Uuid, community_id: Uuid, reason: ReportReason, details: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO reports (id, reporter_id, target_type, target_id, community_id, reason, details)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (reporter_id, target_type, target_id) WHERE resolved_at IS NULL
+                DO UPDATE SET reason = EXCLUDED.reason, details = EXCLUDED.details
+            "#,
+            Uuid::new_v4(), reporter_id, target_type.as_str(), target_id, community_id, reason.as_str(), details
+        )
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn queue(&self, community_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<ReportedItem>> {
+        let (last_reported_at, target_id) = after.unzip();
+        let rows = sqlx::query_as!(
+            ReportedItemRow,
+            r#"WITH per_reason AS (
+                    SELECT target_type, target_id, community_id, reason,
+                           COUNT(*) AS n, MIN(created_at) AS first_at, MAX(created_at) AS last_at
+                    FROM reports
+                    WHERE resolved_at IS NULL AND ($1::uuid IS NULL OR community_id = $1)
+                    GROUP BY target_type, target_id, community_id, reason
+                ), items AS (
+                    SELECT target_type, target_id, community_id,
+                           SUM(n)::int8 AS report_count,
+                           array_agg(reason ORDER BY n DESC, reason) AS reasons,
+                           array_agg(n ORDER BY n DESC, reason) AS reason_counts,
+                           MIN(first_at) AS first_reported_at,
+                           MAX(last_at) AS last_reported_at
+                    FROM per_reason
+                    GROUP BY target_type, target_id, community_id
+                )
+                SELECT i.target_type AS "target_type!", i.target_id AS "target_id!", c.slug AS community,
+                       COALESCE(p.id, cm.post_id) AS post_id,
+                       u.username AS "author_username?",
+                       COALESCE(p.title, LEFT(cm.body, 280)) AS excerpt,
+                       COALESCE(p.removed_at, cm.removed_at) IS NOT NULL AS "removed!",
+                       COALESCE(p.deleted_at, cm.deleted_at) IS NOT NULL AS "deleted!",
+                       i.report_count AS "report_count!",
+                       i.reasons AS "reasons!",
+                       i.reason_counts AS "reason_counts!",
+                       ARRAY(
+                           SELECT r.details FROM reports r
+                           WHERE r.target_type = i.target_type AND r.target_id = i.target_id
+                             AND r.resolved_at IS NULL AND r.details <> ''
+                           ORDER BY r.created_at DESC LIMIT 10
+                       ) AS "details!",
+                       i.first_reported_at AS "first_reported_at!",
+                       i.last_reported_at AS "last_reported_at!"
+                FROM items i
+                JOIN communities c ON c.id = i.community_id
+                LEFT JOIN posts p ON i.target_type = 'post' AND p.id = i.target_id
+                LEFT JOIN comments cm ON i.target_type = 'comment' AND cm.id = i.target_id
+                LEFT JOIN users u ON u.id = COALESCE(p.user_id, cm.user_id)
+                WHERE ($2::timestamptz IS NULL OR (i.last_reported_at, i.target_id) < ($2, $3::uuid))
+                ORDER BY i.last_reported_at DESC, i.target_id DESC
+                LIMIT $4
+            "#,
+            community_id, last_reported_at, target_id, limit
+        )
+        .fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(ReportedItem::try_from).collect()
+    }
+
+    async fn open_report_community(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>> {
+        let community_id = sqlx::query_scalar!(
+            r#"SELECT community_id FROM reports
+                WHERE target_type = $1 AND target_id = $2 AND resolved_at IS NULL
+                LIMIT 1
+            "#,
+            target_type.as_str(), target_id
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(community_id)
+    }
+
+    async fn target_community(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>> {
+        let community_id = match target_type {
+            ReportTarget::Post => sqlx::query_scalar!("SELECT community_id FROM posts WHERE id = $1", target_id)
+                .fetch_optional(&self.pool).await?,
+            ReportTarget::Comment => sqlx::query_scalar!(
+                "SELECT p.community_id FROM comments c JOIN posts p ON p.id = c.post_id WHERE c.id = $1",
+                target_id
+            ).fetch_optional(&self.pool).await?,
+        };
+        Ok(community_id)
+    }
+
+    async fn target_author(&self, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<Option<Uuid>> {
+        let author = match target_type {
+            ReportTarget::Post => sqlx::query_scalar!("SELECT user_id FROM posts WHERE id = $1", target_id)
+                .fetch_optional(&self.pool).await?,
+            ReportTarget::Comment => sqlx::query_scalar!("SELECT user_id FROM comments WHERE id = $1", target_id)
+                .fetch_optional(&self.pool).await?,
+        };
+        Ok(author)
+    }
+
+    async fn held_queue(&self, community_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<HeldItem>> {
+        let (held_at, target_id) = after.unzip();
+        let rows = sqlx::query_as!(
+            HeldItemRow,
+            r#"SELECT h.target_type AS "target_type!", h.target_id AS "target_id!", c.slug AS community,
+                      h.post_id AS "post_id!", u.username AS author_username, h.excerpt AS "excerpt!",
+                      h.hold_reason, h.held_at AS "held_at!"
+                FROM (
+                    SELECT 'post' AS target_type, p.id AS target_id, p.id AS post_id, p.community_id, p.user_id,
+                           p.title AS excerpt, p.hold_reason, p.held_at
+                    FROM posts p
+                    WHERE p.held_at IS NOT NULL AND p.deleted_at IS NULL AND p.removed_at IS NULL
+                    UNION ALL
+                    SELECT 'comment', cm.id, cm.post_id, p.community_id, cm.user_id,
+                           LEFT(cm.body, 280), cm.hold_reason, cm.held_at
+                    FROM comments cm
+                    JOIN posts p ON p.id = cm.post_id
+                    WHERE cm.held_at IS NOT NULL AND cm.deleted_at IS NULL AND cm.removed_at IS NULL
+                ) h
+                JOIN communities c ON c.id = h.community_id
+                JOIN users u ON u.id = h.user_id
+                WHERE ($1::uuid IS NULL OR h.community_id = $1)
+                  AND ($2::timestamptz IS NULL OR (h.held_at, h.target_id) > ($2, $3::uuid))
+                ORDER BY h.held_at ASC, h.target_id ASC
+                LIMIT $4
+            "#,
+            community_id, held_at, target_id, limit
+        )
+        .fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(HeldItem::try_from).collect()
+    }
+}
+
+impl TryFrom<HeldItemRow> for HeldItem {
+    type Error = anyhow::Error;
+
+    fn try_from(row: HeldItemRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            target_type: row.target_type.as_str().try_into()?,
+            target_id: row.target_id,
+            community: row.community,
+            post_id: row.post_id,
+            author_username: row.author_username,
+            excerpt: row.excerpt,
+            hold_reason: row.hold_reason,
+            held_at: row.held_at,
+        })
+    }
+}
+
+impl TryFrom<ReportedItemRow> for ReportedItem {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ReportedItemRow) -> Result<Self, Self::Error> {
+        let reasons = row.reasons.iter()
+            .zip(row.reason_counts)
+            .map(|(reason, count)| Ok(ReasonCount { reason: reason.as_str().try_into()?, count }))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            target_type: row.target_type.as_str().try_into()?,
+            target_id: row.target_id,
+            community: row.community,
+            post_id: row.post_id,
+            author_username: row.author_username,
+            excerpt: row.excerpt,
+            removed: row.removed,
+            deleted: row.deleted,
+            report_count: row.report_count,
+            reasons,
+            details: row.details,
+            first_reported_at: row.first_reported_at,
+            last_reported_at: row.last_reported_at,
+        })
+    }
+}
+
+pub(crate) async fn spam_reported<'e>(db: impl PgExecutor<'e>, target_type: ReportTarget, target_id: Uuid) -> anyhow::Result<bool> {
+    let reported = sqlx::query_scalar!(
+        r#"SELECT EXISTS(
+                SELECT 1 FROM reports
+                WHERE target_type = $1 AND target_id = $2 AND resolved_at IS NULL AND reason = $3
+            ) AS "reported!"
+        "#,
+        target_type.as_str(), target_id, ReportReason::Spam.as_str()
+    )
+    .fetch_one(db).await?;
+    Ok(reported)
+}
+
+pub(crate) async fn resolve_reports<'e>(db: impl PgExecutor<'e>, target_type: ReportTarget, target_id: Uuid, resolved_by: Uuid, resolution: Resolution, note: &str) -> anyhow::Result<u64> {
+    let result = sqlx::query!(
+        r#"UPDATE reports
+            SET resolved_at = NOW(), resolved_by = $3, resolution = $4, resolution_note = NULLIF($5, '')
+            WHERE target_type = $1 AND target_id = $2 AND resolved_at IS NULL
+        "#,
+        target_type.as_str(), target_id, resolved_by, resolution.as_str(), note
+    )
+    .execute(db).await?;
+    Ok(result.rows_affected())
+}
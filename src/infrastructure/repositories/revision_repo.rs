@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::revisions::{CommentRevision, PostRevision, RevisionRepository};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgRevisionRepository { pub pool: DbPool }
+
+#[async_trait]
+impl RevisionRepository for PgRevisionRepository {
+    async fn post_revisions(&self, post_id: Uuid) -> anyhow::Result<Vec<PostRevision>> {
+        let revisions = sqlx::query_as!(
+            PostRevision,
+            r#"SELECT r.post_id, r.title, r.short_description, r.url, r.body, u.username AS "editor_username?", r.replaced_at
+                FROM post_revisions r
+                LEFT JOIN users u ON u.id = r.editor_id
+                WHERE r.post_id = $1
+                ORDER BY r.replaced_at, r.id
+            "#,
+            post_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(revisions)
+    }
+
+    async fn comment_revisions(&self, comment_id: Uuid) -> anyhow::Result<Vec<CommentRevision>> {
+        let revisions = sqlx::query_as!(
+            CommentRevision,
+            r#"SELECT r.comment_id, r.body, u.username AS "editor_username?", r.replaced_at
+                FROM comment_revisions r
+                LEFT JOIN users u ON u.id = r.editor_id
+                WHERE r.comment_id = $1
+                ORDER BY r.replaced_at, r.id
+            "#,
+            comment_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(revisions)
+    }
+}
@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::sanctions::{EvasionSignal, Sanction, SanctionRepository, SanctionKind};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgSanctionRepository { pub pool: DbPool }
+
+struct SanctionRow {
+    id: Uuid,
+    user_id: Uuid,
+    kind: String,
+    reason: Option<String>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+impl SanctionRepository for PgSanctionRepository {
+
+    async fn active_for(&self, user_id: Uuid) -> anyhow::Result<Vec<Sanction>> {
+        let rows = sqlx::query_as!(
+            SanctionRow,
+            r#"SELECT id, user_id, kind, reason, created_by, created_at, expires_at, revoked_at
+                FROM user_sanctions
+                WHERE user_id = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+                ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Sanction::from).collect())
+    }
+
+    async fn list_for(&self, user_id: Uuid) -> anyhow::Result<Vec<Sanction>> {
+        let rows = sqlx::query_as!(
+            SanctionRow,
+            r#"SELECT id, user_id, kind, reason, created_by, created_at, expires_at, revoked_at
+                FROM user_sanctions
+                WHERE user_id = $1
+                ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Sanction::from).collect())
+    }
+
+    async fn record_ip(&self, user_id: Uuid, ip: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO user_ips (user_id, ip) VALUES ($1, $2)
+                ON CONFLICT (user_id, ip) DO UPDATE SET last_seen_at = NOW()
+            "#,
+            user_id, ip
+        )
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn evasion_signals(&self, common_domains: &[String], limit: i64) -> anyhow::Result<Vec<EvasionSignal>> {
+        let signals = sqlx::query_as!(
+            EvasionSignal,
+            r#"WITH bans AS (
+                    SELECT user_id, MIN(created_at) AS banned_at FROM active_sanctions
+                    WHERE kind = 'ban' GROUP BY user_id
+                )
+                SELECT u.id AS "user_id!", u.username AS "username!", u.created_at AS "created_at!",
+                       'shared_ip' AS "signal!", ui.ip AS "matched!", bu.username AS "banned_username!"
+                FROM bans b
+                JOIN users bu ON bu.id = b.user_id
+                JOIN user_ips bi ON bi.user_id = b.user_id
+                JOIN user_ips ui ON ui.ip = bi.ip AND ui.user_id <> b.user_id
+                JOIN users u ON u.id = ui.user_id
+                WHERE u.deleted_at IS NULL AND u.created_at > b.banned_at
+                  AND u.id NOT IN (SELECT user_id FROM bans)
+                UNION
+                SELECT u.id, u.username, u.created_at,
+                       'email_domain', split_part(u.email, '@', 2), bu.username
+                FROM bans b
+                JOIN users bu ON bu.id = b.user_id
+                JOIN users u ON split_part(u.email, '@', 2) = split_part(bu.email, '@', 2) AND u.id <> bu.id
+                WHERE u.deleted_at IS NULL AND u.created_at > b.banned_at
+                  AND u.id NOT IN (SELECT user_id FROM bans)
+                  AND split_part(u.email, '@', 2) <> ALL($1)
+                ORDER BY 3 DESC, 2
+                LIMIT $2
+            "#,
+            common_domains, limit
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(signals)
+    }
+}
+
+impl From<SanctionRow> for Sanction {
+    fn from(row: SanctionRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            kind: row.kind.as_str().into(),
+            reason: row.reason,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+pub(crate) async fn insert_sanction<'e>(db: impl PgExecutor<'e>, user_id: Uuid, kind: SanctionKind, reason: &str, created_by: Uuid, expires_at: Option<DateTime<Utc>>) -> anyhow::Result<Sanction> {
+    let row = sqlx::query_as!(
+        SanctionRow,
+        r#"INSERT INTO user_sanctions (id, user_id, kind, reason, created_by, expires_at)
+            VALUES ($1, $2, $3, NULLIF($4, ''), $5, $6)
+            RETURNING id, user_id, kind, reason, created_by, created_at, expires_at, revoked_at
+        "#,
+        Uuid::new_v4(), user_id, kind.as_str(), reason, created_by, expires_at
+    )
+    .fetch_one(db).await?;
+    Ok(row.into())
+}
+
+pub(crate) async fn revoke_sanction<'e>(db: impl PgExecutor<'e>, sanction_id: Uuid, revoked_by: Uuid) -> anyhow::Result<Option<Sanction>> {
+    let row = sqlx::query_as!(
+        SanctionRow,
+        r#"UPDATE user_sanctions SET revoked_at = NOW(), revoked_by = $2
+            WHERE id = $1 AND revoked_at IS NULL
+            RETURNING id, user_id, kind, reason, created_by, created_at, expires_at, revoked_at
+        "#,
+        sanction_id, revoked_by
+    )
+    .fetch_optional(db).await?;
+    Ok(row.map(Sanction::from))
+}
@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::saved::{SavedCollection, SavedEntry, SavedRepository};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgSavedRepository { pub pool: DbPool }
+
+#[async_trait]
+impl SavedRepository for PgSavedRepository {
+    async fn save(&self, user_id: Uuid, post_id: Uuid, collection_id: Option<Uuid>) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO saved_posts (user_id, post_id, collection_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, post_id) DO UPDATE SET collection_id = EXCLUDED.collection_id
+            "#,
+            user_id, post_id, collection_id
+        )
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn unsave(&self, user_id: Uuid, post_id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM saved_posts WHERE user_id = $1 AND post_id = $2",
+            user_id, post_id
+        )
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self, user_id: Uuid, collection_id: Option<Uuid>, after: Option<(DateTime<Utc>, Uuid)>, limit: i64) -> anyhow::Result<Vec<SavedEntry>> {
+        let (saved_at, post_id) = after.unzip();
+        let entries = sqlx::query_as!(
+            SavedEntry,
+            r#"SELECT post_id, collection_id, saved_at
+                FROM saved_posts
+                WHERE user_id = $1
+                  AND ($2::uuid IS NULL OR collection_id = $2)
+                  AND ($3::timestamptz IS NULL OR (saved_at, post_id) < ($3, $4::uuid))
+                ORDER BY saved_at DESC, post_id DESC
+                LIMIT $5
+            "#,
+            user_id, collection_id, saved_at, post_id, limit
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(entries)
+    }
+
+    async fn create_collection(&self, user_id: Uuid, name: &str) -> anyhow::Result<SavedCollection> {
+        let collection = sqlx::query_as!(
+            SavedCollection,
+            r#"INSERT INTO saved_collections (id, user_id, name)
+                VALUES ($1, $2, $3)
+                RETURNING id, name, 0::int8 AS "post_count!", created_at
+            "#,
+            Uuid::new_v4(), user_id, name
+        )
+        .fetch_one(&self.pool).await?;
+        Ok(collection)
+    }
+
+    async fn find_collection(&self, user_id: Uuid, collection_id: Uuid) -> anyhow::Result<Option<SavedCollection>> {
+        let collection = sqlx::query_as!(
+            SavedCollection,
+            r#"SELECT c.id, c.name, c.created_at,
+                      (SELECT COUNT(*) FROM saved_posts s WHERE s.collection_id = c.id) AS "post_count!"
+                FROM saved_collections c
+                WHERE c.user_id = $1 AND c.id = $2
+            "#,
+            user_id, collection_id
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(collection)
+    }
+
+    async fn find_collection_by_name(&self, user_id: Uuid, name: &str) -> anyhow::Result<Option<SavedCollection>> {
+        let collection = sqlx::query_as!(
+            SavedCollection,
+            r#"SELECT c.id, c.name, c.created_at,
+                      (SELECT COUNT(*) FROM saved_posts s WHERE s.collection_id = c.id) AS "post_count!"
+                FROM saved_collections c
+                WHERE c.user_id = $1 AND lower(c.name) = lower($2)
+            "#,
+            user_id, name
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(collection)
+    }
+
+    async fn list_collections(&self, user_id: Uuid) -> anyhow::Result<Vec<SavedCollection>> {
+        let collections = sqlx::query_as!(
+            SavedCollection,
+            r#"SELECT c.id, c.name, c.created_at,
+                      (SELECT COUNT(*) FROM saved_posts s WHERE s.collection_id = c.id) AS "post_count!"
+                FROM saved_collections c
+                WHERE c.user_id = $1
+                ORDER BY lower(c.name)
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(collections)
+    }
+
+    async fn rename_collection(&self, collection_id: Uuid, name: &str) -> anyhow::Result<()> {
+        sqlx::query!("UPDATE saved_collections SET name = $2 WHERE id = $1", collection_id, name)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_collection(&self, collection_id: Uuid) -> anyhow::Result<()> {
+        sqlx::query!("DELETE FROM saved_collections WHERE id = $1", collection_id)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::domain::spam::{AuthorActivity, DomainReputation, SpamRepository};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgSpamRepository { pub pool: DbPool }
+
+/// How many of an author's latest texts are compared against a new one.
+const RECENT_TEXTS: i64 = 20;
+
+#[async_trait]
+impl SpamRepository for PgSpamRepository {
+    async fn author_activity(&self, user_id: Uuid, velocity_since: DateTime<Utc>, texts_since: DateTime<Utc>) -> anyhow::Result<Option<AuthorActivity>> {
+        let activity = sqlx::query_as!(
+            AuthorActivity,
+            r#"SELECT u.created_at AS account_created_at,
+                      COALESCE((SELECT SUM(p.score) FROM posts p
+                                WHERE p.user_id = u.id AND p.deleted_at IS NULL AND p.removed_at IS NULL), 0)::int8 AS "karma!",
+                      (SELECT COUNT(*) FROM posts p WHERE p.user_id = u.id AND p.created_at > $2)
+                        + (SELECT COUNT(*) FROM comments c WHERE c.user_id = u.id AND c.created_at > $2) AS "recent_count!",
+                      ARRAY(
+                          SELECT r.text FROM (
+                              SELECT concat_ws(E'\n', p.title, p.url, p.body) AS text, p.created_at
+                              FROM posts p WHERE p.user_id = u.id AND p.created_at > $3
+                              UNION ALL
+                              SELECT c.body, c.created_at
+                              FROM comments c WHERE c.user_id = u.id AND c.created_at > $3
+                          ) r
+                          ORDER BY r.created_at DESC
+                          LIMIT $4
+                      ) AS "recent_texts!"
+                FROM users u
+                WHERE u.id = $1
+            "#,
+            user_id, velocity_since, texts_since, RECENT_TEXTS
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(activity)
+    }
+
+    async fn reputations(&self, domains: &[String]) -> anyhow::Result<Vec<DomainReputation>> {
+        let reputations = sqlx::query_as!(
+            DomainReputation,
+            "SELECT domain, approved, removed FROM domain_reputation WHERE domain = ANY($1)",
+            domains
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(reputations)
+    }
+}
+
+pub(crate) async fn record_decision<'e>(db: impl PgExecutor<'e>, domains: &[String], approved: bool) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"INSERT INTO domain_reputation (domain, approved, removed)
+            SELECT d, CASE WHEN $2 THEN 1 ELSE 0 END, CASE WHEN $2 THEN 0 ELSE 1 END
+            FROM unnest($1::text[]) AS d
+            ON CONFLICT (domain) DO UPDATE
+            SET approved = domain_reputation.approved + EXCLUDED.approved,
+                removed = domain_reputation.removed + EXCLUDED.removed,
+                updated_at = NOW()
+        "#,
+        domains, approved
+    )
+    .execute(db).await?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::tags::{Tag, TagRepository};
+use crate::infrastructure::db::DbPool;
+
+pub struct PgTagRepository { pub pool: DbPool }
+
+#[async_trait]
+impl TagRepository for PgTagRepository {
+    async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Tag>> {
+        let tag = sqlx::query_as!(
+            Tag,
+            r#"SELECT t.id, t.slug, t.created_at,
+                    (SELECT COUNT(*) FROM post_tags pt WHERE pt.tag_id = t.id) AS "post_count!"
+                FROM tags t
+                WHERE t.slug = $1
+                   OR t.id = (SELECT a.tag_id FROM tag_aliases a WHERE a.alias_slug = $1)
+            "#,
+            slug
+        )
+        .fetch_optional(&self.pool).await?;
+        Ok(tag)
+    }
+
+    async fn autocomplete(&self, prefix: &str, limit: i64) -> anyhow::Result<Vec<Tag>> {
+        let pattern = format!("{}%", prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        let tags = sqlx::query_as!(
+            Tag,
+            r#"SELECT t.id, t.slug, t.created_at,
+                    (SELECT COUNT(*) FROM post_tags pt WHERE pt.tag_id = t.id) AS "post_count!"
+                FROM tags t
+                WHERE t.slug LIKE $1
+                   OR EXISTS (SELECT 1 FROM tag_aliases a WHERE a.tag_id = t.id AND a.alias_slug LIKE $1)
+                ORDER BY "post_count!" DESC, t.slug
+                LIMIT $2
+            "#,
+            pattern, limit
+        )
+        .fetch_all(&self.pool).await?;
+        Ok(tags)
+    }
+
+    async fn add_alias(&self, tag_id: Uuid, alias_slug: &str, created_by: Uuid) -> anyhow::Result<()> {
+        sqlx::query!(
+            "INSERT INTO tag_aliases (alias_slug, tag_id, created_by) VALUES ($1, $2, $3)",
+            alias_slug, tag_id, created_by
+        )
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn remove_alias(&self, alias_slug: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query!("DELETE FROM tag_aliases WHERE alias_slug = $1", alias_slug)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn merge(&self, source_id: Uuid, target_id: Uuid, merged_by: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        let moved: Vec<Uuid> = sqlx::query_scalar!(
+            "SELECT post_id FROM post_tags WHERE tag_id = $1",
+            source_id
+        )
+        .fetch_all(&mut *tx).await?;
+
+        sqlx::query!(
+            r#"INSERT INTO post_tags (post_id, tag_id)
+                SELECT post_id, $2 FROM post_tags WHERE tag_id = $1
+                ON CONFLICT DO NOTHING
+            "#,
+            source_id, target_id
+        )
+        .execute(&mut *tx).await?;
+
+        sqlx::query!(
+            r#"INSERT INTO tag_follows (user_id, tag_id)
+                SELECT user_id, $2 FROM tag_follows WHERE tag_id = $1
+                ON CONFLICT DO NOTHING
+            "#,
+            source_id, target_id
+        )
+        .execute(&mut *tx).await?;
+
+        sqlx::query!("UPDATE tag_aliases SET tag_id = $2 WHERE tag_id = $1", source_id, target_id)
+            .execute(&mut *tx).await?;
+
+        // deleting the tag cascades to its post_tags and tag_follows rows
+        let source_slug = sqlx::query_scalar!("DELETE FROM tags WHERE id = $1 RETURNING slug", source_id)
+            .fetch_one(&mut *tx).await?;
+
+        sqlx::query!(
+            "INSERT INTO tag_aliases (alias_slug, tag_id, created_by) VALUES ($1, $2, $3)",
+            source_slug, target_id, merged_by
+        )
+        .execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(moved)
+    }
+}
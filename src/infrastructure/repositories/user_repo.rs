@@ -1,7 +1,8 @@
 use uuid::Uuid;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use crate::{domain::users::{User, UserRepository}, infrastructure::{auth, db::DbPool}};
+use crate::{domain::users::{PendingSkeleton, User, UserRepository, Username}, infrastructure::{auth, db::DbPool}};
 
 pub struct PgUserRepository {
     pub pool: DbPool,
@@ -10,13 +11,13 @@ pub struct PgUserRepository {
 
 #[async_trait]
 impl UserRepository for PgUserRepository {
-    async fn create(&self, email: &str, username: &str, avatar: &str, password_hash: &str) -> anyhow::Result<User> {
+    async fn create(&self, email: &str, username: &Username, avatar: &str, password_hash: &str) -> anyhow::Result<User> {
         let id = Uuid::new_v4();
         let rec = sqlx::query_as!(UserRow, 
-            r#"INSERT INTO users (id, email, username, avatar, password_hash)
-                VALUES ($1, $2, $3, $4, $5)
-                RETURNING id, email, username, avatar, password_hash, created_at"#,
-            id, email, username, avatar, password_hash
+            r#"INSERT INTO users (id, email, username, username_normalized, username_skeleton, avatar, password_hash)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id, email, username, avatar, password_hash, created_at, deletion_requested_at, role"#,
+            id, email, username.display, username.normalized, username.skeleton, avatar, password_hash
         )
         .fetch_one(&self.pool).await?;
 
@@ -25,9 +26,154 @@ impl UserRepository for PgUserRepository {
     
     async fn find_by_email_or_username(&self, key: &str) -> anyhow::Result<Option<User>> {
         let row = sqlx::query_as!(UserRow,
-            r#"SELECT id, email, username, avatar, password_hash, created_at 
+            r#"SELECT id, email, username, avatar, password_hash, created_at, deletion_requested_at, role
                 FROM users
-                WHERE email = $1 OR username = $1 LIMIT 1"#, key
+                WHERE (email = $1 OR username_normalized = $1) AND deleted_at IS NULL LIMIT 1"#, key
+        ).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.into()))
+    }
+
+    async fn find_by_username(&self, username: &Username) -> anyhow::Result<Option<User>> {
+        let row = sqlx::query_as!(UserRow,
+            r#"SELECT id, email, username, avatar, password_hash, created_at, deletion_requested_at, role
+                FROM users
+                WHERE username_normalized = $1 AND deleted_at IS NULL"#, username.normalized
+        ).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.into()))
+    }
+
+    async fn find_by_username_skeleton(&self, username: &Username) -> anyhow::Result<Option<User>> {
+        let row = sqlx::query_as!(UserRow,
+            r#"SELECT id, email, username, avatar, password_hash, created_at, deletion_requested_at, role
+                FROM users
+                WHERE username_skeleton = $1 AND deleted_at IS NULL"#, username.skeleton
+        ).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.into()))
+    }
+
+    async fn find_by_id(&self, user_id: Uuid) -> anyhow::Result<Option<User>> {
+        let row = sqlx::query_as!(UserRow,
+            r#"SELECT id, email, username, avatar, password_hash, created_at, deletion_requested_at, role
+                FROM users
+                WHERE id = $1 AND deleted_at IS NULL"#, user_id
+        ).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.into()))
+    }
+
+    async fn update_avatar(&self, user_id: Uuid, avatar: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            "UPDATE users SET avatar = $2 WHERE id = $1 AND deleted_at IS NULL",
+            user_id, avatar
+        ).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn set_deletion_requested(&self, user_id: Uuid, at: Option<DateTime<Utc>>) -> anyhow::Result<()> {
+        sqlx::query!(
+            "UPDATE users SET deletion_requested_at = $2 WHERE id = $1 AND deleted_at IS NULL",
+            user_id, at
+        ).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn anonymize_requested_before(&self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        // Posts and comments stay; only the identity behind them goes away.
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query!(
+            r#"WITH expired AS (
+                    SELECT id, avatar FROM users WHERE deletion_requested_at <= $1 AND deleted_at IS NULL FOR UPDATE
+                )
+                UPDATE users u
+                SET username = '[deleted]',
+                    username_normalized = '[deleted]',
+                    username_skeleton = '[deleted]',
+                    email = 'deleted+' || u.id::text || '@invalid',
+                    avatar = '',
+                    password_hash = '',
+                    deleted_at = NOW()
+                FROM expired
+                WHERE u.id = expired.id
+                RETURNING u.id, expired.avatar"#,
+            cutoff
+        ).fetch_all(&mut *tx).await?;
+        let (ids, avatars): (Vec<Uuid>, Vec<String>) = rows.into_iter().map(|r| (r.id, r.avatar)).unzip();
+
+        // who they followed and who followed them is personal data too, as are their bookmarks
+        sqlx::query!("DELETE FROM follows WHERE follower_id = ANY($1) OR followee_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM tag_follows WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM saved_posts WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM saved_collections WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM hidden_posts WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM muted_users WHERE user_id = ANY($1) OR muted_user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM muted_domains WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM user_ips WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+        // former names would keep pointing at the account and redirecting to it
+        sqlx::query!("DELETE FROM username_history WHERE user_id = ANY($1)", &ids)
+            .execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(avatars)
+    }
+
+    async fn change_username(&self, user_id: Uuid, new_username: &Username, reserved_until: DateTime<Utc>) -> anyhow::Result<User> {
+        let mut tx = self.pool.begin().await?;
+
+        let current = sqlx::query!(
+            "SELECT username, username_skeleton FROM users WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+            user_id
+        ).fetch_one(&mut *tx).await?;
+
+        sqlx::query!(
+            r#"INSERT INTO username_history (id, user_id, old_username, old_username_skeleton, new_username, reserved_until)
+                VALUES ($1, $2, $3, $4, $5, $6)"#,
+            Uuid::new_v4(), user_id, current.username, current.username_skeleton, new_username.display, reserved_until
+        ).execute(&mut *tx).await?;
+
+        let row = sqlx::query_as!(UserRow,
+            r#"UPDATE users SET username = $2, username_normalized = $3, username_skeleton = $4
+                WHERE id = $1
+                RETURNING id, email, username, avatar, password_hash, created_at, deletion_requested_at, role"#,
+            user_id, new_username.display, new_username.normalized, new_username.skeleton
+        ).fetch_one(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(row.into())
+    }
+
+    async fn last_username_change(&self, user_id: Uuid) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let rec = sqlx::query!(
+            "SELECT MAX(changed_at) AS last_change FROM username_history WHERE user_id = $1",
+            user_id
+        ).fetch_one(&self.pool).await?;
+        Ok(rec.last_change)
+    }
+
+    async fn username_reserved_by(&self, username: &Username) -> anyhow::Result<Option<Uuid>> {
+        let rec = sqlx::query!(
+            r#"SELECT user_id FROM username_history
+                WHERE old_username_skeleton = $1 AND reserved_until > NOW()
+                ORDER BY changed_at DESC LIMIT 1"#,
+            username.skeleton
+        ).fetch_optional(&self.pool).await?;
+        Ok(rec.map(|r| r.user_id))
+    }
+
+    async fn find_by_previous_username(&self, username: &Username) -> anyhow::Result<Option<User>> {
+        let row = sqlx::query_as!(UserRow,
+            r#"SELECT u.id, u.email, u.username, u.avatar, u.password_hash, u.created_at, u.deletion_requested_at, u.role
+                FROM username_history h
+                JOIN users u ON u.id = h.user_id
+                WHERE h.old_username_skeleton = $1 AND u.deleted_at IS NULL
+                ORDER BY h.changed_at DESC LIMIT 1"#,
+            username.skeleton
         ).fetch_optional(&self.pool).await?;
         Ok(row.map(|r| r.into()))
     }
@@ -37,6 +183,49 @@ impl UserRepository for PgUserRepository {
         Ok(claims)
     }
 
+    async fn pending_skeletons(&self, limit: i64) -> anyhow::Result<Vec<PendingSkeleton>> {
+        let pending = sqlx::query_as!(PendingSkeleton,
+            r#"SELECT id, username FROM users
+                WHERE username_skeleton_pending
+                ORDER BY created_at, id LIMIT $1"#,
+            limit
+        ).fetch_all(&self.pool).await?;
+        Ok(pending)
+    }
+
+    async fn set_skeleton(&self, user_id: Uuid, skeleton: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"UPDATE users u
+                SET username_skeleton = CASE
+                        WHEN EXISTS (SELECT 1 FROM users o WHERE o.username_skeleton = $2 AND o.deleted_at IS NULL AND o.id <> u.id)
+                        THEN $2 || '#' || u.id::text
+                        ELSE $2
+                    END,
+                    username_skeleton_pending = FALSE
+                WHERE u.id = $1"#,
+            user_id, skeleton
+        ).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn pending_history_skeletons(&self, limit: i64) -> anyhow::Result<Vec<PendingSkeleton>> {
+        let pending = sqlx::query_as!(PendingSkeleton,
+            r#"SELECT id, old_username AS username FROM username_history
+                WHERE old_username_skeleton_pending
+                LIMIT $1"#,
+            limit
+        ).fetch_all(&self.pool).await?;
+        Ok(pending)
+    }
+
+    async fn set_history_skeleton(&self, history_id: Uuid, skeleton: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            "UPDATE username_history SET old_username_skeleton = $2, old_username_skeleton_pending = FALSE WHERE id = $1",
+            history_id, skeleton
+        ).execute(&self.pool).await?;
+        Ok(())
+    }
+
 }
 
 #[derive(sqlx::FromRow)]
@@ -46,7 +235,9 @@ struct UserRow {
     username: String,
     avatar: String,
     password_hash: String,
-    created_at: chrono::DateTime<chrono::Utc>
+    created_at: DateTime<Utc>,
+    deletion_requested_at: Option<DateTime<Utc>>,
+    role: String,
 }
 
 impl From<UserRow> for User  {
@@ -58,6 +249,8 @@ impl From<UserRow> for User  {
             avatar: value.avatar,
             password_hash: value.password_hash,
             created_at: value.created_at,
+            deletion_requested_at: value.deletion_requested_at,
+            role: value.role.as_str().into(),
         }
     }
 }
\ No newline at end of file
@@ -68,5 +68,20 @@ impl VoteRepository for PgVoteRepository {
         Ok(record.score.unwrap_or(0) as i16)
     }
 
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<Vote>, anyhow::Error> {
+        let votes = sqlx::query_as!(
+            Vote,
+            r#"SELECT user_id, post_id, value
+               FROM votes
+               WHERE user_id = $1
+               ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(votes)
+    }
+
 
 }
\ No newline at end of file
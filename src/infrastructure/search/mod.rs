@@ -0,0 +1,22 @@
+pub mod pg;
+pub mod tantivy;
+pub mod tsquery;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::SearchConfig;
+use crate::domain::posts::PostRepository;
+use crate::domain::search::SearchIndex;
+use crate::infrastructure::db::DbPool;
+use crate::infrastructure::repositories::posts_repo::PgPostRepository;
+
+pub fn from_config(cfg: &SearchConfig, pool: DbPool) -> anyhow::Result<Arc<dyn SearchIndex>> {
+    Ok(match cfg {
+        SearchConfig::Postgres => Arc::new(pg::PgSearchIndex { pool }),
+        SearchConfig::Tantivy { dir } => {
+            let posts: Arc<dyn PostRepository> = Arc::new(PgPostRepository { pool });
+            Arc::new(tantivy::TantivySearchIndex::open(Path::new(dir), posts)?)
+        }
+    })
+}
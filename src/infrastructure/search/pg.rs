@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::domain::posts::Post;
+use crate::domain::search::{FacetCount, SearchFacets, SearchHit, SearchIndex, SearchQuery, SearchResults};
+use crate::infrastructure::db::DbPool;
+use crate::infrastructure::search::tsquery;
+
+/// Searches the primary database through the generated `search_vector` columns, which
+/// Postgres keeps current by itself; the indexing hooks are no-ops.
+pub struct PgSearchIndex { pub pool: DbPool }
+
+/// Matching post ids from both GIN indexes: posts matching directly and posts with a matching comment.
+fn push_candidates(qb: &mut QueryBuilder<'_, Postgres>, tsquery: String, viewer: Option<Uuid>) {
+    qb.push("WITH q AS (SELECT to_tsquery('english', ");
+    qb.push_bind(tsquery);
+    qb.push(r#") AS query),
+        candidates AS (
+            SELECT p.id FROM posts p, q WHERE p.search_vector @@ q.query
+            UNION
+            SELECT c.post_id FROM comments c, q WHERE c.search_vector @@ q.query AND "#);
+    push_visible_comment(qb, viewer);
+    qb.push(")\n");
+}
+
+/// Comments anyone may see, and the viewer's own comments made under a shadowban.
+fn push_visible_comment(qb: &mut QueryBuilder<'_, Postgres>, viewer: Option<Uuid>) {
+    qb.push("c.deleted_at IS NULL AND c.removed_at IS NULL AND c.held_at IS NULL AND (c.user_id = ")
+        .push_bind(viewer)
+        .push("::uuid OR c.user_id NOT IN (SELECT user_id FROM active_sanctions WHERE kind = 'shadowban'))");
+}
+
+/// Posts in private communities are never searchable.
+fn push_filters(qb: &mut QueryBuilder<'_, Postgres>, query: &SearchQuery) {
+    qb.push(" AND p.deleted_at IS NULL AND p.removed_at IS NULL AND p.held_at IS NULL AND p.community_visibility <> 'private'");
+    if let Some(author) = &query.author {
+        qb.push(" AND u.username_normalized = ").push_bind(author.clone());
+    }
+    if let Some(domain) = &query.domain {
+        qb.push(" AND (p.domain = ").push_bind(domain.clone())
+            .push(" OR p.domain LIKE ").push_bind(format!("%.{domain}")).push(")");
+    }
+    if let Some(tag) = &query.tag {
+        qb.push(" AND EXISTS (SELECT 1 FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = p.id AND t.slug = ")
+            .push_bind(tag.clone()).push(")");
+    }
+    if !query.exclude.post_ids.is_empty() {
+        qb.push(" AND p.id <> ALL(").push_bind(query.exclude.post_ids.clone()).push(")");
+    }
+    if !query.exclude.authors.is_empty() {
+        qb.push(" AND u.username_normalized <> ALL(").push_bind(query.exclude.authors.clone()).push(")");
+    }
+    if !query.exclude.domains.is_empty() {
+        qb.push(" AND (p.domain IS NULL OR NOT EXISTS (SELECT 1 FROM unnest(").push_bind(query.exclude.domains.clone())
+            .push("::text[]) AS m(domain) WHERE p.domain = m.domain OR p.domain LIKE '%.' || m.domain))");
+    }
+    if let Some(from) = query.from {
+        qb.push(" AND p.created_at >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        qb.push(" AND p.created_at < ").push_bind(to);
+    }
+}
+
+#[async_trait]
+impl SearchIndex for PgSearchIndex {
+    async fn search(&self, query: &SearchQuery) -> anyhow::Result<SearchResults> {
+        let Some(tsquery) = tsquery::to_tsquery(&query.text) else { return Ok(SearchResults::default()) };
+        let headline_options = format!(
+            "StartSel={}, StopSel={}, MaxFragments=2, MaxWords=35, MinWords=15",
+            tsquery::HIGHLIGHT_START, tsquery::HIGHLIGHT_STOP
+        );
+
+        // rank = text relevance (a matching comment counts half) boosted by log(score) and up to 1.5x for fresh posts.
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new("");
+        push_candidates(&mut qb, tsquery.clone(), query.viewer);
+        qb.push(r#"SELECT p.*,
+                (GREATEST(ts_rank(ps.search_vector, q.query), COALESCE(bc.rank, 0) * 0.5)
+                    * (1 + 0.1 * ln(1 + GREATEST(p.score, 0)))
+                    * (1 + 0.5 * exp(-extract(epoch FROM NOW() - p.created_at) / 604800.0))
+                )::real AS rank,
+                CASE WHEN ps.search_vector @@ q.query
+                    THEN ts_headline('english', concat_ws(' ', p.title, NULLIF(p.short_description, ''), p.body), q.query, "#);
+        qb.push_bind(headline_options.clone());
+        qb.push(r#")
+                    ELSE ts_headline('english', bc.body, q.query, "#);
+        qb.push_bind(headline_options);
+        qb.push(r#")
+                END AS snippet
+            FROM candidates
+            JOIN post_view p ON p.id = candidates.id
+            JOIN posts ps ON ps.id = p.id
+            JOIN users u ON u.id = p.user_id
+            CROSS JOIN q
+            LEFT JOIN LATERAL (
+                SELECT c.body, ts_rank(c.search_vector, q.query) AS rank
+                FROM comments c
+                WHERE c.post_id = p.id AND c.search_vector @@ q.query AND "#);
+        push_visible_comment(&mut qb, query.viewer);
+        qb.push(r#"
+                ORDER BY rank DESC LIMIT 1
+            ) bc ON true
+            WHERE TRUE"#);
+        push_filters(&mut qb, query);
+        qb.push(" ORDER BY rank DESC, p.created_at DESC, p.id DESC LIMIT ").push_bind(query.limit);
+        qb.push(" OFFSET ").push_bind(query.offset);
+
+        let mut hits: Vec<SearchHit> = qb.build_query_as().fetch_all(&self.pool).await?;
+        for hit in &mut hits {
+            hit.snippet = tsquery::highlight_to_html(&hit.snippet);
+        }
+
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new("");
+        push_candidates(&mut qb, tsquery.clone(), query.viewer);
+        qb.push(r#"SELECT p.domain AS value, COUNT(*) AS count
+            FROM candidates
+            JOIN post_view p ON p.id = candidates.id
+            JOIN users u ON u.id = p.user_id
+            WHERE p.domain IS NOT NULL"#);
+        push_filters(&mut qb, query);
+        qb.push(" GROUP BY p.domain ORDER BY count DESC, value LIMIT 10");
+        let domains: Vec<FacetCount> = qb.build_query_as().fetch_all(&self.pool).await?;
+
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new("");
+        push_candidates(&mut qb, tsquery, query.viewer);
+        qb.push(r#"SELECT t.slug AS value, COUNT(*) AS count
+            FROM candidates
+            JOIN post_view p ON p.id = candidates.id
+            JOIN users u ON u.id = p.user_id
+            JOIN post_tags facet_pt ON facet_pt.post_id = p.id
+            JOIN tags t ON t.id = facet_pt.tag_id
+            WHERE TRUE"#);
+        push_filters(&mut qb, query);
+        qb.push(" GROUP BY t.slug ORDER BY count DESC, value LIMIT 10");
+        let tags: Vec<FacetCount> = qb.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(SearchResults { hits, facets: SearchFacets { domains, tags } })
+    }
+
+    async fn index_posts(&self, _posts: &[Post]) -> anyhow::Result<()> { Ok(()) }
+
+    async fn remove_post(&self, _post_id: Uuid) -> anyhow::Result<()> { Ok(()) }
+
+    async fn clear(&self) -> anyhow::Result<()> { Ok(()) }
+}
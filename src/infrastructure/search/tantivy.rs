@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tantivy::collector::{FacetCollector, FacetCounts, TopDocs};
+use tantivy::directory::error::LockError;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery, TermQuery};
+use tantivy::schema::{
+    Facet, FacetOptions, Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, FAST, INDEXED, STORED, STRING,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{DateTime as TantivyDateTime, Index, IndexReader, IndexWriter, ReloadPolicy, SegmentReader, TantivyDocument, TantivyError, Term};
+use uuid::Uuid;
+
+use crate::application::utils::identity;
+use crate::domain::posts::{Post, PostRepository};
+use crate::domain::search::{parse_terms, FacetCount, QueryTerm, SearchFacets, SearchHit, SearchIndex, SearchQuery, SearchResults};
+
+const WRITER_HEAP_BYTES: usize = 50_000_000;
+const TITLE_BOOST: f32 = 2.0;
+const SNIPPET_CHARS: usize = 240;
+const FACET_LIMIT: usize = 10;
+
+#[derive(Clone, Copy)]
+struct Fields {
+    id: Field,
+    title: Field,
+    body: Field,
+    author: Field,
+    /// Domain labels reversed (`/com/example/blog`) so a filter on a domain also matches its subdomains.
+    site: Field,
+    /// The domain as a single label, counted for the facet list.
+    domain: Field,
+    /// One `/slug` facet per tag.
+    tags: Field,
+    created_at: Field,
+    score: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+    let text = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("en_stem")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_text_field("id", STRING | STORED),
+        title: builder.add_text_field("title", text.clone()),
+        body: builder.add_text_field("body", text),
+        author: builder.add_text_field("author", STRING),
+        site: builder.add_facet_field("site", FacetOptions::default()),
+        domain: builder.add_facet_field("domain", FacetOptions::default()),
+        tags: builder.add_facet_field("tags", FacetOptions::default()),
+        created_at: builder.add_date_field("created_at", INDEXED | FAST),
+        score: builder.add_i64_field("score", FAST),
+    };
+    (builder.build(), fields)
+}
+
+struct Inner {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+/// Embedded index stored on local disk. Only posts are indexed (not comments); hits are
+/// hydrated from the database so scores and authors are never stale in responses.
+/// Only one process can hold the index open: a running server reindexes through
+/// `POST /api/admin/search/reindex`.
+pub struct TantivySearchIndex {
+    inner: Arc<Inner>,
+    posts: Arc<dyn PostRepository>,
+}
+
+impl TantivySearchIndex {
+    pub fn open(dir: &Path, posts: Arc<dyn PostRepository>) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let (schema, fields) = schema();
+        let index = Index::open_or_create(MmapDirectory::open(dir)?, schema)?;
+        let reader = index.reader_builder().reload_policy(ReloadPolicy::OnCommitWithDelay).try_into()?;
+        let writer = index.writer(WRITER_HEAP_BYTES).map_err(|e| match e {
+            TantivyError::LockFailure(LockError::LockBusy, _) => anyhow::anyhow!(
+                "search index {} is in use by another process; while the server runs, reindex with POST /api/admin/search/reindex",
+                dir.display()
+            ),
+            e => e.into(),
+        })?;
+        let writer = Mutex::new(writer);
+        Ok(Self { inner: Arc::new(Inner { index, reader, writer, fields }), posts })
+    }
+
+    async fn write<F>(&self, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut IndexWriter, &Fields) -> anyhow::Result<()> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut writer = inner.writer.lock().map_err(|_| anyhow::anyhow!("search index writer poisoned"))?;
+            f(&mut writer, &inner.fields)?;
+            writer.commit()?;
+            inner.reader.reload()?;
+            Ok(())
+        }).await?
+    }
+}
+
+#[async_trait]
+impl SearchIndex for TantivySearchIndex {
+    async fn search(&self, query: &SearchQuery) -> anyhow::Result<SearchResults> {
+        let inner = self.inner.clone();
+        let query = query.clone();
+        let found = tokio::task::spawn_blocking(move || inner.search(&query)).await??;
+        let Some((ranked, facets)) = found else { return Ok(SearchResults::default()) };
+
+        let ids: Vec<Uuid> = ranked.iter().map(|r| r.id).collect();
+        let mut posts: HashMap<Uuid, Post> = self.posts.find_by_ids(&ids).await?
+            .into_iter().map(|p| (p.id, p)).collect();
+
+        // posts deleted since they were indexed simply drop out of the page
+        let hits = ranked.into_iter()
+            .filter_map(|r| posts.remove(&r.id).map(|post| SearchHit { post, rank: r.rank, snippet: r.snippet }))
+            .collect();
+        Ok(SearchResults { hits, facets })
+    }
+
+    async fn index_posts(&self, posts: &[Post]) -> anyhow::Result<()> {
+        let posts = posts.to_vec();
+        self.write(move |writer, fields| {
+            for post in &posts {
+                writer.delete_term(Term::from_field_text(fields.id, &post.id.to_string()));
+                writer.add_document(document(fields, post))?;
+            }
+            Ok(())
+        }).await
+    }
+
+    async fn remove_post(&self, post_id: Uuid) -> anyhow::Result<()> {
+        self.write(move |writer, fields| {
+            writer.delete_term(Term::from_field_text(fields.id, &post_id.to_string()));
+            Ok(())
+        }).await
+    }
+
+    async fn clear(&self) -> anyhow::Result<()> {
+        self.write(|writer, _| {
+            writer.delete_all_documents()?;
+            Ok(())
+        }).await
+    }
+}
+
+fn document(fields: &Fields, post: &Post) -> TantivyDocument {
+    let mut doc = TantivyDocument::default();
+    doc.add_text(fields.id, post.id.to_string());
+    doc.add_text(fields.title, &post.title);
+    let body = [post.short_description.as_deref(), post.body.as_deref()]
+        .into_iter().flatten().collect::<Vec<_>>().join("\n");
+    doc.add_text(fields.body, body);
+    doc.add_text(fields.author, identity::fold(&post.author_username));
+    if let Some(domain) = post.url.as_deref().and_then(domain_of) {
+        doc.add_facet(fields.site, reversed_domain(&domain));
+        doc.add_facet(fields.domain, Facet::from_path([domain.as_str()]));
+    }
+    for tag in &post.tags {
+        doc.add_facet(fields.tags, Facet::from_path([tag.as_str()]));
+    }
+    doc.add_date(fields.created_at, TantivyDateTime::from_timestamp_micros(post.created_at.timestamp_micros()));
+    doc.add_i64(fields.score, post.score as i64);
+    doc
+}
+
+/// Same normalization as the `posts.domain` column: lowercase host without a leading `www.`.
+fn domain_of(url: &str) -> Option<String> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn reversed_domain(domain: &str) -> Facet {
+    Facet::from_path(domain.rsplit('.'))
+}
+
+struct RankedDoc {
+    id: Uuid,
+    rank: f32,
+    snippet: String,
+}
+
+impl Inner {
+    fn search(&self, query: &SearchQuery) -> anyhow::Result<Option<(Vec<RankedDoc>, SearchFacets)>> {
+        let Some(text_query) = self.text_query(&query.text)? else { return Ok(None) };
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text_query.box_clone())];
+        if let Some(author) = &query.author {
+            clauses.push((Occur::Must, Box::new(TermQuery::new(
+                Term::from_field_text(self.fields.author, author),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        if let Some(domain) = &query.domain {
+            clauses.push((Occur::Must, Box::new(TermQuery::new(
+                Term::from_facet(self.fields.site, &reversed_domain(domain)),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        if let Some(tag) = &query.tag {
+            clauses.push((Occur::Must, Box::new(TermQuery::new(
+                Term::from_facet(self.fields.tags, &Facet::from_path([tag.as_str()])),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        for post_id in &query.exclude.post_ids {
+            clauses.push((Occur::MustNot, Box::new(TermQuery::new(
+                Term::from_field_text(self.fields.id, &post_id.to_string()),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        for author in &query.exclude.authors {
+            clauses.push((Occur::MustNot, Box::new(TermQuery::new(
+                Term::from_field_text(self.fields.author, author),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        for domain in &query.exclude.domains {
+            clauses.push((Occur::MustNot, Box::new(TermQuery::new(
+                Term::from_facet(self.fields.site, &reversed_domain(domain)),
+                IndexRecordOption::Basic,
+            ))));
+        }
+        if query.from.is_some() || query.to.is_some() {
+            let to_tantivy = |d: chrono::DateTime<Utc>| TantivyDateTime::from_timestamp_micros(d.timestamp_micros());
+            let lower = query.from.map_or(Bound::Unbounded, |d| Bound::Included(to_tantivy(d)));
+            let upper = query.to.map_or(Bound::Unbounded, |d| Bound::Excluded(to_tantivy(d)));
+            clauses.push((Occur::Must, Box::new(RangeQuery::new_date_bounds("created_at".to_string(), lower, upper))));
+        }
+        let full_query = BooleanQuery::new(clauses);
+
+        let searcher = self.reader.searcher();
+        let now = Utc::now().timestamp();
+        // same shape as the Postgres ranking: relevance boosted by log(score) and up to 1.5x for fresh posts
+        let top_docs = TopDocs::with_limit(query.limit as usize)
+            .and_offset(query.offset as usize)
+            .tweak_score(move |segment: &SegmentReader| {
+                let scores = segment.fast_fields().i64("score").ok();
+                let created = segment.fast_fields().date("created_at").ok();
+                move |doc, relevance| {
+                    let score = scores.as_ref().and_then(|c| c.first(doc)).unwrap_or(0).max(0) as f32;
+                    let age = created.as_ref().and_then(|c| c.first(doc))
+                        .map_or(f32::MAX, |d| (now - d.into_timestamp_secs()) as f32);
+                    relevance * (1.0 + 0.1 * (1.0 + score).ln()) * (1.0 + 0.5 * (-age / 604_800.0).exp())
+                }
+            });
+        let mut domain_facets = FacetCollector::for_field("domain");
+        domain_facets.add_facet(Facet::root());
+        let mut tag_facets = FacetCollector::for_field("tags");
+        tag_facets.add_facet(Facet::root());
+
+        let (top, domain_counts, tag_counts) = searcher.search(&full_query, &(top_docs, domain_facets, tag_facets))?;
+
+        let title_snippets = SnippetGenerator::create(&searcher, &*text_query, self.fields.title)?;
+        let mut body_snippets = SnippetGenerator::create(&searcher, &*text_query, self.fields.body)?;
+        body_snippets.set_max_num_chars(SNIPPET_CHARS);
+
+        let mut ranked = Vec::with_capacity(top.len());
+        for (rank, address) in top {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let Some(id) = stored_id(&doc, self.fields.id) else { continue };
+            let snippet = snippet_html(&body_snippets, &doc)
+                .or_else(|| snippet_html(&title_snippets, &doc))
+                .unwrap_or_default();
+            ranked.push(RankedDoc { id, rank, snippet });
+        }
+
+        Ok(Some((ranked, SearchFacets { domains: top_facets(&domain_counts), tags: top_facets(&tag_counts) })))
+    }
+
+    /// Every positive term must match the title or the body; typos are tolerated on plain words.
+    fn text_query(&self, raw: &str) -> anyhow::Result<Option<Box<dyn Query>>> {
+        let terms = parse_terms(raw);
+        if terms.is_empty() {
+            return Ok(None);
+        }
+        let mut analyzer = self.index.tokenizer_for_field(self.fields.body)?;
+        let mut stems = |text: &str| {
+            let mut out = Vec::new();
+            let mut stream = analyzer.token_stream(text);
+            while stream.advance() {
+                out.push(stream.token().text.clone());
+            }
+            out
+        };
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for term in terms {
+            match term {
+                QueryTerm::Word(word) => {
+                    let distance = match word.chars().count() {
+                        0..=3 => 0,
+                        4..=7 => 1,
+                        _ => 2,
+                    };
+                    let per_field = stems(&word).into_iter().map(|stem| {
+                        self.across_fields(|field| {
+                            let term = Term::from_field_text(field, &stem);
+                            // exact matches keep their BM25 score; fuzzy ones only widen the net
+                            let exact: Box<dyn Query> = Box::new(TermQuery::new(term.clone(), IndexRecordOption::WithFreqs));
+                            if distance == 0 {
+                                return exact;
+                            }
+                            let fuzzy: Box<dyn Query> = Box::new(FuzzyTermQuery::new(term, distance, true));
+                            Box::new(BooleanQuery::new(vec![(Occur::Should, exact), (Occur::Should, fuzzy)]))
+                        })
+                    });
+                    clauses.extend(per_field.map(|q| (Occur::Must, q)));
+                }
+                QueryTerm::Prefix(prefix) => {
+                    clauses.push((Occur::Must, self.across_fields(|field| {
+                        Box::new(FuzzyTermQuery::new_prefix(Term::from_field_text(field, &prefix), 0, false))
+                    })));
+                }
+                QueryTerm::Phrase(words) => {
+                    let tokens = stems(&words.join(" "));
+                    if tokens.is_empty() {
+                        continue;
+                    }
+                    clauses.push((Occur::Must, self.across_fields(|field| {
+                        let terms: Vec<Term> = tokens.iter().map(|t| Term::from_field_text(field, t)).collect();
+                        if terms.len() == 1 {
+                            Box::new(TermQuery::new(terms[0].clone(), IndexRecordOption::WithFreqs))
+                        } else {
+                            Box::new(PhraseQuery::new(terms))
+                        }
+                    })));
+                }
+                QueryTerm::Exclude(word) => {
+                    for stem in stems(&word) {
+                        clauses.push((Occur::MustNot, self.across_fields(|field| {
+                            Box::new(TermQuery::new(Term::from_field_text(field, &stem), IndexRecordOption::Basic))
+                        })));
+                    }
+                }
+            }
+        }
+
+        if !clauses.iter().any(|(occur, _)| *occur == Occur::Must) {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(BooleanQuery::new(clauses))))
+    }
+
+    fn across_fields(&self, build: impl Fn(Field) -> Box<dyn Query>) -> Box<dyn Query> {
+        Box::new(BooleanQuery::new(vec![
+            (Occur::Should, Box::new(BoostQuery::new(build(self.fields.title), TITLE_BOOST))),
+            (Occur::Should, build(self.fields.body)),
+        ]))
+    }
+}
+
+fn top_facets(counts: &FacetCounts) -> Vec<FacetCount> {
+    counts.top_k(Facet::root(), FACET_LIMIT).into_iter()
+        .filter_map(|(facet, count)| {
+            let value = facet.to_path().last()?.to_string();
+            Some(FacetCount { value, count: count as i64 })
+        })
+        .collect()
+}
+
+fn stored_id(doc: &TantivyDocument, field: Field) -> Option<Uuid> {
+    doc.get_first(field).and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn snippet_html(generator: &SnippetGenerator, doc: &TantivyDocument) -> Option<String> {
+    let mut snippet = generator.snippet_from_doc(doc);
+    if snippet.highlighted().is_empty() {
+        return None;
+    }
+    snippet.set_snippet_prefix_postfix("<mark>", "</mark>");
+    Some(snippet.to_html())
+}
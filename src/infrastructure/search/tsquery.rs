@@ -0,0 +1,39 @@
+use crate::domain::search::{parse_terms, QueryTerm};
+
+/// Renders a user's search query in `to_tsquery` syntax. Terms are already reduced to
+/// letters and digits by `parse_terms`, so the output can't carry operators the user didn't ask for.
+pub fn to_tsquery(raw: &str) -> Option<String> {
+    let terms: Vec<String> = parse_terms(raw)
+        .into_iter()
+        .map(|term| match term {
+            QueryTerm::Word(w) => w,
+            QueryTerm::Prefix(w) => format!("{w}:*"),
+            QueryTerm::Phrase(words) => format!("({})", words.join(" <-> ")),
+            QueryTerm::Exclude(w) => format!("!{w}"),
+        })
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+/// `ts_headline` marks matches with these control characters so the surrounding user text
+/// can be escaped before the markers become `<mark>` tags.
+pub const HIGHLIGHT_START: char = '\u{2}';
+pub const HIGHLIGHT_STOP: char = '\u{3}';
+
+pub fn highlight_to_html(snippet: &str) -> String {
+    let mut out = String::with_capacity(snippet.len());
+    for c in snippet.chars() {
+        match c {
+            HIGHLIGHT_START => out.push_str("<mark>"),
+            HIGHLIGHT_STOP => out.push_str("</mark>"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
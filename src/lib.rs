@@ -5,7 +5,14 @@ pub mod infrastructure;
 pub mod application;
 pub mod presentation;
 
+use std::sync::Arc;
+
 use crate::app::build_router;
+use crate::config::{ClientIpSource, RevisionConfig, SpamConfig};
+use crate::domain::blobs::BlobStore;
+use crate::domain::content_filter::ContentFilter;
+use crate::domain::previews::PageFetcher;
+use crate::domain::search::SearchIndex;
 use axum::Router;
 use sqlx::{Pool, Postgres};
 
@@ -13,6 +20,17 @@ use sqlx::{Pool, Postgres};
 pub struct AppContext {
 pub pool: Pool<Postgres>,
     pub jwt_secret: String,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub search_index: Arc<dyn SearchIndex>,
+    /// `None` when link previews are turned off.
+    pub page_fetcher: Option<Arc<dyn PageFetcher>>,
+    pub client_ip_source: ClientIpSource,
+    pub public_mod_log: bool,
+    pub content_filter: Arc<dyn ContentFilter>,
+    pub spam: SpamConfig,
+    /// `None` when posts are never archived.
+    pub archive_after_days: Option<i64>,
+    pub revisions: RevisionConfig,
 }
 
 pub async fn build_app(ctx: AppContext) -> Router {
@@ -1,5 +1,15 @@
+use std::sync::Arc;
 use lotus_news_service::{build_app, AppContext};
 use lotus_news_service::config::Config;
+use lotus_news_service::application::search_service::SearchService;
+use lotus_news_service::application::{domain_service, user_service};
+use lotus_news_service::infrastructure::auth::JwtKeys;
+use lotus_news_service::infrastructure::{blob_store, content_filter, page_fetcher, search};
+use lotus_news_service::infrastructure::repositories::domain_repo::PgDomainRepository;
+use lotus_news_service::infrastructure::repositories::mute_repo::PgMuteRepository;
+use lotus_news_service::infrastructure::repositories::posts_repo::PgPostRepository;
+use lotus_news_service::infrastructure::repositories::tag_repo::PgTagRepository;
+use lotus_news_service::infrastructure::repositories::user_repo::PgUserRepository;
 
 use axum::{extract::{FromRequestParts, Path, State}, http::{header, request::Parts, StatusCode}, Json};
 use jsonwebtoken::{decode, Algorithm, DecodingKey, EncodingKey, Validation};
@@ -127,13 +137,6 @@ struct VotePayload {
     value: i16, // must be 1 or -1
 }
 
-#[derive(Debug, Deserialize, Validate)]
-struct CreateCommentPayload {
-    parent_id: Option<Uuid>,
-    #[validate(length(min = 1, max = 10_000))]
-    body: String
-}
-
 #[derive(Debug, Serialize, sqlx::FromRow, Clone)]
 struct CommentRow {
     id: Uuid,
@@ -183,18 +186,6 @@ fn validate_optional_url(v: &str) -> Result<(), validator::ValidationError> {
     Ok(())
 }
 
-// toy profanity filter (extend as needed)
-fn censor(s: &str) -> String {
-    // lower-effort example list
-    const BAD: &[&str] = &["damn", "hell"];
-    let mut out = s.to_string();
-    for &w in BAD {
-        let mask = "*".repeat(w.len());
-        out = out.replace(w, &mask).replace(&w.to_ascii_uppercase(), &mask);
-    }
-    out
-}
-
 // ---------------------------- Error helper ----------------------------------
 fn http_err(status: StatusCode, msg: &str) -> (StatusCode, Json<ApiError>) {
     (status, Json(ApiError { error: msg.to_string() }))
@@ -257,15 +248,46 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("Failed to run database migrations");
 
+    // Skeletons are computed by the app, so the migrations that need them leave them pending
+    let users = PgUserRepository { pool: pool.clone(), jwt: JwtKeys::new(&cfg.jwt_secret) };
+    let recomputed = user_service::recompute_skeletons(&users).await?;
+    if recomputed > 0 {
+        tracing::info!(names = recomputed, "recomputed username skeletons");
+    }
+
+    let seeded = domain_service::seed_banned_domains(&PgDomainRepository { pool: pool.clone() }, &cfg.spam.banned_domains).await?;
+    if seeded > 0 {
+        tracing::info!(domains = seeded, "added domain rules from SPAM_BANNED_DOMAINS");
+    }
+
     // Optional: run migrations in-process (simple files loader)
     // db::apply_sql_folder(&pool, "migrations").await?;
 
-    let ctx = AppContext { pool, jwt_secret: cfg.jwt_secret.clone() };
+    let blob_store = blob_store::from_config(&cfg.blob_store).await?;
+
+    let search_index = search::from_config(&cfg.search, pool.clone())?;
+
+    // `lotus_news_service reindex` rebuilds the search index from the posts table and exits;
+    // with the tantivy backend it only works while the server is stopped
+    if std::env::args().nth(1).as_deref() == Some("reindex") {
+        let posts = Arc::new(PgPostRepository { pool: pool.clone() });
+        let mutes = Arc::new(PgMuteRepository { pool: pool.clone() });
+        let tags = Arc::new(PgTagRepository { pool: pool.clone() });
+        let indexed = SearchService::new(search_index, posts, mutes, tags).reindex().await?;
+        tracing::info!(posts = indexed, "search index rebuilt");
+        return Ok(());
+    }
+
+    let page_fetcher = page_fetcher::from_config(&cfg.link_previews)?;
+
+    let content_filter = content_filter::from_config(cfg.content_filter_dictionary.as_deref())?;
+
+    let ctx = AppContext { pool, jwt_secret: cfg.jwt_secret.clone(), blob_store, search_index, page_fetcher, client_ip_source: cfg.client_ip_source, public_mod_log: cfg.public_mod_log, content_filter, spam: cfg.spam.clone(), archive_after_days: cfg.archive_after_days, revisions: cfg.revisions };
     let app = build_app(ctx).await;
 
     tracing::info!("listening on {}", cfg.bind_addr);
     let listener = tokio::net::TcpListener::bind(&cfg.bind_addr).await?;
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
 
     Ok(())
 
@@ -302,7 +324,6 @@ async fn main() -> anyhow::Result<()> {
     //     .route("/posts", get(list_posts))
     //     .route("/posts", post(create_post))
 
-    //     .route("/posts/{id}/comments", post(create_comment))
     //     .route("/posts/{id}/comments", get(list_comments))
     //     .route("/posts/{id}/vote", post(vote_post))
         
@@ -321,44 +342,6 @@ async fn main() -> anyhow::Result<()> {
     // Ok(())
 }
 
-// Create COMMNET (atuh)
-async fn create_comment(
-    State(state): State<AppState>,
-    AuthUser { user_id}: AuthUser,
-    Path(post_id): Path<Uuid>,
-    Json(payload): Json<CreateCommentPayload>,
-) -> std::result::Result<Json<CommentRow>, (StatusCode, Json<ApiError>)> {
-    if let Err(e) = payload.validate() {
-        return Err(http_err(StatusCode::BAD_REQUEST, &e.to_string()));
-    }
-
-    // ensure parent belongs to same post if provided 
-    if let Some(pid) = payload.parent_id {
-        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(1) FROM comments WHERE id=$1 AND post_id=$2")
-            .bind(pid).bind(post_id)
-            .fetch_one(&state.db).await
-            .map_err(|e| { error!(?e, "parent check failed"); http_err(StatusCode::INTERNAL_SERVER_ERROR, "internal error")})?;
-        
-        if exists == 0 { return Err(http_err(StatusCode::BAD_REQUEST, "parent comment not found in this post"));}
-    }
-
-    let id = Uuid::new_v4();
-    let body = censor(&payload.body);
-
-    let q = r#"
-        INSERT INTO comments (id, post_id, user_id, parent_id, body)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, post_id, user_id, parent_id, body, created_at
-    "#;
-
-    let row = sqlx::query_as::<_, CommentRow>(q)
-    .bind(id).bind(post_id).bind(user_id).bind(payload.parent_id).bind(body)
-    .fetch_one(&state.db).await
-    .map_err(|e| { error!(?e, "create_comment failed"); http_err(StatusCode::INTERNAL_SERVER_ERROR, "internal error")})?;
-
-    Ok(Json(row))
-}
-
 // GET COMMENTS (nested tree)
 async fn list_comments(
     State(state): State<AppState>,
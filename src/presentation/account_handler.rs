@@ -0,0 +1,51 @@
+use axum::{
+    extract::State, http::{header, StatusCode}, response::IntoResponse, Json
+};
+use serde::Deserialize;
+use crate::application::account_service::DeletionScheduled;
+use crate::presentation::{auth::AuthUser, error::app_error, ApiState};
+
+#[derive(Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+}
+
+pub async fn delete_me(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<DeleteAccountRequest>,
+) -> Result<(StatusCode, Json<DeletionScheduled>), (StatusCode, String)> {
+    let scheduled = state.account_service.request_deletion(user_id, &payload.password)
+        .await
+        .map_err(app_error)?;
+
+    Ok((StatusCode::ACCEPTED, Json(scheduled)))
+}
+
+pub async fn cancel_deletion(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.account_service.cancel_deletion(user_id)
+        .await
+        .map_err(app_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn export_me(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let archive = state.account_service.export(user_id)
+        .await
+        .map_err(app_error)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"lotus-export.zip\""),
+        ],
+        archive,
+    ))
+}
@@ -1,8 +1,9 @@
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
 use axum::{
-    extract::{FromRef, FromRequestParts},
-    http::{request::Parts, StatusCode},
+    extract::{ConnectInfo, FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
 };
 
 use axum_extra::TypedHeader;
@@ -12,6 +13,8 @@ use headers::authorization::Bearer;
 use uuid::Uuid;
 
 use crate::application::user_service::UserService;
+use crate::config::ClientIpSource;
+use crate::presentation::error::app_error;
 
 pub struct AuthUser {
     pub user_id: Uuid,
@@ -27,7 +30,7 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         // Extract Authorization: Bearer <token>
         let TypedHeader(Authorization(bearer)) =
-            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+            <TypedHeader<Authorization<Bearer>> as FromRequestParts<S>>::from_request_parts(parts, state)
                 .await
                 .map_err(|_| (StatusCode::UNAUTHORIZED, "Missing token".into()))?;
 
@@ -41,6 +44,95 @@ where
             .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".into()))?
             .ok_or((StatusCode::UNAUTHORIZED, "Invalid token".into()))?;
 
+        // bans and suspensions are enforced here so no write path can forget them
+        user_service.check_standing(user_id, !parts.method.is_safe())
+            .await
+            .map_err(app_error)?;
+
         Ok(AuthUser { user_id })
     }
 }
+
+/// `Option<AuthUser>` for endpoints that also serve anonymous visitors. A token that is
+/// present but invalid is still rejected rather than silently ignored.
+impl<S> OptionalFromRequestParts<S> for AuthUser
+where
+    Arc<UserService>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(None);
+        }
+        <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state).await.map(Some)
+    }
+}
+
+/// An authenticated user holding the moderator or admin role.
+pub struct ModeratorUser {
+    pub user_id: Uuid,
+}
+
+impl<S> FromRequestParts<S> for ModeratorUser
+where
+    Arc<UserService>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthUser { user_id } = <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+
+        let user_service: Arc<UserService> = FromRef::from_ref(state);
+        user_service.require_moderator(user_id).await.map_err(app_error)?;
+
+        Ok(ModeratorUser { user_id })
+    }
+}
+
+/// An authenticated user holding the admin role.
+pub struct AdminUser {
+    pub user_id: Uuid,
+}
+
+impl<S> FromRequestParts<S> for AdminUser
+where
+    Arc<UserService>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthUser { user_id } = <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+
+        let user_service: Arc<UserService> = FromRef::from_ref(state);
+        user_service.require_admin(user_id).await.map_err(app_error)?;
+
+        Ok(AdminUser { user_id })
+    }
+}
+
+/// The client's address as configured by `CLIENT_IP_SOURCE`; `None` when it can't be told.
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    ClientIpSource: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ip = match ClientIpSource::from_ref(state) {
+            ClientIpSource::Peer => parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip()),
+            ClientIpSource::XForwardedFor => parts.headers.get_all("x-forwarded-for").iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .last()
+                .and_then(|ip| ip.trim().parse().ok()),
+        };
+        Ok(ClientIp(ip))
+    }
+}
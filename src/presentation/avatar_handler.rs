@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Multipart, Path, State}, http::{header, StatusCode}, response::IntoResponse, Json
+};
+use uuid::Uuid;
+use crate::application::avatar_service::AvatarUrls;
+use crate::presentation::{auth::AuthUser, error::app_error, ApiState};
+
+/// Expects a multipart form with the image in a field named `avatar`.
+pub async fn upload_avatar(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    mut multipart: Multipart,
+) -> Result<Json<AvatarUrls>, (StatusCode, String)> {
+    let bad_request = |msg: &str| (StatusCode::BAD_REQUEST, msg.to_string());
+
+    let mut image = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| bad_request(&e.body_text()))? {
+        if field.name() == Some("avatar") {
+            let bytes = field.bytes().await.map_err(|e| bad_request(&e.body_text()))?;
+            image = Some(bytes.to_vec());
+            break;
+        }
+    }
+    let image = image.ok_or_else(|| bad_request("missing avatar field"))?;
+
+    let urls = state.avatar_service.upload(user_id, image)
+        .await
+        .map_err(app_error)?;
+
+    Ok(Json(urls))
+}
+
+pub async fn get_avatar(
+    State(state): State<ApiState>,
+    Path((user_id, version, file)): Path<(Uuid, Uuid, String)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let blob = state.avatar_service.get(user_id, version, &file)
+        .await
+        .map_err(app_error)?;
+
+    // every upload gets a new version in the path, so a stored avatar never changes
+    Ok((
+        [
+            (header::CONTENT_TYPE, blob.content_type),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable".to_string()),
+        ],
+        blob.bytes,
+    ))
+}
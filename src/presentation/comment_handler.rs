@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Path, State}, http::StatusCode, Json
+};
+use uuid::Uuid;
+use crate::application::comment_service::{CommentNode, CreateCommentInput, UpdateCommentInput};
+use crate::domain::comments::Comment;
+use crate::presentation::{auth::AuthUser, error::app_error, ApiState};
+
+pub async fn create_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    Json(payload): Json<CreateCommentInput>,
+) -> Result<(StatusCode, Json<Comment>), (StatusCode, String)> {
+    let comment = state.comment_service.create(user_id, post_id, payload)
+        .await
+        .map_err(app_error)?;
+
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+pub async fn list_comments(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(post_id): Path<Uuid>,
+) -> Result<Json<Vec<CommentNode>>, (StatusCode, String)> {
+    let thread = state.comment_service.thread(viewer.map(|v| v.user_id), post_id)
+        .await
+        .map_err(app_error)?;
+
+    Ok(Json(thread))
+}
+
+pub async fn update_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+    Json(payload): Json<UpdateCommentInput>,
+) -> Result<Json<Comment>, (StatusCode, String)> {
+    let comment = state.comment_service.update(user_id, comment_id, payload)
+        .await
+        .map_err(app_error)?;
+
+    Ok(Json(comment))
+}
+
+pub async fn delete_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.comment_service.delete(user_id, comment_id)
+        .await
+        .map_err(app_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State}, http::StatusCode, Json
+};
+use serde::Deserialize;
+
+use crate::application::community_service::{CreateCommunityInput, UpdateCommunityInput};
+use crate::domain::communities::{Community, MemberRole};
+use crate::domain::posts::FeedCursor;
+use crate::presentation::post_handler::ListPostQuery;
+use crate::presentation::{auth::AuthUser, error::app_error, pagination, ApiState};
+
+#[derive(Deserialize)]
+pub struct SetMemberRequest {
+    pub role: MemberRole,
+}
+
+pub async fn create_community(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<CreateCommunityInput>,
+) -> Result<(StatusCode, Json<Community>), (StatusCode, String)> {
+    let community = state.community_service.create(user_id, payload).await.map_err(app_error)?;
+    Ok((StatusCode::CREATED, Json(community)))
+}
+
+pub async fn get_community(
+    State(state): State<ApiState>,
+    Path(slug): Path<String>,
+) -> Result<Json<Community>, (StatusCode, String)> {
+    let community = state.community_service.find(&slug).await.map_err(app_error)?;
+    Ok(Json(community))
+}
+
+pub async fn update_community(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(slug): Path<String>,
+    Json(payload): Json<UpdateCommunityInput>,
+) -> Result<Json<Community>, (StatusCode, String)> {
+    let community = state.community_service.update(user_id, &slug, payload).await.map_err(app_error)?;
+    Ok(Json(community))
+}
+
+pub async fn list_community_posts(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(slug): Path<String>,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let community = state.community_service.find(&slug).await.map_err(app_error)?;
+    let viewer = viewer.map(|v| v.user_id);
+    let posts = state.post_service.list_by_community(viewer, &community, query.sort, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_feed_cursor(&posts, limit, |p| FeedCursor::at(query.sort, p.score.into(), p.created_at, p.id));
+    let posts = state.post_service.annotate(viewer, posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "posts": posts, "next_cursor": next_cursor })))
+}
+
+pub async fn join(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(slug): Path<String>,
+) -> Result<Json<Community>, (StatusCode, String)> {
+    let community = state.community_service.join(user_id, &slug).await.map_err(app_error)?;
+    Ok(Json(community))
+}
+
+pub async fn leave(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.community_service.leave(user_id, &slug).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn set_member(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path((slug, username)): Path<(String, String)>,
+    Json(payload): Json<SetMemberRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let member = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.community_service.set_member_role(user_id, &slug, member.id, payload.role).await
+        .map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_member(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path((slug, username)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let member = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.community_service.remove_member(user_id, &slug, member.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_my_communities(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<Json<Vec<Community>>, (StatusCode, String)> {
+    let communities = state.community_service.joined(user_id).await.map_err(app_error)?;
+    Ok(Json(communities))
+}
@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, Query, State}, http::StatusCode, Json
+};
+
+use crate::application::domain_service::DomainRuleInput;
+use crate::domain::domains::{DomainRule, DomainStats};
+use crate::domain::posts::FeedCursor;
+use crate::presentation::post_handler::ListPostQuery;
+use crate::presentation::{auth::{AdminUser, AuthUser}, error::app_error, pagination, ApiState};
+
+pub async fn list_domain_posts(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(domain): Path<String>,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let posts = state.domain_service.posts(&domain, viewer.as_ref().map(|v| v.user_id), query.sort, after, limit).await.map_err(app_error)?;
+
+    let next_cursor = pagination::next_feed_cursor(&posts, limit, |p| FeedCursor::at(query.sort, p.score.into(), p.created_at, p.id));
+    let posts = state.post_service.annotate(viewer.map(|v| v.user_id), posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "domain": domain, "posts": posts, "next_cursor": next_cursor })))
+}
+
+pub async fn domain_stats(
+    State(state): State<ApiState>,
+    Path(domain): Path<String>,
+) -> Result<Json<DomainStats>, (StatusCode, String)> {
+    let stats = state.domain_service.stats(&domain).await.map_err(app_error)?;
+    Ok(Json(stats))
+}
+
+pub async fn list_rules(
+    State(state): State<ApiState>,
+    AdminUser { .. }: AdminUser,
+) -> Result<Json<Vec<DomainRule>>, (StatusCode, String)> {
+    let rules = state.domain_service.rules().await.map_err(app_error)?;
+    Ok(Json(rules))
+}
+
+pub async fn set_rule(
+    State(state): State<ApiState>,
+    AdminUser { user_id }: AdminUser,
+    Path(domain): Path<String>,
+    Json(payload): Json<DomainRuleInput>,
+) -> Result<Json<DomainRule>, (StatusCode, String)> {
+    let rule = state.domain_service.set_rule(user_id, &domain, payload).await.map_err(app_error)?;
+    Ok(Json(rule))
+}
+
+pub async fn delete_rule(
+    State(state): State<ApiState>,
+    AdminUser { .. }: AdminUser,
+    Path(domain): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.domain_service.remove_rule(&domain).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
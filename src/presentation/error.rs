@@ -0,0 +1,22 @@
+use axum::http::StatusCode;
+use tracing::error;
+
+use crate::application::error::AppError;
+
+/// Maps service errors onto the `(StatusCode, String)` rejection used by all handlers.
+pub fn app_error(e: AppError) -> (StatusCode, String) {
+    let status = match &e {
+        AppError::Validation(_) => StatusCode::BAD_REQUEST,
+        AppError::Conflict(_) => StatusCode::CONFLICT,
+        AppError::NotFound(_) => StatusCode::NOT_FOUND,
+        AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+        AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+        AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+        AppError::Other(err) => {
+            error!(error = ?err, "request failed");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "internal error".to_string());
+        }
+    };
+    (status, e.to_string())
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Path, State}, http::StatusCode, Json
+};
+
+use crate::application::follow_service::Following;
+use crate::presentation::{auth::AuthUser, error::app_error, ApiState};
+
+pub async fn follow_user(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let followee = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.follow_service.follow_user(user_id, followee.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unfollow_user(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let followee = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.follow_service.unfollow_user(user_id, followee.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn follow_tag(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let tag = state.tag_service.find(&slug).await.map_err(app_error)?;
+    state.follow_service.follow_tag(user_id, tag.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unfollow_tag(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let tag = state.tag_service.find(&slug).await.map_err(app_error)?;
+    state.follow_service.unfollow_tag(user_id, tag.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_following(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<Json<Following>, (StatusCode, String)> {
+    let following = state.follow_service.following(user_id).await.map_err(app_error)?;
+    Ok(Json(following))
+}
@@ -1,22 +1,66 @@
 use std::sync::Arc;
-use axum::routing::{delete, post, put};
+use std::time::Duration;
+use axum::routing::{delete, patch, post, put};
 use axum::{routing::get, Router};
-use axum::extract::FromRef;
+use axum::extract::{DefaultBodyLimit, FromRef};
 use tokio::sync::broadcast;
 use tower_http::cors::CorsLayer;
 
+use crate::application::account_service::AccountService;
+use crate::application::avatar_service::{AvatarService, MAX_AVATAR_BYTES};
+use crate::application::comment_service::CommentService;
+use crate::application::community_service::CommunityService;
+use crate::application::domain_service::DomainService;
+use crate::application::follow_service::FollowService;
+use crate::application::link_preview_service::LinkPreviewService;
+use crate::application::mute_service::MuteService;
+use crate::application::moderation_service::{ModerationRepos, ModerationService};
+use crate::application::saved_service::SavedService;
+use crate::application::search_service::SearchService;
+use crate::application::tag_service::TagService;
+use crate::application::utils::spam::SpamScorer;
 use crate::application::vote_service::VoteService;
 use crate::domain::posts::Post;
 use crate::infrastructure::repositories::user_repo::PgUserRepository;
 use crate::infrastructure::repositories::vote_repo;
 use crate::AppContext;
-use crate::application::posts_service::PostService;
+use crate::application::posts_service::{PostRepos, PostService};
+use crate::application::revision_service::RevisionService;
 use crate::application::user_service::UserService;
 use crate::infrastructure::repositories::posts_repo::PgPostRepository;
+use crate::infrastructure::repositories::comment_repo::PgCommentRepository;
+use crate::infrastructure::repositories::tag_repo::PgTagRepository;
+use crate::infrastructure::repositories::community_repo::PgCommunityRepository;
+use crate::infrastructure::repositories::follow_repo::PgFollowRepository;
+use crate::infrastructure::repositories::saved_repo::PgSavedRepository;
+use crate::infrastructure::repositories::mute_repo::PgMuteRepository;
+use crate::infrastructure::repositories::report_repo::PgReportRepository;
+use crate::infrastructure::repositories::sanction_repo::PgSanctionRepository;
+use crate::infrastructure::repositories::mod_log_repo::PgModLogRepository;
+use crate::infrastructure::repositories::moderation_repo::PgModerationStore;
+use crate::infrastructure::repositories::spam_repo::PgSpamRepository;
+use crate::infrastructure::repositories::domain_repo::PgDomainRepository;
+use crate::infrastructure::repositories::revision_repo::PgRevisionRepository;
+use crate::config::ClientIpSource;
 
 
+mod account_handler;
 mod auth;
+mod avatar_handler;
+mod comment_handler;
+mod community_handler;
+mod domain_handler;
+mod error;
+mod follow_handler;
+mod moderation_handler;
+mod mute_handler;
+mod pagination;
 mod post_handler;
+mod preconditions;
+mod revision_handler;
+mod saved_handler;
+mod search_handler;
+mod tag_handler;
 mod user_handler;
 mod vote_handler;
 
@@ -27,20 +71,92 @@ pub struct ApiState {
     user_service: Arc<UserService>,
     post_service: Arc<PostService>,
     vote_service: Arc<VoteService>,
+    comment_service: Arc<CommentService>,
+    account_service: Arc<AccountService>,
+    avatar_service: Arc<AvatarService>,
+    search_service: Arc<SearchService>,
+    tag_service: Arc<TagService>,
+    community_service: Arc<CommunityService>,
+    follow_service: Arc<FollowService>,
+    saved_service: Arc<SavedService>,
+    mute_service: Arc<MuteService>,
+    moderation_service: Arc<ModerationService>,
+    domain_service: Arc<DomainService>,
+    revision_service: Arc<RevisionService>,
     jwt_keys: Arc<JwtKeys>,
+    client_ip_source: ClientIpSource,
     post_broadcaster: broadcast::Sender<Post>,
 }
 
 pub fn routes(ctx: AppContext) -> Router {
     let posts_repo: Arc<dyn crate::domain::posts::PostRepository> = Arc::new(PgPostRepository { pool: ctx.pool.clone() });
-    let post_service = Arc::new(PostService::new(posts_repo));
+    let (post_events, _) = broadcast::channel(1024);
+    let community_repo: Arc<dyn crate::domain::communities::CommunityRepository> = Arc::new(PgCommunityRepository { pool: ctx.pool.clone() });
+    let follow_repo: Arc<dyn crate::domain::follows::FollowRepository> = Arc::new(PgFollowRepository { pool: ctx.pool.clone() });
+    let spam_repo: Arc<dyn crate::domain::spam::SpamRepository> = Arc::new(PgSpamRepository { pool: ctx.pool.clone() });
+    let spam_scorer = Arc::new(SpamScorer::new(spam_repo, ctx.spam.hold_score));
+    let edit_grace = chrono::Duration::seconds(ctx.revisions.grace_secs);
+    let domain_repo: Arc<dyn crate::domain::domains::DomainRepository> = Arc::new(PgDomainRepository { pool: ctx.pool.clone() });
+    let post_service = Arc::new(PostService::new(PostRepos {
+        posts: posts_repo.clone(),
+        communities: community_repo.clone(),
+        follows: follow_repo.clone(),
+        domains: domain_repo.clone(),
+    }, ctx.content_filter.clone(), spam_scorer.clone(), edit_grace, post_events.clone()));
+    let domain_service = Arc::new(DomainService::new(domain_repo, posts_repo.clone()));
+    let follow_service = Arc::new(FollowService::new(follow_repo));
+    let saved_repo: Arc<dyn crate::domain::saved::SavedRepository> = Arc::new(PgSavedRepository { pool: ctx.pool.clone() });
+    let saved_service = Arc::new(SavedService::new(saved_repo, posts_repo.clone(), community_repo.clone()));
+
+    let mute_repo: Arc<dyn crate::domain::mutes::MuteRepository> = Arc::new(PgMuteRepository { pool: ctx.pool.clone() });
+    let mute_service = Arc::new(MuteService::new(mute_repo.clone(), posts_repo.clone(), community_repo.clone()));
+
+    if let Some(fetcher) = ctx.page_fetcher.clone() {
+        Arc::new(LinkPreviewService::new(fetcher, Arc::new(PgPostRepository { pool: ctx.pool.clone() }))).spawn_worker(post_events.subscribe());
+    }
+
+    let tag_repo: Arc<dyn crate::domain::tags::TagRepository> = Arc::new(PgTagRepository { pool: ctx.pool.clone() });
+    let tag_service = Arc::new(TagService::new(tag_repo.clone(), posts_repo.clone(), post_events.clone()));
+
+    let search_service = Arc::new(SearchService::new(ctx.search_index.clone(), posts_repo.clone(), mute_repo, tag_repo));
+    search_service.clone().spawn_indexer(post_events.subscribe());
     
     let jwt_keys = Arc::new(JwtKeys::new(&ctx.jwt_secret));
     let user_repo: Arc<dyn crate::domain::users::UserRepository> = Arc::new(PgUserRepository { pool: ctx.pool.clone(), jwt: (*jwt_keys).clone() });
-    let user_service = Arc::new(UserService::new(user_repo));
+    let sanction_repo: Arc<dyn crate::domain::sanctions::SanctionRepository> = Arc::new(PgSanctionRepository { pool: ctx.pool.clone() });
+    let user_service = Arc::new(UserService::new(user_repo.clone(), sanction_repo.clone()));
+    let mod_log_repo: Arc<dyn crate::domain::mod_log::ModLogRepository> = Arc::new(PgModLogRepository { pool: ctx.pool.clone() });
+    let moderation_store: Arc<dyn crate::domain::mod_log::ModerationStore> = Arc::new(PgModerationStore { pool: ctx.pool.clone() });
+    let community_service = Arc::new(CommunityService::new(community_repo.clone(), user_repo.clone(), moderation_store.clone()));
 
     let vote_repo: Arc<dyn crate::domain::votes::VoteRepository> = Arc::new(vote_repo::PgVoteRepository { pool: ctx.pool.clone() });
-    let vote_service = Arc::new(VoteService::new(vote_repo));
+    let vote_service = Arc::new(VoteService::new(vote_repo.clone()));
+
+    let comment_repo: Arc<dyn crate::domain::comments::CommentRepository> = Arc::new(PgCommentRepository { pool: ctx.pool.clone() });
+    let comment_service = Arc::new(CommentService::new(comment_repo.clone(), posts_repo.clone(), community_repo.clone(), ctx.content_filter.clone(), spam_scorer, edit_grace));
+
+    let report_repo: Arc<dyn crate::domain::reports::ReportRepository> = Arc::new(PgReportRepository { pool: ctx.pool.clone() });
+    let moderation_service = Arc::new(ModerationService::new(ModerationRepos {
+        reports: report_repo,
+        posts: posts_repo.clone(),
+        comments: comment_repo.clone(),
+        communities: community_repo.clone(),
+        users: user_repo.clone(),
+        sanctions: sanction_repo,
+        log: mod_log_repo,
+        store: moderation_store,
+    }, post_events.clone()));
+
+    let revision_repo: Arc<dyn crate::domain::revisions::RevisionRepository> = Arc::new(PgRevisionRepository { pool: ctx.pool.clone() });
+    let revision_service = Arc::new(RevisionService::new(revision_repo, posts_repo.clone(), comment_repo.clone(), community_repo.clone(), user_repo.clone(), ctx.revisions));
+
+    let avatar_service = Arc::new(AvatarService::new(user_repo.clone(), ctx.blob_store.clone()));
+
+    let account_service = Arc::new(AccountService::new(user_repo, posts_repo, comment_repo, vote_repo, ctx.blob_store.clone()));
+    account_service.clone().spawn_deletion_sweeper(Duration::from_secs(60 * 60));
+    if let Some(days) = ctx.archive_after_days {
+        post_service.clone().spawn_archiver(chrono::Duration::days(days), Duration::from_secs(60 * 60));
+    }
 
     let (tx, _) = broadcast::channel(100);
 
@@ -48,18 +164,115 @@ pub fn routes(ctx: AppContext) -> Router {
         user_service,
         post_service,
         vote_service,
+        comment_service,
+        account_service,
+        avatar_service,
+        search_service,
+        tag_service,
+        community_service,
+        follow_service,
+        saved_service,
+        mute_service,
+        moderation_service,
+        domain_service,
+        revision_service,
         jwt_keys,
+        client_ip_source: ctx.client_ip_source,
         post_broadcaster: tx,
     };
 
-    Router::new()
+    let router = Router::new()
         .route("/signup", post(user_handler::signup))
         .route("/login", post(user_handler::login))
         .route("/posts", get(post_handler::list_posts))
         .route("/posts", post(post_handler::create_post))
         .route("/posts/{id}", delete(post_handler::delete_post))
+        .route("/posts/{id}", get(post_handler::get_post))
         .route("/posts/{id}", put(post_handler::update_post))
+        .route("/posts/{id}", patch(post_handler::patch_post))
         .route("/posts/{id}/vote", post(post_handler::vote_post))
-        .route("/ws/posts", get(post_handler::ws_handler))
-        .with_state(state)
+        .route("/posts/{id}/save", post(saved_handler::save_post))
+        .route("/posts/{id}/save", delete(saved_handler::unsave_post))
+        .route("/posts/{id}/hide", post(mute_handler::hide_post))
+        .route("/posts/{id}/hide", delete(mute_handler::unhide_post))
+        .route("/posts/{id}/report", post(moderation_handler::report_post))
+        .route("/comments/{id}/report", post(moderation_handler::report_comment))
+        .route("/comments/{id}", delete(comment_handler::delete_comment))
+        .route("/comments/{id}", put(comment_handler::update_comment))
+        .route("/comments/{id}/revisions", get(revision_handler::comment_revisions))
+        .route("/posts/{id}/revisions", get(revision_handler::post_revisions))
+        .route("/posts/{id}/comments", get(comment_handler::list_comments))
+        .route("/posts/{id}/comments", post(comment_handler::create_comment))
+        .route("/users/{username}", get(user_handler::get_profile))
+        .route("/users/{username}/posts", get(user_handler::list_user_posts))
+        .route("/users/{username}/comments", get(user_handler::list_user_comments))
+        .route("/me", delete(account_handler::delete_me))
+        .route("/me/deletion/cancel", post(account_handler::cancel_deletion))
+        // leave room for the multipart framing around the image itself
+        .route("/me/avatar", put(avatar_handler::upload_avatar).layer(DefaultBodyLimit::max(MAX_AVATAR_BYTES + 64 * 1024)))
+        .route("/avatars/{user_id}/{version}/{file}", get(avatar_handler::get_avatar))
+        .route("/me/username", put(user_handler::change_username))
+        .route("/me/export", get(account_handler::export_me))
+        .route("/me/upvoted", get(post_handler::list_my_upvoted))
+        .route("/search", get(search_handler::search))
+        .route("/tags", get(tag_handler::autocomplete))
+        .route("/tags/{slug}/posts", get(tag_handler::list_tag_posts))
+        .route("/mod/tags/{slug}/aliases", post(tag_handler::add_alias))
+        .route("/mod/tags/aliases/{alias}", delete(tag_handler::remove_alias))
+        .route("/mod/tags/{slug}/merge", post(tag_handler::merge))
+        .route("/mod/reports", get(moderation_handler::list_queue))
+        .route("/mod/reports/{target_type}/{target_id}/resolve", post(moderation_handler::resolve))
+        .route("/mod/held", get(moderation_handler::list_held))
+        .route("/mod/posts/{id}/remove", post(moderation_handler::remove_post))
+        .route("/mod/posts/{id}/restore", post(moderation_handler::restore_post))
+        .route("/mod/posts/{id}/approve", post(moderation_handler::approve_post))
+        .route("/mod/posts/{id}/lock", post(moderation_handler::lock_post).delete(moderation_handler::unlock_post))
+        .route("/mod/posts/{id}/pin", post(moderation_handler::pin_post).delete(moderation_handler::unpin_post))
+        .route("/mod/comments/{id}/remove", post(moderation_handler::remove_comment))
+        .route("/mod/comments/{id}/restore", post(moderation_handler::restore_comment))
+        .route("/mod/comments/{id}/approve", post(moderation_handler::approve_comment))
+        .route("/mod/users/{username}/sanctions", post(moderation_handler::sanction_user).get(moderation_handler::list_sanctions))
+        .route("/mod/sanctions/{id}", delete(moderation_handler::revoke_sanction))
+        .route("/mod/evasion", get(moderation_handler::evasion_signals))
+        .route("/mod/posts/{id}", put(moderation_handler::edit_post))
+        .route("/mod/log", get(moderation_handler::list_log))
+        .route("/admin/search/reindex", post(search_handler::reindex))
+        .route("/admin/domains", get(domain_handler::list_rules))
+        .route("/admin/domains/{domain}", put(domain_handler::set_rule).delete(domain_handler::delete_rule))
+        .route("/domains/{domain}", get(domain_handler::domain_stats))
+        .route("/domains/{domain}/posts", get(domain_handler::list_domain_posts))
+        .route("/communities", post(community_handler::create_community))
+        .route("/c/{slug}", get(community_handler::get_community))
+        .route("/c/{slug}", patch(community_handler::update_community))
+        .route("/c/{slug}/posts", get(community_handler::list_community_posts))
+        .route("/c/{slug}/join", post(community_handler::join))
+        .route("/c/{slug}/join", delete(community_handler::leave))
+        .route("/c/{slug}/members/{username}", put(community_handler::set_member))
+        .route("/c/{slug}/members/{username}", delete(community_handler::remove_member))
+        .route("/me/communities", get(community_handler::list_my_communities))
+        .route("/users/{username}/follow", post(follow_handler::follow_user))
+        .route("/users/{username}/follow", delete(follow_handler::unfollow_user))
+        .route("/tags/{slug}/follow", post(follow_handler::follow_tag))
+        .route("/tags/{slug}/follow", delete(follow_handler::unfollow_tag))
+        .route("/me/following", get(follow_handler::list_following))
+        .route("/me/saved", get(saved_handler::list_saved))
+        .route("/me/collections", get(saved_handler::list_collections))
+        .route("/me/collections", post(saved_handler::create_collection))
+        .route("/me/collections/{id}", patch(saved_handler::rename_collection))
+        .route("/me/collections/{id}", delete(saved_handler::delete_collection))
+        .route("/me/hidden", get(mute_handler::list_hidden))
+        .route("/users/{username}/mute", post(mute_handler::mute_user))
+        .route("/users/{username}/mute", delete(mute_handler::unmute_user))
+        .route("/me/mutes", get(mute_handler::list_mutes))
+        .route("/me/mutes/domains", post(mute_handler::mute_domain))
+        .route("/me/mutes/domains/{domain}", delete(mute_handler::unmute_domain))
+        .route("/feed/home", get(post_handler::home_feed))
+        .route("/ws/posts", get(post_handler::ws_handler));
+
+    let router = if ctx.public_mod_log {
+        router.route("/modlog", get(moderation_handler::public_log))
+    } else {
+        router
+    };
+    router.with_state(state)
 }
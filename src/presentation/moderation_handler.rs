@@ -0,0 +1,322 @@
+use axum::{
+    extract::{Path, Query, State}, http::{header, HeaderMap, StatusCode}, response::IntoResponse, Json
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::application::moderation_service::{EditPostInput, ReportInput, ResolveInput, SanctionInput};
+use crate::domain::mod_log::{ModActionKind, ModLogFilter, ModTarget};
+use crate::domain::posts::Post;
+use crate::domain::reports::ReportTarget;
+use crate::domain::sanctions::{EvasionSignal, Sanction};
+use crate::presentation::{auth::AuthUser, error::app_error, pagination, preconditions, ApiState};
+
+#[derive(Deserialize)]
+pub struct QueueQuery {
+    /// Community slug; required unless the caller is a site moderator.
+    pub community: Option<String>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+pub async fn report_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    Json(payload): Json<ReportInput>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.report_post(user_id, post_id, payload).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn report_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+    Json(payload): Json<ReportInput>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.report_comment(user_id, comment_id, payload).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Open reports grouped per item; paginated on `(last_reported_at, target_id)`.
+pub async fn list_queue(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<QueueQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let items = state.moderation_service.queue(user_id, query.community.as_deref(), after, limit)
+        .await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_cursor(&items, limit, |i| (i.last_reported_at, i.target_id));
+    Ok(Json(serde_json::json!({ "items": items, "next_cursor": next_cursor })))
+}
+
+/// Posts and comments held by the content filter, oldest first; paginated on `(held_at, target_id)`.
+pub async fn list_held(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<QueueQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let items = state.moderation_service.held(user_id, query.community.as_deref(), after, limit)
+        .await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_cursor(&items, limit, |i| (i.held_at, i.target_id));
+    Ok(Json(serde_json::json!({ "items": items, "next_cursor": next_cursor })))
+}
+
+pub async fn resolve(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path((target_type, target_id)): Path<(ReportTarget, Uuid)>,
+    Json(payload): Json<ResolveInput>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let resolved = state.moderation_service.resolve(user_id, target_type, target_id, payload)
+        .await
+        .map_err(app_error)?;
+
+    Ok(Json(serde_json::json!({ "resolved": resolved })))
+}
+
+#[derive(Deserialize)]
+pub struct RemoveRequest {
+    #[serde(default)]
+    pub reason: String,
+}
+
+pub async fn remove_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    payload: Option<Json<RemoveRequest>>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let reason = payload.map(|Json(p)| p.reason).unwrap_or_default();
+    state.moderation_service.remove(user_id, ReportTarget::Post, post_id, &reason).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Undoes a moderator removal only; a post its author deleted stays deleted, and restoring
+/// a post that isn't removed is a conflict.
+pub async fn restore_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.restore(user_id, ReportTarget::Post, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn approve_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.approve(user_id, ReportTarget::Post, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn lock_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    payload: Option<Json<RemoveRequest>>,
+) -> Result<Json<Post>, (StatusCode, String)> {
+    let reason = payload.map(|Json(p)| p.reason).unwrap_or_default();
+    let post = state.moderation_service.set_locked(user_id, post_id, true, &reason).await.map_err(app_error)?;
+    Ok(Json(post))
+}
+
+pub async fn unlock_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<Json<Post>, (StatusCode, String)> {
+    let post = state.moderation_service.set_locked(user_id, post_id, false, "").await.map_err(app_error)?;
+    Ok(Json(post))
+}
+
+#[derive(Deserialize)]
+pub struct PinRequest {
+    pub position: Option<i32>,
+}
+
+pub async fn pin_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    payload: Option<Json<PinRequest>>,
+) -> Result<Json<Post>, (StatusCode, String)> {
+    let position = payload.and_then(|Json(p)| p.position);
+    let post = state.moderation_service.pin(user_id, post_id, position).await.map_err(app_error)?;
+    Ok(Json(post))
+}
+
+pub async fn unpin_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.unpin(user_id, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+    payload: Option<Json<RemoveRequest>>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let reason = payload.map(|Json(p)| p.reason).unwrap_or_default();
+    state.moderation_service.remove(user_id, ReportTarget::Comment, comment_id, &reason).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Same as `restore_post`, for comments.
+pub async fn restore_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.restore(user_id, ReportTarget::Comment, comment_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn approve_comment(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(comment_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.moderation_service.approve(user_id, ReportTarget::Comment, comment_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn sanction_user(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+    Json(payload): Json<SanctionInput>,
+) -> Result<(StatusCode, Json<Sanction>), (StatusCode, String)> {
+    let user = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    let sanction = state.moderation_service.sanction(user_id, user.id, payload).await.map_err(app_error)?;
+    Ok((StatusCode::CREATED, Json(sanction)))
+}
+
+/// Every sanction the user ever got, including expired and revoked ones.
+pub async fn list_sanctions(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+) -> Result<Json<Vec<Sanction>>, (StatusCode, String)> {
+    let user = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    let sanctions = state.moderation_service.sanctions(user_id, user.id).await.map_err(app_error)?;
+    Ok(Json(sanctions))
+}
+
+pub async fn revoke_sanction(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(sanction_id): Path<Uuid>,
+) -> Result<Json<Sanction>, (StatusCode, String)> {
+    let sanction = state.moderation_service.revoke_sanction(user_id, sanction_id).await.map_err(app_error)?;
+    Ok(Json(sanction))
+}
+
+#[derive(Deserialize)]
+pub struct EvasionQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn evasion_signals(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<EvasionQuery>,
+) -> Result<Json<Vec<EvasionSignal>>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let signals = state.moderation_service.evasion_signals(user_id, limit).await.map_err(app_error)?;
+    Ok(Json(signals))
+}
+
+pub async fn edit_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<EditPostInput>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let expected_version = preconditions::if_match(&headers)?;
+    let post = state.moderation_service.edit_post(user_id, post_id, payload, expected_version).await.map_err(app_error)?;
+    Ok(([(header::ETAG, preconditions::etag(post.version))], Json(post)))
+}
+
+#[derive(Deserialize)]
+pub struct LogQuery {
+    /// Community slug; required unless the caller is a site moderator.
+    pub community: Option<String>,
+    /// Username of the moderator who acted.
+    pub actor: Option<String>,
+    pub action: Option<ModActionKind>,
+    pub target_type: Option<ModTarget>,
+    pub target_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+/// The moderation log, newest first; paginated on `(created_at, id)`.
+pub async fn list_log(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<LogQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let actor_id = match &query.actor {
+        Some(username) => Some(state.user_service.find_by_username(username).await.map_err(app_error)?.id),
+        None => None,
+    };
+    let filter = ModLogFilter {
+        actor_id,
+        action: query.action,
+        target_type: query.target_type,
+        target_id: query.target_id,
+        ..Default::default()
+    };
+
+    let entries = state.moderation_service.log(user_id, query.community.as_deref(), filter, after, limit)
+        .await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_cursor(&entries, limit, |e| (e.created_at, e.id));
+    Ok(Json(serde_json::json!({ "entries": entries, "next_cursor": next_cursor })))
+}
+
+/// Same as `list_log` minus the `actor` filter, with moderator names left out.
+pub async fn public_log(
+    State(state): State<ApiState>,
+    Query(query): Query<LogQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let filter = ModLogFilter {
+        action: query.action,
+        target_type: query.target_type,
+        target_id: query.target_id,
+        ..Default::default()
+    };
+
+    let entries = state.moderation_service.public_log(query.community.as_deref(), filter, after, limit)
+        .await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_cursor(&entries, limit, |e| (e.created_at, e.id));
+    Ok(Json(serde_json::json!({ "entries": entries, "next_cursor": next_cursor })))
+}
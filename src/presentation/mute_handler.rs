@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Path, Query, State}, http::StatusCode, Json
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::application::mute_service::{MuteDomainInput, Mutes};
+use crate::presentation::{auth::AuthUser, error::app_error, pagination, ApiState};
+
+#[derive(Deserialize)]
+pub struct HiddenQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+pub async fn hide_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.mute_service.hide_post(user_id, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unhide_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.mute_service.unhide_post(user_id, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Paginated on `(hidden_at, id)`.
+pub async fn list_hidden(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<HiddenQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let page = state.mute_service.hidden(user_id, after, limit).await.map_err(app_error)?;
+
+    let next_cursor = page.next.map(|(hidden_at, id)| pagination::encode_cursor(hidden_at, id));
+    Ok(Json(serde_json::json!({ "posts": page.posts, "next_cursor": next_cursor })))
+}
+
+pub async fn mute_user(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let muted = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.mute_service.mute_user(user_id, muted.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unmute_user(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(username): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let muted = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    state.mute_service.unmute_user(user_id, muted.id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn mute_domain(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<MuteDomainInput>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    let domain = state.mute_service.mute_domain(user_id, payload).await.map_err(app_error)?;
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "domain": domain }))))
+}
+
+pub async fn unmute_domain(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(domain): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.mute_service.unmute_domain(user_id, &domain).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_mutes(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<Json<Mutes>, (StatusCode, String)> {
+    let mutes = state.mute_service.mutes(user_id).await.map_err(app_error)?;
+    Ok(Json(mutes))
+}
@@ -0,0 +1,75 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::posts::{FeedCursor, FeedSort};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+type Cursor = (DateTime<Utc>, Uuid);
+
+/// Cursors are opaque to clients: `<created_at micros>_<id>` of the last item on the page.
+pub fn encode_cursor(created_at: DateTime<Utc>, id: Uuid) -> String {
+    format!("{}_{}", created_at.timestamp_micros(), id)
+}
+
+pub fn parse_cursor(raw: &Option<String>) -> Result<Option<Cursor>, (StatusCode, String)> {
+    let Some(raw) = raw.as_deref().filter(|s| !s.is_empty()) else { return Ok(None) };
+    decode_cursor(raw).map(Some).ok_or_else(invalid_cursor)
+}
+
+/// Cursors of `Top` feeds lead with the score: `<score>_<created_at micros>_<id>`.
+pub fn encode_feed_cursor(cursor: FeedCursor) -> String {
+    match cursor.score {
+        Some(score) => format!("{score}_{}", encode_cursor(cursor.created_at, cursor.id)),
+        None => encode_cursor(cursor.created_at, cursor.id),
+    }
+}
+
+/// Fails on a cursor from a feed sorted the other way.
+pub fn parse_feed_cursor(raw: &Option<String>, sort: FeedSort) -> Result<Option<FeedCursor>, (StatusCode, String)> {
+    let Some(raw) = raw.as_deref().filter(|s| !s.is_empty()) else { return Ok(None) };
+    let (score, rest) = match sort {
+        FeedSort::New => (None, raw),
+        FeedSort::Top => {
+            let (score, rest) = raw.split_once('_').ok_or_else(invalid_cursor)?;
+            (Some(score.parse().map_err(|_| invalid_cursor())?), rest)
+        }
+    };
+    let (created_at, id) = decode_cursor(rest).ok_or_else(invalid_cursor)?;
+    Ok(Some(FeedCursor { score, created_at, id }))
+}
+
+fn decode_cursor(raw: &str) -> Option<Cursor> {
+    let (micros, id) = raw.split_once('_')?;
+    let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+    Some((created_at, Uuid::parse_str(id).ok()?))
+}
+
+fn invalid_cursor() -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, "Invalid cursor".to_string())
+}
+
+pub fn page_limit(limit: Option<i64>) -> Result<i64, (StatusCode, String)> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    if limit <= 0 {
+        return Err((StatusCode::BAD_REQUEST, "Limit must be greater than 0".to_string()));
+    }
+    Ok(limit.min(MAX_LIMIT))
+}
+
+/// A full page means there may be more; hand back the cursor of its last item.
+pub fn next_cursor<T>(items: &[T], limit: i64, key: impl Fn(&T) -> Cursor) -> Option<String> {
+    if (items.len() as i64) < limit { return None; }
+    items.last().map(|item| {
+        let (created_at, id) = key(item);
+        encode_cursor(created_at, id)
+    })
+}
+
+/// As `next_cursor`, for feeds that can be sorted by score.
+pub fn next_feed_cursor<T>(items: &[T], limit: i64, key: impl Fn(&T) -> FeedCursor) -> Option<String> {
+    if (items.len() as i64) < limit { return None; }
+    items.last().map(|item| encode_feed_cursor(key(item)))
+}
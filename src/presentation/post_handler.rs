@@ -1,17 +1,25 @@
 use axum::{
-    extract::{ws::WebSocket, Path, Query, State, WebSocketUpgrade}, http::StatusCode, response::IntoResponse, Json
+    extract::{ws::WebSocket, Path, Query, State, WebSocketUpgrade}, http::{header, HeaderMap, StatusCode}, response::IntoResponse, Json
 };
 use serde::Deserialize;
 use uuid::Uuid;
-use crate::application::posts_service::CreatePostInput;
-use crate::domain::posts::Post;
-use crate::presentation::{auth::AuthUser, ApiState};
+use crate::application::posts_service::{CreatePostInput, PatchPostInput, Submission, UpdatePostInput};
+use crate::domain::posts::{FeedCursor, FeedSort, Post};
+use crate::presentation::{auth::AuthUser, error::app_error, pagination, preconditions, ApiState};
 
 
 #[derive(Deserialize)]
 pub struct ListPostQuery {
     pub limit: Option<i64>,
     pub cursor: Option<String>,
+    #[serde(default)]
+    pub sort: FeedSort,
+}
+
+#[derive(Deserialize)]
+pub struct HomeFeedQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -20,68 +28,134 @@ pub struct VoteRequest {
 }
 
 
+/// 201 with the new post, or 200 with the existing one when the link was already submitted recently.
 #[axum::debug_handler]
 pub async fn create_post(
     State(state): State<ApiState>,
     AuthUser { user_id }: AuthUser,
     Json(_payload): Json<CreatePostInput>,
 ) -> Result<(StatusCode, Json<Post>), (StatusCode, String)> {
-    let post = state.post_service.create(user_id, _payload)
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let post = match state.post_service.create(user_id, _payload).await.map_err(app_error)? {
+        Submission::Created(post) => post,
+        Submission::Duplicate(existing) => return Ok((StatusCode::OK, Json(existing))),
+    };
 
     // Send the enw post to all WebSocket listeners
     // We ignore the result, as it's okay if there are no active listeners
-    let _ = state.post_broadcaster.send(post.clone());
+    if post.is_listed() && !post.author_shadowbanned {
+        let _ = state.post_broadcaster.send(post.clone());
+    }
 
     Ok((StatusCode::CREATED, Json(post)))
 }
 
+/// The post with its version as the ETag; edits send it back in `If-Match`.
+pub async fn get_post(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(post_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let view = state.post_service.get(viewer.map(|v| v.user_id), post_id).await.map_err(app_error)?;
+    Ok(([(header::ETAG, preconditions::etag(view.post.version))], Json(view)))
+}
+
+/// Replaces every field but `tags`, which are kept when left out. Needs `If-Match`: 428 without it, 412 when the post changed since.
 #[axum::debug_handler]
 pub async fn update_post(
     State(state): State<ApiState>, 
+    AuthUser { user_id }: AuthUser,
     Path(post_id): Path<Uuid>,
-    Json(_payload): Json<CreatePostInput>
-) -> Result<(StatusCode, Json<Post>), (StatusCode, String)> {
-    let post = state.post_service.update(
-        post_id, 
-        &_payload.title, 
-        &_payload.short_description, 
-        &_payload.url, 
-        &_payload.body
-    )
+    headers: HeaderMap,
+    Json(payload): Json<UpdatePostInput>
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let expected_version = preconditions::if_match(&headers)?;
+    let post = state.post_service.update(user_id, post_id, payload, expected_version)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        .map_err(app_error)?;
     
-    Ok((StatusCode::OK, Json(post)))
+    Ok(([(header::ETAG, preconditions::etag(post.version))], Json(post)))
+}
+
+/// As `update_post`, but only the fields sent change.
+pub async fn patch_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<PatchPostInput>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let expected_version = preconditions::if_match(&headers)?;
+    let post = state.post_service.patch(user_id, post_id, payload, expected_version).await.map_err(app_error)?;
+    Ok(([(header::ETAG, preconditions::etag(post.version))], Json(post)))
 }
 
 #[axum::debug_handler]
 pub async fn delete_post(
     State(state): State<ApiState>, 
+    AuthUser { user_id }: AuthUser,
     Path(post_id): Path<Uuid>
 ) -> Result<StatusCode, (StatusCode, String)> {
-    state.post_service.delete(post_id)
+    state.post_service.delete(user_id, post_id)
     .await
-    .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    .map_err(app_error)?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 pub async fn list_posts(
     State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
     Query(query): Query<ListPostQuery>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
 
-    let limit = query.limit.unwrap_or(20);
-    if limit <= 0 {
-        return Err((StatusCode::BAD_REQUEST, "Limit must be greater than 0".to_string()));
-    }
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let viewer = viewer.map(|v| v.user_id);
+    let posts = match query.sort {
+        FeedSort::New => state.post_service.list_new(viewer, after.map(|a| (a.created_at, a.id)), limit).await,
+        FeedSort::Top => state.post_service.list_top(viewer, after, limit).await,
+    }.map_err(app_error)?;
+
+    // pinned posts ride on top of the first page; the cursor only follows the rest
+    let pinned = posts.iter().take_while(|p| p.pinned_at.is_some()).count();
+    let next_cursor = pagination::next_feed_cursor(&posts[pinned..], limit, |p| FeedCursor::at(query.sort, p.score.into(), p.created_at, p.id));
+    let posts = state.post_service.annotate(viewer, posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "posts": posts, "next_cursor": next_cursor })))
+}
+
+/// Hot-ranked, so the cursor carries `hot_at` rather than `created_at`.
+pub async fn home_feed(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<HomeFeedQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let feed = state.post_service.home_feed(user_id, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_cursor(&feed.posts, limit, |p| (p.hot_at, p.id));
+    let posts = state.post_service.annotate(Some(user_id), feed.posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "posts": posts, "personalized": feed.personalized, "next_cursor": next_cursor })))
+}
+
+/// Posts the authenticated user has upvoted; only ever visible to that user.
+pub async fn list_my_upvoted(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
 
-    let posts = state.post_service.list_new(None, limit).await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let posts = state.post_service.list_upvoted_by(user_id, after, limit).await
+        .map_err(app_error)?;
 
-    Ok(Json(serde_json::json!({ "posts": posts })))
+    let next_cursor = pagination::next_cursor(&posts, limit, |p| (p.created_at, p.id));
+    let posts = state.post_service.annotate(Some(user_id), posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "posts": posts, "next_cursor": next_cursor })))
 }
 
 
@@ -94,7 +168,7 @@ pub async fn vote_post(
     state.post_service.vote_post(user_id, post_id, payload.value)
     .await
     .map(|_| StatusCode::OK)
-    .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+    .map_err(app_error)
 }
 
 #[axum::debug_handler]
@@ -0,0 +1,71 @@
+use axum::http::{header::IF_MATCH, HeaderMap, StatusCode};
+
+/// A post's ETag is its version, quoted.
+pub fn etag(version: i32) -> String {
+    format!("\"{version}\"")
+}
+
+/// The version an edit expects to replace, from its `If-Match` header. Edits must send one; `*` matches
+/// any version. Weak tags never match, as `If-Match` compares strongly.
+pub fn if_match(headers: &HeaderMap) -> Result<Option<i32>, (StatusCode, String)> {
+    let Some(value) = headers.get(IF_MATCH) else {
+        return Err((StatusCode::PRECONDITION_REQUIRED, "If-Match header required".to_string()));
+    };
+    let invalid = || (StatusCode::BAD_REQUEST, "If-Match must be a single ETag or *".to_string());
+
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    if value.starts_with("W/") {
+        return Err((StatusCode::PRECONDITION_FAILED, "the post was edited since you loaded it".to_string()));
+    }
+    let version = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).ok_or_else(invalid)?;
+    version.parse().map(Some).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header::IF_MATCH, HeaderMap, HeaderValue, StatusCode};
+
+    use super::{etag, if_match};
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn status(headers: &HeaderMap) -> StatusCode {
+        if_match(headers).unwrap_err().0
+    }
+
+    #[test]
+    fn etag_is_the_quoted_version() {
+        assert_eq!(etag(3), "\"3\"");
+        assert_eq!(if_match(&headers(&etag(3))), Ok(Some(3)));
+    }
+
+    #[test]
+    fn star_matches_any_version() {
+        assert_eq!(if_match(&headers("*")), Ok(None));
+        assert_eq!(if_match(&headers(" * ")), Ok(None));
+    }
+
+    #[test]
+    fn edits_need_the_header() {
+        assert_eq!(status(&HeaderMap::new()), StatusCode::PRECONDITION_REQUIRED);
+    }
+
+    #[test]
+    fn weak_tags_never_match() {
+        assert_eq!(status(&headers("W/\"3\"")), StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[test]
+    fn malformed_tags_are_rejected() {
+        for value in ["3", "\"three\"", "\"3", "\"1\", \"2\"", ""] {
+            assert_eq!(status(&headers(value)), StatusCode::BAD_REQUEST, "{value:?}");
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::{Path, State}, http::StatusCode, Json
+};
+use uuid::Uuid;
+
+use crate::presentation::{auth::AuthUser, error::app_error, ApiState};
+
+pub async fn post_revisions(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(post_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let versions = state.revision_service.post_history(viewer.map(|v| v.user_id), post_id).await
+        .map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "post_id": post_id, "versions": versions })))
+}
+
+pub async fn comment_revisions(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(comment_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let versions = state.revision_service.comment_history(viewer.map(|v| v.user_id), comment_id).await
+        .map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "comment_id": comment_id, "versions": versions })))
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Path, Query, State}, http::StatusCode, Json
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::application::saved_service::CollectionInput;
+use crate::domain::saved::SavedCollection;
+use crate::presentation::{auth::AuthUser, error::app_error, pagination, ApiState};
+
+/// The body is optional; without one the post is saved outside any collection.
+#[derive(Deserialize, Default)]
+pub struct SaveRequest {
+    pub collection_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct SavedQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub collection_id: Option<Uuid>,
+}
+
+pub async fn save_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+    payload: Option<Json<SaveRequest>>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let Json(payload) = payload.unwrap_or_default();
+    state.saved_service.save(user_id, post_id, payload.collection_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unsave_post(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(post_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.saved_service.unsave(user_id, post_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Paginated on `(saved_at, id)` rather than the post's own `created_at`.
+pub async fn list_saved(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<SavedQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_cursor(&query.cursor)?;
+
+    let page = state.saved_service.list(user_id, query.collection_id, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = page.next.map(|(saved_at, id)| pagination::encode_cursor(saved_at, id));
+    Ok(Json(serde_json::json!({ "posts": page.posts, "next_cursor": next_cursor })))
+}
+
+pub async fn list_collections(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<Json<Vec<SavedCollection>>, (StatusCode, String)> {
+    let collections = state.saved_service.collections(user_id).await.map_err(app_error)?;
+    Ok(Json(collections))
+}
+
+pub async fn create_collection(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<CollectionInput>,
+) -> Result<(StatusCode, Json<SavedCollection>), (StatusCode, String)> {
+    let collection = state.saved_service.create_collection(user_id, payload).await.map_err(app_error)?;
+    Ok((StatusCode::CREATED, Json(collection)))
+}
+
+pub async fn rename_collection(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(collection_id): Path<Uuid>,
+    Json(payload): Json<CollectionInput>,
+) -> Result<Json<SavedCollection>, (StatusCode, String)> {
+    let collection = state.saved_service.rename_collection(user_id, collection_id, payload).await.map_err(app_error)?;
+    Ok(Json(collection))
+}
+
+pub async fn delete_collection(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Path(collection_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.saved_service.delete_collection(user_id, collection_id).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Query, State}, http::StatusCode, Json
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::domain::search::SearchQuery;
+use crate::presentation::{auth::{AdminUser, AuthUser}, error::app_error, pagination, ApiState};
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    pub author: Option<String>,
+    pub domain: Option<String>,
+    pub tag: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub async fn search(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(params.limit)?;
+    let offset = params.offset.unwrap_or(0);
+
+    let query = SearchQuery {
+        text: params.q,
+        author: params.author,
+        domain: params.domain,
+        tag: params.tag,
+        from: params.from,
+        to: params.to,
+        limit,
+        offset,
+        exclude: Default::default(),
+        viewer: None,
+    };
+    let results = state.search_service.search(viewer.map(|v| v.user_id), query).await.map_err(app_error)?;
+
+    let next_offset = (results.hits.len() as i64 == limit).then_some(offset + limit);
+    Ok(Json(serde_json::json!({ "results": results.hits, "facets": results.facets, "next_offset": next_offset })))
+}
+
+/// Rebuilds the search index inside the running server, which holds the only writer an embedded index allows.
+pub async fn reindex(
+    State(state): State<ApiState>,
+    AdminUser { .. }: AdminUser,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let indexed = state.search_service.reindex().await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "indexed": indexed })))
+}
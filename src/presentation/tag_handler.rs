@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, Query, State}, http::StatusCode, Json
+};
+use serde::Deserialize;
+
+use crate::domain::posts::FeedCursor;
+use crate::domain::tags::Tag;
+use crate::presentation::post_handler::ListPostQuery;
+use crate::presentation::{auth::{AuthUser, ModeratorUser}, error::app_error, pagination, ApiState};
+
+#[derive(Deserialize)]
+pub struct AutocompleteQuery {
+    pub prefix: String,
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct AliasRequest {
+    pub alias: String,
+}
+
+#[derive(Deserialize)]
+pub struct MergeRequest {
+    pub into: String,
+}
+
+pub async fn autocomplete(
+    State(state): State<ApiState>,
+    Query(query): Query<AutocompleteQuery>,
+) -> Result<Json<Vec<Tag>>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let tags = state.tag_service.autocomplete(&query.prefix, limit).await.map_err(app_error)?;
+    Ok(Json(tags))
+}
+
+pub async fn list_tag_posts(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(slug): Path<String>,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let tag = state.tag_service.find(&slug).await.map_err(app_error)?;
+    let posts = state.post_service.list_by_tag(tag.id, viewer.as_ref().map(|v| v.user_id), query.sort, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_feed_cursor(&posts, limit, |p| FeedCursor::at(query.sort, p.score.into(), p.created_at, p.id));
+    let posts = state.post_service.annotate(viewer.map(|v| v.user_id), posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "tag": tag, "posts": posts, "next_cursor": next_cursor })))
+}
+
+pub async fn add_alias(
+    State(state): State<ApiState>,
+    ModeratorUser { user_id }: ModeratorUser,
+    Path(slug): Path<String>,
+    Json(payload): Json<AliasRequest>,
+) -> Result<(StatusCode, Json<Tag>), (StatusCode, String)> {
+    let tag = state.tag_service.add_alias(user_id, &slug, &payload.alias).await.map_err(app_error)?;
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+pub async fn remove_alias(
+    State(state): State<ApiState>,
+    ModeratorUser { .. }: ModeratorUser,
+    Path(alias): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.tag_service.remove_alias(&alias).await.map_err(app_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Folds the tag in the path into `into`; the old slug keeps working as an alias.
+pub async fn merge(
+    State(state): State<ApiState>,
+    ModeratorUser { user_id }: ModeratorUser,
+    Path(slug): Path<String>,
+    Json(payload): Json<MergeRequest>,
+) -> Result<Json<Tag>, (StatusCode, String)> {
+    let tag = state.tag_service.merge(user_id, &slug, &payload.into).await.map_err(app_error)?;
+    Ok(Json(tag))
+}
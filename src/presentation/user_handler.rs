@@ -1,7 +1,10 @@
-use axum::{Json, http::StatusCode, extract::State};
+use axum::{Json, http::StatusCode, extract::{Path, Query, State}, response::{IntoResponse, Redirect, Response}};
 use serde::Deserialize;
-use crate::presentation::ApiState;
-use crate::domain::users::UserPublic;
+use crate::application::error::AppError;
+use crate::application::user_service::ProfileLookup;
+use crate::presentation::{auth::{AuthUser, ClientIp}, error::app_error, pagination, post_handler::ListPostQuery, ApiState};
+use crate::domain::posts::FeedCursor;
+use crate::domain::users::{UserProfile, UserPublic};
 
 #[derive(Deserialize)]
 pub struct SignupRequest {
@@ -17,22 +20,41 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Deserialize)]
+pub struct ChangeUsernameRequest {
+    pub username: String,
+}
+
 pub async fn signup(
     State(state): State<ApiState>,
+    ClientIp(ip): ClientIp,
     Json(payload): Json<SignupRequest>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    state.user_service.signup(payload.username, payload.email, payload.avatar, payload.password)
+    let user = state.user_service.signup(payload.username, payload.email, payload.avatar, payload.password)
         .await
-        .map(|_| StatusCode::CREATED)
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    if let Some(ip) = ip {
+        state.user_service.record_ip(user.id, ip).await;
+    }
+    Ok(StatusCode::CREATED)
 }
 
 pub async fn login(
     State(state): State<ApiState>,
+    ClientIp(ip): ClientIp,
     Json(payload): Json<LoginRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let user = state.user_service.authenticate(&payload.email, &payload.password).await
-        .map_err(|e| (StatusCode::UNAUTHORIZED, e.to_string()))?;
+        .map_err(|e| match e {
+            // a banned account gets told so rather than a generic bad-credentials error
+            AppError::Forbidden(_) => app_error(e),
+            e => (StatusCode::UNAUTHORIZED, e.to_string()),
+        })?;
+
+    if let Some(ip) = ip {
+        state.user_service.record_ip(user.id, ip).await;
+    }
 
     let token = state.jwt_keys.issue(user.id, 7)
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to issue token".to_string()))?;
@@ -44,3 +66,62 @@ pub async fn login(
         "user": user_public,
     })))
 }
+
+pub async fn list_user_posts(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(username): Path<String>,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let user = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    let posts = state.post_service.list_by_author(user.id, viewer.as_ref().map(|v| v.user_id), query.sort, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_feed_cursor(&posts, limit, |p| FeedCursor::at(query.sort, p.score.into(), p.created_at, p.id));
+    let posts = state.post_service.annotate(viewer.map(|v| v.user_id), posts).await.map_err(app_error)?;
+    Ok(Json(serde_json::json!({ "posts": posts, "next_cursor": next_cursor })))
+}
+
+pub async fn list_user_comments(
+    State(state): State<ApiState>,
+    viewer: Option<AuthUser>,
+    Path(username): Path<String>,
+    Query(query): Query<ListPostQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let limit = pagination::page_limit(query.limit)?;
+    let after = pagination::parse_feed_cursor(&query.cursor, query.sort)?;
+
+    let user = state.user_service.find_by_username(&username).await.map_err(app_error)?;
+    let comments = state.comment_service.list_by_author(user.id, viewer.map(|v| v.user_id), query.sort, after, limit).await
+        .map_err(app_error)?;
+
+    let next_cursor = pagination::next_feed_cursor(&comments, limit, |c| FeedCursor::at(query.sort, c.reply_count, c.created_at, c.id));
+    Ok(Json(serde_json::json!({ "comments": comments, "next_cursor": next_cursor })))
+}
+
+pub async fn get_profile(
+    State(state): State<ApiState>,
+    Path(username): Path<String>,
+) -> Result<Response, (StatusCode, String)> {
+    match state.user_service.resolve_profile(&username).await.map_err(app_error)? {
+        ProfileLookup::Found(user) => Ok(Json(UserProfile::from(user)).into_response()),
+        // temporary: the old name is released for anyone once its reservation runs out
+        ProfileLookup::Renamed(new_username) => Ok(Redirect::temporary(&format!("/api/users/{new_username}")).into_response()),
+    }
+}
+
+pub async fn change_username(
+    State(state): State<ApiState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<ChangeUsernameRequest>,
+) -> Result<Json<UserPublic>, (StatusCode, String)> {
+    let user = state.user_service.change_username(user_id, &payload.username)
+        .await
+        .map_err(app_error)?;
+    state.search_service.reindex_author(user_id).await.map_err(app_error)?;
+
+    Ok(Json(user.into()))
+}